@@ -83,8 +83,16 @@ export function CreateFactory({
       // Use .rpc() instead of .transaction() + sendTransaction
       const tx = await program.methods
         .createFactory()
-        .accounts({
+        .accountsPartial({
+          programConfig: PublicKey.findProgramAddressSync(
+            [Buffer.from("program_config")],
+            program.programId,
+          )[0],
           factory,
+          protocolStats: PublicKey.findProgramAddressSync(
+            [Buffer.from("protocol_stats")],
+            program.programId,
+          )[0],
           authority: publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -78,12 +78,12 @@ export function CreateToken() {
       );
       console.log("Token Data:", tokenData.toBase58());
 
-      // --- WHITELIST PDA ---
+      // --- WHITELIST PDA (page keyed by the address's first byte) ---
       const [whitelist] = PublicKey.findProgramAddressSync(
         [
           Buffer.from("whitelist"),
-          publicKey.toBuffer(),
-          new anchor.BN(factoryAcc.tokenCount).toBuffer("le", 8),
+          mint.publicKey.toBuffer(),
+          Buffer.from([defaultPk.toBuffer()[0]]),
         ],
         program.programId,
       );
@@ -107,6 +107,17 @@ export function CreateToken() {
       );
       console.log("Metadata:", metadata.toBase58());
 
+      // --- TREASURY PDAs ---
+      const [treasury] = PublicKey.findProgramAddressSync(
+        [Buffer.from("treasury"), mint.publicKey.toBuffer()],
+        program.programId,
+      );
+      const [treasuryAuthority] = PublicKey.findProgramAddressSync(
+        [Buffer.from("treasury_authority"), mint.publicKey.toBuffer()],
+        program.programId,
+      );
+      console.log("Treasury:", treasury.toBase58());
+
       // --- ASSOCIATED TOKEN ACCOUNT (Token-2022) ---
       const ata = getAssociatedTokenAddressSync(
         mint.publicKey,
@@ -129,20 +140,35 @@ export function CreateToken() {
       // --- SEND TX ---
       const tx = await program.methods
         .createToken(supplyBN, name, symbol, uri, defaultPk)
-        .accounts({
+        .accountsPartial({
+          programConfig: PublicKey.findProgramAddressSync(
+            [Buffer.from("program_config")],
+            program.programId,
+          )[0],
+          protocolStats: PublicKey.findProgramAddressSync(
+            [Buffer.from("protocol_stats")],
+            program.programId,
+          )[0],
           factory,
           tokenData,
           whitelist,
           mint: mint.publicKey,
           mintAuthorityPda,
           ata,
+          treasury,
+          treasuryAuthority,
           metadata,
           authority: publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
           tokenProgram: TOKEN_2022_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-          tokenMetadataProgram: METADATA_PROGRAM_ID,
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          sysvarInstructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          collectionMint: null,
+          collectionMetadata: null,
+          collectionMasterEdition: null,
+          tokenMetadataProgram: METADATA_PROGRAM_ID,
+          preparedConfig: null,
         })
         .signers(signers)
         .rpc();
@@ -38,12 +38,14 @@ export function ManageToken({
     setError(null);
     setWhitelist([]);
     try {
-      const whitelistAccount = await program.account.whitelist.fetch(
-        token.whitelist
+      const pages = await program.account.whitelist.fetchMultiple(
+        Array.from({ length: 256 }, (_, page) => whitelistPage(page))
       );
       setWhitelist(
-        (whitelistAccount.addresses as PublicKey[]).map((addr) =>
-          addr.toBase58()
+        pages.flatMap((page) =>
+          page
+            ? (page.addresses as PublicKey[]).map((addr) => addr.toBase58())
+            : []
         )
       );
     } catch (e: any) {
@@ -160,13 +162,30 @@ export function ManageToken({
     }
   };
 
+  const pda = (...seeds: (Buffer | Uint8Array)[]) =>
+    PublicKey.findProgramAddressSync(seeds, program!.programId)[0];
+
+  // Whitelist pages are keyed by the first byte of each address
+  const whitelistPage = (page: number) =>
+    pda(Buffer.from("whitelist"), token.mint.toBuffer(), Buffer.from([page]));
+
+  const groupByPage = (input: string) => {
+    const pages = new Map<number, PublicKey[]>();
+    for (const entry of input.split(",")) {
+      const address = new PublicKey(entry.trim());
+      const page = address.toBuffer()[0];
+      pages.set(page, [...(pages.get(page) ?? []), address]);
+    }
+    return pages;
+  };
+
   const mintTokens = () =>
     handleTransaction(async () => {
       const toAccount = await getOrCreateAssociatedTokenAccount(publicKey!);
       
-      const [mintAuthorityPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("mint_authority"), publicKey!.toBuffer()],
-        program.programId
+      const mintAuthorityPda = pda(
+        Buffer.from("mint_authority"),
+        token.creator.toBuffer()
       );
 
       console.log("Minting to ATA:", toAccount.toBase58());
@@ -174,17 +193,19 @@ export function ManageToken({
       console.log("Mint Authority PDA:", mintAuthorityPda.toBase58());
       
       return program.methods
-        .mintTokens(
-          new anchor.BN(token.token_count),
-          new anchor.BN(amountToMint)
-        )
-        .accounts({
+        .mintTokens(new anchor.BN(amountToMint), null)
+        .accountsPartial({
+          programConfig: pda(Buffer.from("program_config")),
           tokenData: token.tokenDataAddress,
           mint: token.mint,
           to: toAccount,
           mintAuthorityPda: mintAuthorityPda,
+          idempotencyLog: null,
+          protocolStats: pda(Buffer.from("protocol_stats")),
           authority: publicKey!,
           tokenProgram: TOKEN_2022_PROGRAM_ID,
+          role: null,
+          allowance: null,
         })
         .transaction();
     });
@@ -194,11 +215,9 @@ export function ManageToken({
       const fromAccount = await getOrCreateAssociatedTokenAccount(publicKey!);
 
       return program.methods
-        .burnTokens(
-          new anchor.BN(token.token_count),
-          new anchor.BN(amountToBurn)
-        )
-        .accounts({
+        .burnTokens(new anchor.BN(amountToBurn))
+        .accountsPartial({
+          programConfig: pda(Buffer.from("program_config")),
           tokenData: token.tokenDataAddress,
           mint: token.mint,
           from: fromAccount,
@@ -210,71 +229,115 @@ export function ManageToken({
 
   const addToWhitelist = () =>
     handleTransaction(async () => {
-      return program.methods
-        .addToWhitelist(
-          new anchor.BN(token.token_count),
-          addressesToWhitelist
-            .split(",")
-            .map((address) => new PublicKey(address.trim()))
-        )
-        .accounts({
-          tokenData: token.tokenDataAddress,
-          whitelist: token.whitelist,
-          authority: publicKey!,
-          systemProgram: anchor.web3.SystemProgram.programId,
-        })
-        .transaction();
+      const transaction = new Transaction();
+      for (const [page, addresses] of groupByPage(addressesToWhitelist)) {
+        const whitelist = whitelistPage(page);
+        if ((await connection.getAccountInfo(whitelist)) === null) {
+          transaction.add(
+            await program.methods
+              .createWhitelistPage(page)
+              .accountsPartial({
+                programConfig: pda(Buffer.from("program_config")),
+                tokenData: token.tokenDataAddress,
+                whitelist,
+                authority: publicKey!,
+                role: null,
+              })
+              .instruction()
+          );
+        }
+        transaction.add(
+          await program.methods
+            .addToWhitelist(page, addresses)
+            .accountsPartial({
+              programConfig: pda(Buffer.from("program_config")),
+              tokenData: token.tokenDataAddress,
+              whitelist,
+              authority: publicKey!,
+              role: null,
+            })
+            .instruction()
+        );
+      }
+      return transaction;
     });
 
   const removeFromWhitelist = () =>
+    handleTransaction(async () => {
+      const transaction = new Transaction();
+      for (const [page, addresses] of groupByPage(addressesToRemove)) {
+        transaction.add(
+          await program.methods
+            .removeFromWhitelist(page, addresses)
+            .accountsPartial({
+              programConfig: pda(Buffer.from("program_config")),
+              tokenData: token.tokenDataAddress,
+              whitelist: whitelistPage(page),
+              authority: publicKey!,
+              role: null,
+            })
+            .instruction()
+        );
+      }
+      return transaction;
+    });
+
+  const pauseMinting = () =>
     handleTransaction(async () => {
       return program.methods
-        .removeFromWhitelist(
-          new anchor.BN(token.token_count),
-          addressesToRemove
-            .split(",")
-            .map((address) => new PublicKey(address.trim()))
-        )
-        .accounts({
+        .pauseMinting(null)
+        .accountsPartial({
           tokenData: token.tokenDataAddress,
-          whitelist: token.whitelist,
+          idempotencyLog: null,
+          hookManifest: null,
+          programConfig: pda(Buffer.from("program_config")),
+          timelock: pda(Buffer.from("timelock"), token.mint.toBuffer()),
           authority: publicKey!,
+          role: null,
         })
         .transaction();
     });
 
-  const pauseMinting = () =>
+  const pauseToken = () =>
     handleTransaction(async () => {
       return program.methods
-        .pauseMinting(new anchor.BN(token.token_count))
-        .accounts({
+        .pauseToken(null)
+        .accountsPartial({
           tokenData: token.tokenDataAddress,
+          idempotencyLog: null,
+          hookManifest: null,
+          programConfig: pda(Buffer.from("program_config")),
+          timelock: pda(Buffer.from("timelock"), token.mint.toBuffer()),
           authority: publicKey!,
+          role: null,
         })
         .transaction();
     });
 
-  const pauseToken = () =>
+  // Hand-over is two-step: the current authority proposes a successor,
+  // which then signs `acceptAuthority` from its own wallet
+  const proposeAuthority = () =>
     handleTransaction(async () => {
       return program.methods
-        .pauseToken(new anchor.BN(token.token_count))
-        .accounts({
+        .proposeAuthority(new PublicKey(newAuthority))
+        .accountsPartial({
           tokenData: token.tokenDataAddress,
+          hookManifest: null,
+          programConfig: pda(Buffer.from("program_config")),
+          timelock: pda(Buffer.from("timelock"), token.mint.toBuffer()),
           authority: publicKey!,
         })
         .transaction();
     });
 
-  const transferAuthority = () =>
+  const acceptAuthority = () =>
     handleTransaction(async () => {
       return program.methods
-        .transferAuthority(
-          new anchor.BN(token.token_count),
-          new PublicKey(newAuthority)
-        )
-        .accounts({
+        .acceptAuthority()
+        .accountsPartial({
+          programConfig: pda(Buffer.from("program_config")),
           tokenData: token.tokenDataAddress,
-          authority: publicKey!,
+          newAuthority: publicKey!,
         })
         .transaction();
     });
@@ -424,12 +487,22 @@ export function ManageToken({
             className="mt-1 block w-full px-3 py-2 bg-gray-800 border border-gray-700 rounded-md text-white shadow-sm focus:outline-none focus:ring-indigo-500 focus:border-indigo-500 sm:text-sm"
           />
           <button
-            onClick={transferAuthority}
+            onClick={proposeAuthority}
             disabled={isLoading || !program || !publicKey}
             className="mt-2 w-full inline-flex justify-center py-2 px-4 border border-transparent shadow-sm text-sm font-medium rounded-md text-white bg-red-800 hover:bg-red-900 disabled:opacity-50 disabled:cursor-not-allowed"
           >
-            Transfer Authority (Careful!)
+            Propose New Authority
           </button>
+          <p className="text-xs text-gray-400 mt-1">The new authority must accept from its own wallet</p>
+          {token.pendingAuthority && publicKey?.equals(token.pendingAuthority) && (
+            <button
+              onClick={acceptAuthority}
+              disabled={isLoading || !program}
+              className="mt-2 w-full inline-flex justify-center py-2 px-4 border border-transparent shadow-sm text-sm font-medium rounded-md text-white bg-red-800 hover:bg-red-900 disabled:opacity-50 disabled:cursor-not-allowed"
+            >
+              Accept Authority
+            </button>
+          )}
         </div>
       </div>
 
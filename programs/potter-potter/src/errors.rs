@@ -28,4 +28,219 @@ pub enum ErrorCode {
 
     #[msg("Unauthorized")]
     Unauthorized,
+
+    #[msg("Swap pair must use two different tokens")]
+    InvalidSwapPair,
+
+    #[msg("Swap between these tokens is disabled")]
+    SwapDisabled,
+
+    #[msg("Address does not belong to this whitelist page")]
+    WrongWhitelistPage,
+
+    #[msg("Import chunk is out of order")]
+    ChunkOutOfOrder,
+
+    #[msg("Import is already being committed")]
+    ImportAlreadyCommitting,
+
+    #[msg("Idempotency key must not be all zeroes")]
+    InvalidIdempotencyKey,
+
+    #[msg("Idempotency log account is required when a key is provided")]
+    IdempotencyLogMissing,
+
+    #[msg("This config area is permanently locked")]
+    ConfigLocked,
+
+    #[msg("Invalid config lock mask")]
+    InvalidLockMask,
+
+    #[msg("This instruction version is deprecated")]
+    InstructionDeprecated,
+
+    #[msg("Address is not suspended")]
+    AddressNotSuspended,
+
+    #[msg("This instruction is only available on devnet deployments")]
+    DevnetOnly,
+
+    #[msg("Token account whitelisting is disabled for this token")]
+    TokenAccountWhitelistingDisabled,
+
+    #[msg("Remove whitelisted token accounts before disabling")]
+    TokenAccountEntriesExist,
+
+    #[msg("Factory collection is already initialized")]
+    CollectionAlreadyInitialized,
+
+    #[msg("Collection accounts do not match the factory collection")]
+    InvalidCollection,
+
+    #[msg("The metadata update timelock has not elapsed")]
+    TimelockNotElapsed,
+
+    #[msg("Metadata update delay can only be increased")]
+    MetadataDelayDecrease,
+
+    #[msg("Website is too long (max 100 characters)")]
+    WebsiteTooLong,
+
+    #[msg("Invalid decimals for this token")]
+    InvalidDecimals,
+
+    #[msg("Signer is not the pending authority")]
+    NotPendingAuthority,
+
+    #[msg(
+        "Whitelists can only be mirrored from a token with the same authority that owns its pages"
+    )]
+    InvalidWhitelistSource,
+
+    #[msg("Mint would exceed the token's max supply")]
+    SupplyCapExceeded,
+
+    #[msg("No whitelist Merkle root is set for this token")]
+    WhitelistRootNotSet,
+
+    #[msg("Invalid whitelist Merkle proof")]
+    InvalidWhitelistProof,
+
+    #[msg("Transfer amount is below the token's minimum")]
+    TransferBelowMinimum,
+
+    #[msg("Metadata changes must be queued while an update delay is set")]
+    MetadataDelayActive,
+
+    #[msg("Supply is locked; the mint authority has been revoked")]
+    SupplyLocked,
+
+    #[msg("Transfer fee basis points exceed 100%")]
+    InvalidTransferFee,
+
+    #[msg("Clawback was not enabled when this token was created")]
+    ClawbackDisabled,
+
+    #[msg("Token account owner is still whitelisted")]
+    AddressStillWhitelisted,
+
+    #[msg("Transfer permit must have a cap and a future expiry")]
+    InvalidTransferPermit,
+
+    #[msg("Account has not been dormant for the policy's dormancy period")]
+    AccountNotDormant,
+
+    #[msg("The holder was active after the dormancy notice was filed")]
+    DormancyNoticeContested,
+
+    #[msg("Locale must be 1 to 16 characters")]
+    InvalidLocale,
+
+    #[msg("Burning is paused along with transfers")]
+    BurningPaused,
+
+    #[msg("Parameters do not match the prepared token config")]
+    PreparedConfigMismatch,
+
+    #[msg("Burn the whole supply before closing the token")]
+    SupplyNotZero,
+
+    #[msg("Mint exceeds the minter's remaining allowance")]
+    MinterAllowanceExceeded,
+
+    #[msg("Transfer would put the destination wallet above the holding cap")]
+    WalletCapExceeded,
+
+    #[msg("This feature has been disabled program-wide")]
+    FeatureDisabled,
+
+    #[msg("Feature mask contains unknown bits")]
+    InvalidFeatureMask,
+
+    #[msg("Transfer would pass the sender's daily limit, or no transfer window is open")]
+    DailyTransferLimitExceeded,
+
+    #[msg("Transfer window has not expired yet")]
+    TransferWindowActive,
+
+    #[msg("Account is not the recipient's opt-out address")]
+    InvalidOptOutAccount,
+
+    #[msg("Trading has not started for this token")]
+    TradingNotStarted,
+
+    #[msg("Limits must be nonzero and within the program's hard ceilings")]
+    InvalidLimits,
+
+    #[msg("Parameter exceeds the deployment's configured limit")]
+    LimitExceeded,
+
+    #[msg("Vesting cliff must fall within a nonzero duration")]
+    InvalidVestingSchedule,
+
+    #[msg("Nothing has vested since the last claim")]
+    NothingToClaim,
+
+    #[msg("Fee share must be at most 10000 basis points")]
+    InvalidFeeShare,
+
+    #[msg("Insurance fund and vault must be passed together")]
+    InvalidInsuranceFund,
+
+    #[msg("Payout claim is still within its veto period")]
+    PayoutVetoPeriodActive,
+
+    #[msg("This action must be queued through the timelock")]
+    ActionTimelocked,
+
+    #[msg("Timelock delay must be positive and can't be shortened")]
+    TimelockDelayDecrease,
+
+    #[msg("Transfer authority isn't a custodian within its approved cap and expiry")]
+    CustodianLimitExceeded,
+
+    #[msg("Source or destination owner is blacklisted")]
+    AddressBlacklisted,
+
+    #[msg("Earlier resume phases have to be re-enabled first")]
+    ResumeOutOfOrder,
+
+    #[msg("Whitelist entry expiry must be in the future")]
+    InvalidWhitelistExpiry,
+
+    #[msg("Whitelist entry has not expired")]
+    WhitelistEntryNotExpired,
+
+    #[msg("Token has no NFT gate collection")]
+    NftGateNotConfigured,
+
+    #[msg("NFT is not held or not in the token's verified gate collection")]
+    InvalidNftPass,
+
+    #[msg("NFT pass owner still holds the NFT")]
+    NftPassStillValid,
+
+    #[msg("Program-owned vaults still hold tokens that need the mint authority to pay out")]
+    VaultsNotEmpty,
+
+    #[msg("Account isn't in a layout this program can migrate")]
+    InvalidAccountLayout,
+
+    #[msg("Other tokens still mirror this token's whitelist")]
+    WhitelistMirrored,
+
+    #[msg("Vault name is too long (max 32 bytes)")]
+    VaultNameTooLong,
+
+    #[msg("Arithmetic overflow")]
+    Overflow,
+
+    #[msg("Accounts of the token authority or a program address can't be refrozen")]
+    RefreezeExempt,
+
+    #[msg("Feature mask is held by a program pause until every phase is resumed")]
+    ProgramPaused,
+
+    #[msg("Program is not paused")]
+    ProgramNotPaused,
 }
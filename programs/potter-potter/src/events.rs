@@ -0,0 +1,573 @@
+use crate::instructions::AdminAction;
+use crate::state::{Limits, ResumePhase, Role};
+use anchor_lang::prelude::*;
+
+// Emitted through self-CPI (`emit_cpi!`) so indexers can read them from
+// inner instructions even when RPC providers truncate logs. Amounts are
+// whole tokens, with the base-unit amount and its decimal rendering
+// alongside for consumers that don't have the mint cached.
+
+#[event]
+pub struct TokenCreated {
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub token_index: u64,
+    pub total_supply: u64,
+    pub decimals: u8,
+}
+
+#[event]
+pub struct TokensMinted {
+    pub mint: Pubkey,
+    pub to: Pubkey,
+    pub amount: u64,
+    pub raw_amount: u64,
+    pub ui_amount_string: String,
+    pub total_supply: u64,
+}
+
+#[event]
+pub struct TokensBurned {
+    pub mint: Pubkey,
+    pub from: Pubkey,
+    pub amount: u64,
+    pub raw_amount: u64,
+    pub ui_amount_string: String,
+    pub total_supply: u64,
+}
+
+/// Off-chain ledger reference for a mint, for treasury reconciliation.
+#[event]
+pub struct MintReferenceRecorded {
+    pub mint: Pubkey,
+    pub to: Pubkey,
+    pub amount: u64,
+    pub reference: [u8; 32],
+}
+
+/// Context for a burn, e.g. a fiat redemption ID hashed into `reference_hash`.
+#[event]
+pub struct BurnReasonRecorded {
+    pub mint: Pubkey,
+    pub from: Pubkey,
+    pub amount: u64,
+    pub reason_code: u16,
+    pub reference_hash: [u8; 32],
+}
+
+#[event]
+pub struct TokensSwapped {
+    pub user: Pubkey,
+    pub from_mint: Pubkey,
+    pub to_mint: Pubkey,
+    pub amount_in: u64,
+    pub raw_amount_in: u64,
+    pub ui_amount_in_string: String,
+    pub amount_out: u64,
+    pub raw_amount_out: u64,
+    pub ui_amount_out_string: String,
+}
+
+#[event]
+pub struct AdminActionApplied {
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub action: AdminAction,
+}
+
+#[event]
+pub struct TimelockUpdated {
+    pub mint: Pubkey,
+    pub min_delay: i64,
+}
+
+#[event]
+pub struct ActionQueued {
+    pub mint: Pubkey,
+    pub action_id: u64,
+    pub action: AdminAction,
+    pub ready_at: i64,
+}
+
+#[event]
+pub struct ActionCancelled {
+    pub mint: Pubkey,
+    pub action_id: u64,
+}
+
+#[event]
+pub struct AuthorityAccepted {
+    pub mint: Pubkey,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct RoleGranted {
+    pub mint: Pubkey,
+    pub member: Pubkey,
+    pub role: Role,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub mint: Pubkey,
+    pub member: Pubkey,
+    pub role: Role,
+}
+
+/// `cap` is in base units.
+#[event]
+pub struct CustodianApproved {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub custodian: Pubkey,
+    pub cap: u64,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct CustodianRevoked {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub custodian: Pubkey,
+}
+
+#[event]
+pub struct DistributionOptOutUpdated {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub opted_out: bool,
+}
+
+/// `allowance` is zero when revoked.
+#[event]
+pub struct MinterAllowanceUpdated {
+    pub mint: Pubkey,
+    pub minter: Pubkey,
+    pub allowance: u64,
+}
+
+/// Emitted by the transfer hook with `emit!`, as a self-CPI from inside
+/// Token-2022's CPI would eat into the caller's depth budget.
+#[event]
+pub struct HookTelemetry {
+    pub mint: Pubkey,
+    /// Hook checks run before the transfer was decided
+    pub rules_evaluated: u8,
+    /// Whitelist page entries compared while looking up the destination
+    pub lookup_iterations: u32,
+}
+
+#[event]
+pub struct RulesDigestExported {
+    pub mint: Pubkey,
+    pub version: u8,
+    pub hash: [u8; 32],
+}
+
+#[event]
+pub struct BlacklistUpdated {
+    pub mint: Pubkey,
+    pub address: Pubkey,
+    pub blacklisted: bool,
+}
+
+#[event]
+pub struct WhitelistUpdated {
+    pub mint: Pubkey,
+    pub change: WhitelistChange,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum WhitelistChange {
+    AddressesAdded {
+        page: u8,
+        addresses: Vec<Pubkey>,
+    },
+    AddressesRemoved {
+        page: u8,
+        addresses: Vec<Pubkey>,
+    },
+    AddressSuspended {
+        address: Pubkey,
+    },
+    AddressRestored {
+        address: Pubkey,
+    },
+    EntryAdded {
+        owner: Pubkey,
+    },
+    EntryRemoved {
+        owner: Pubkey,
+    },
+    EntryExpired {
+        owner: Pubkey,
+    },
+    NftPassRegistered {
+        owner: Pubkey,
+        nft_mint: Pubkey,
+    },
+    NftPassRevoked {
+        owner: Pubkey,
+    },
+    TokenAccountAdded {
+        token_account: Pubkey,
+    },
+    TokenAccountRemoved {
+        token_account: Pubkey,
+    },
+    Mirrored {
+        whitelist_mint: Pubkey,
+    },
+    CredentialIssued {
+        owner: Pubkey,
+    },
+    CredentialRevoked {
+        owner: Pubkey,
+    },
+    PermitIssued {
+        owner: Pubkey,
+        amount_cap: u64,
+        expires_at: i64,
+    },
+    PermitRevoked {
+        owner: Pubkey,
+    },
+}
+
+/// Emitted by the transfer hook with `emit!` just before it fails the
+/// transfer, so the reason survives in the failed transaction's logs.
+#[event]
+pub struct TransferRejected {
+    pub mint: Pubkey,
+    pub destination_owner: Pubkey,
+    pub reason: TransferRejection,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum TransferRejection {
+    TransfersHalted,
+    TokenPaused,
+    Blacklisted,
+    TradingNotStarted,
+    BelowMinimum,
+    AboveWalletCap,
+    DailyLimitExceeded,
+    CustodianNotApproved,
+    NotWhitelisted,
+    SourceNotWhitelisted,
+}
+
+/// Emitted by the hook with `emit!` when transfer velocity pauses the token.
+#[event]
+pub struct CircuitBreakerTripped {
+    pub mint: Pubkey,
+    pub window_start_slot: u64,
+    pub transfers: u32,
+    pub volume: u64,
+}
+
+#[event]
+pub struct CircuitBreakerUpdated {
+    pub mint: Pubkey,
+    pub window_slots: u64,
+    pub max_transfers: u32,
+    pub max_volume: u64,
+}
+
+#[event]
+pub struct SupplyLocked {
+    pub mint: Pubkey,
+    pub total_supply: u64,
+}
+
+/// `reissued_to` is set when the clawed-back tokens were minted to another
+/// account rather than destroyed.
+#[event]
+pub struct TokensClawedBack {
+    pub mint: Pubkey,
+    pub from: Pubkey,
+    pub amount: u64,
+    pub raw_amount: u64,
+    pub reissued_to: Option<Pubkey>,
+}
+
+#[event]
+pub struct EscheatmentPolicyUpdated {
+    pub mint: Pubkey,
+    pub custody: Pubkey,
+    pub dormancy_period: i64,
+    pub waiting_period: i64,
+}
+
+/// The public notice a dormant balance recovery has to wait out.
+#[event]
+pub struct DormancyNoticeFiled {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+    pub last_activity_at: i64,
+    pub recoverable_at: i64,
+    pub raw_amount: u64,
+}
+
+#[event]
+pub struct DormantBalanceRecovered {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+    pub custody: Pubkey,
+    pub raw_amount: u64,
+}
+
+#[event]
+pub struct InterestRateUpdated {
+    pub mint: Pubkey,
+    pub rate_bps: i16,
+}
+
+#[event]
+pub struct TokenClosed {
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub mint_closed: bool,
+}
+
+#[event]
+pub struct TransferFeeUpdated {
+    pub mint: Pubkey,
+    pub basis_points: u16,
+    pub maximum_fee: u64,
+}
+
+#[event]
+pub struct WithheldFeesWithdrawn {
+    pub mint: Pubkey,
+    pub treasury: Pubkey,
+    pub raw_amount: u64,
+    /// Part of `raw_amount` kept in the insurance vault
+    pub insurance_share: u64,
+}
+
+/// Treasury movements are a burn and a mint of the same amount, so supply
+/// doesn't change.
+#[event]
+pub struct TreasuryDeposited {
+    pub mint: Pubkey,
+    pub from: Pubkey,
+    pub amount: u64,
+    pub raw_amount: u64,
+}
+
+#[event]
+pub struct TreasuryWithdrawn {
+    pub mint: Pubkey,
+    pub to: Pubkey,
+    pub amount: u64,
+    pub raw_amount: u64,
+}
+
+/// Named vault movements are a burn and a mint of the same amount, as the
+/// treasury's are.
+#[event]
+pub struct VaultCreated {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub name: String,
+}
+
+#[event]
+pub struct VaultDeposited {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub from: Pubkey,
+    pub amount: u64,
+    pub raw_amount: u64,
+}
+
+#[event]
+pub struct VaultWithdrawn {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub to: Pubkey,
+    pub amount: u64,
+    pub raw_amount: u64,
+}
+
+#[event]
+pub struct BuybackBurn {
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub raw_amount: u64,
+    pub supply_before: u64,
+    pub supply_after: u64,
+}
+
+/// Amounts are whole tokens.
+#[event]
+pub struct VestingCreated {
+    pub mint: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub start_at: i64,
+    pub cliff: i64,
+    pub duration: i64,
+}
+
+#[event]
+pub struct VestingClaimed {
+    pub mint: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    /// Total released so far, this claim included
+    pub released: u64,
+}
+
+#[event]
+pub struct VestingRevoked {
+    pub mint: Pubkey,
+    pub beneficiary: Pubkey,
+    /// What stays claimable by the beneficiary
+    pub vested: u64,
+    /// Unvested tokens sent back to the authority
+    pub returned: u64,
+}
+
+#[event]
+pub struct InsuranceFundCreated {
+    pub mint: Pubkey,
+    pub guardian: Pubkey,
+    pub fee_share_bps: u16,
+    pub veto_period: i64,
+}
+
+#[event]
+pub struct PayoutClaimQueued {
+    pub mint: Pubkey,
+    pub claim_id: u64,
+    pub recipient: Pubkey,
+    pub raw_amount: u64,
+    pub evidence_hash: [u8; 32],
+    pub ready_at: i64,
+}
+
+#[event]
+pub struct PayoutClaimVetoed {
+    pub mint: Pubkey,
+    pub claim_id: u64,
+    pub guardian: Pubkey,
+}
+
+#[event]
+pub struct PayoutClaimPaid {
+    pub mint: Pubkey,
+    pub claim_id: u64,
+    pub recipient: Pubkey,
+    pub raw_amount: u64,
+    pub evidence_hash: [u8; 32],
+}
+
+#[event]
+pub struct SwapRateUpdated {
+    pub from_mint: Pubkey,
+    pub to_mint: Pubkey,
+    pub rate_numerator: u64,
+    pub rate_denominator: u64,
+}
+
+#[event]
+pub struct FactoryCreated {
+    pub factory: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct FactoryCollectionInitialized {
+    pub factory: Pubkey,
+    pub collection_mint: Pubkey,
+}
+
+#[event]
+pub struct FactoryWhitelistUpdated {
+    pub factory: Pubkey,
+    pub change: WhitelistChange,
+}
+
+#[event]
+pub struct ProgramConfigUpdated {
+    pub authority: Pubkey,
+    pub v1_disabled: bool,
+    pub disabled_features: u16,
+}
+
+#[event]
+pub struct ProgramPaused {
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct ProgramResumed {
+    pub authority: Pubkey,
+    pub phase: ResumePhase,
+    pub disabled_features: u16,
+}
+
+#[event]
+pub struct StateCheckpointed {
+    pub sequence: u64,
+    pub slot: u64,
+    pub token_count: u32,
+    pub state_hash: [u8; 32],
+}
+
+#[event]
+pub struct LimitsUpdated {
+    pub authority: Pubkey,
+    pub limits: Limits,
+}
+
+#[event]
+pub struct MetadataUpdateQueued {
+    pub mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub ready_at: i64,
+}
+
+#[event]
+pub struct MetadataUpdateApplied {
+    pub mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+#[event]
+pub struct MetadataUpdateCancelled {
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct LocalizedMetadataUpdated {
+    pub mint: Pubkey,
+    pub locale: String,
+    pub name: String,
+    pub uri: String,
+}
+
+#[event]
+pub struct TokenProfileUpdated {
+    pub mint: Pubkey,
+    pub description_hash: Option<[u8; 32]>,
+    pub website: String,
+    pub socials_hash: Option<[u8; 32]>,
+}
+
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub old_space: u64,
+    pub new_space: u64,
+}
@@ -0,0 +1,714 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{
+        transfer_hook::TransferHookAccount, BaseStateWithExtensionsMut, PodStateWithExtensionsMut,
+    },
+    pod::PodAccount,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+pub fn handle_transfer_hook(mut ctx: Context<TransferHook>, amount: u64) -> Result<()> {
+    check_is_transferring(&ctx)?;
+    let now = unix_timestamp(ctx.remaining_accounts)?;
+    let token_data = &ctx.accounts.token_data;
+    let destination_owner = ctx.accounts.destination_token.owner;
+
+    if ctx.accounts.program_config.disabled_features & FEATURE_TRANSFERS != 0 {
+        emit!(TransferRejected {
+            mint: token_data.mint,
+            destination_owner,
+            reason: TransferRejection::TransfersHalted,
+        });
+        return err!(ErrorCode::FeatureDisabled);
+    }
+
+    if token_data.is_paused {
+        emit!(TransferRejected {
+            mint: token_data.mint,
+            destination_owner,
+            reason: TransferRejection::TokenPaused,
+        });
+        return err!(ErrorCode::TokenPaused);
+    }
+
+    // Checked before anything else can let the transfer through
+    let source_owner = ctx.accounts.source_token.owner;
+    if entry_exists(&ctx.accounts.blacklist_entry)?
+        || entry_exists(&ctx.accounts.source_blacklist_entry)?
+    {
+        emit!(TransferRejected {
+            mint: token_data.mint,
+            destination_owner,
+            reason: TransferRejection::Blacklisted,
+        });
+        return err!(ErrorCode::AddressBlacklisted);
+    }
+
+    // Anyone signing in place of the owner has to be a custodian within
+    // the bounds it was approved for
+    if ctx.accounts.owner.key() != source_owner
+        && !record_custodian_transfer(&ctx.accounts.custodian_approval, amount, now)?
+    {
+        emit!(TransferRejected {
+            mint: token_data.mint,
+            destination_owner,
+            reason: TransferRejection::CustodianNotApproved,
+        });
+        return err!(ErrorCode::CustodianLimitExceeded);
+    }
+
+    // Before launch only the authority can move tokens, e.g. to seed a pool
+    if now < token_data.trading_starts_at && source_owner != token_data.authority {
+        emit!(TransferRejected {
+            mint: token_data.mint,
+            destination_owner,
+            reason: TransferRejection::TradingNotStarted,
+        });
+        return err!(ErrorCode::TradingNotStarted);
+    }
+
+    // Balances are already updated here, so an empty source means the
+    // holder moved everything out and should be able to close the account
+    if amount < token_data.min_transfer_amount && ctx.accounts.source_token.amount > 0 {
+        emit!(TransferRejected {
+            mint: token_data.mint,
+            destination_owner,
+            reason: TransferRejection::BelowMinimum,
+        });
+        return err!(ErrorCode::TransferBelowMinimum);
+    }
+
+    if token_data.exceeds_wallet_cap(&destination_owner, ctx.accounts.destination_token.amount) {
+        emit!(TransferRejected {
+            mint: token_data.mint,
+            destination_owner,
+            reason: TransferRejection::AboveWalletCap,
+        });
+        return err!(ErrorCode::WalletCapExceeded);
+    }
+
+    let mut stats = LookupStats {
+        enabled: token_data.debug_telemetry,
+        // The program pause, token pause, blacklist, launch, minimum and
+        // holding cap checks always run
+        rules_evaluated: 6,
+        lookup_iterations: 0,
+    };
+    let policy = token_data.whitelist_policy;
+
+    let mut rejection = None;
+    if policy.checks_destination()
+        && !ctx
+            .accounts
+            .owner_whitelisted(TransferEnd::Destination, now, &mut stats)?
+        && !consume_permit(&ctx.accounts.permit, amount, now, &mut stats)?
+    {
+        rejection = Some(TransferRejection::NotWhitelisted);
+    } else if policy.checks_source()
+        && !ctx
+            .accounts
+            .owner_whitelisted(TransferEnd::Source, now, &mut stats)?
+    {
+        rejection = Some(TransferRejection::SourceNotWhitelisted);
+    }
+
+    if stats.enabled {
+        emit!(HookTelemetry {
+            mint: token_data.mint,
+            rules_evaluated: stats.rules_evaluated,
+            lookup_iterations: stats.lookup_iterations,
+        });
+    }
+    if let Some(reason) = rejection {
+        emit!(TransferRejected {
+            mint: token_data.mint,
+            destination_owner,
+            reason,
+        });
+        return err!(ErrorCode::AddressNotWhitelisted);
+    }
+
+    if token_data.daily_transfer_limit > 0
+        && source_owner != token_data.authority
+        && !record_window_volume(
+            &ctx.accounts.source_transfer_window,
+            token_data.daily_transfer_limit,
+            amount,
+            now,
+        )?
+    {
+        emit!(TransferRejected {
+            mint: token_data.mint,
+            destination_owner,
+            reason: TransferRejection::DailyLimitExceeded,
+        });
+        return err!(ErrorCode::DailyTransferLimitExceeded);
+    }
+
+    contest_dormancy_notice(&ctx.accounts.source_dormancy_notice)?;
+    record_activity(&ctx.accounts.source_activity, now)?;
+    trip_circuit_breaker(&mut ctx, amount)?;
+
+    msg!(
+        "Transfer hook passed: {} -> {} under {:?}",
+        source_owner,
+        destination_owner,
+        policy
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct TransferHook<'info> {
+    #[account(token::mint = mint)]
+    pub source_token: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(token::mint = mint)]
+    pub destination_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: transfer authority, the source owner or an approved custodian
+    pub owner: UncheckedAccount<'info>,
+
+    /// CHECK: ExtraAccountMetaList Account
+    #[account(seeds = [b"extra-account-metas", mint.key().as_ref()], bump)]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    /// Writable so the circuit breaker can pause the token
+    #[account(mut, has_one = mint)]
+    pub token_data: Account<'info, TokenData>,
+
+    /// CHECK: Whitelist page passed via extra account metas, may not exist yet
+    #[account(
+        seeds = [b"whitelist", token_data.whitelist_mint.as_ref(), &[whitelist_page_of(&destination_token.owner)]],
+        bump
+    )]
+    pub whitelist: UncheckedAccount<'info>,
+
+    /// CHECK: WhitelistedTokenAccount entry for the destination, may not exist
+    #[account(
+        seeds = [b"whitelisted_account", mint.key().as_ref(), destination_token.key().as_ref()],
+        bump
+    )]
+    pub token_account_entry: UncheckedAccount<'info>,
+
+    /// CHECK: WhitelistEntry for the destination owner, may not exist
+    #[account(
+        seeds = [b"wl", token_data.whitelist_mint.as_ref(), destination_token.owner.as_ref()],
+        bump
+    )]
+    pub whitelist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: Membership credential for the destination owner, may not exist
+    #[account(
+        seeds = [b"credential", token_data.whitelist_mint.as_ref(), destination_token.owner.as_ref()],
+        bump
+    )]
+    pub credential: UncheckedAccount<'info>,
+
+    /// CHECK: Whitelist page for the source owner, may not exist yet
+    #[account(
+        seeds = [b"whitelist", token_data.whitelist_mint.as_ref(), &[whitelist_page_of(&source_token.owner)]],
+        bump
+    )]
+    pub source_whitelist: UncheckedAccount<'info>,
+
+    /// CHECK: WhitelistedTokenAccount entry for the source, may not exist
+    #[account(
+        seeds = [b"whitelisted_account", mint.key().as_ref(), source_token.key().as_ref()],
+        bump
+    )]
+    pub source_token_account_entry: UncheckedAccount<'info>,
+
+    /// CHECK: WhitelistEntry for the source owner, may not exist
+    #[account(
+        seeds = [b"wl", token_data.whitelist_mint.as_ref(), source_token.owner.as_ref()],
+        bump
+    )]
+    pub source_whitelist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: Membership credential for the source owner, may not exist
+    #[account(
+        seeds = [b"credential", token_data.whitelist_mint.as_ref(), source_token.owner.as_ref()],
+        bump
+    )]
+    pub source_credential: UncheckedAccount<'info>,
+
+    /// CHECK: TransferPermit for the destination owner, may not exist
+    #[account(
+        mut,
+        seeds = [b"permit", mint.key().as_ref(), destination_token.owner.as_ref()],
+        bump
+    )]
+    pub permit: UncheckedAccount<'info>,
+
+    /// CHECK: DormancyNotice for the source token account, may not exist
+    #[account(
+        mut,
+        seeds = [b"dormancy", source_token.key().as_ref()],
+        bump
+    )]
+    pub source_dormancy_notice: UncheckedAccount<'info>,
+
+    /// CHECK: TransferWindow for the source owner, may not exist
+    #[account(
+        mut,
+        seeds = [b"transfer_window", mint.key().as_ref(), source_token.owner.as_ref()],
+        bump
+    )]
+    pub source_transfer_window: UncheckedAccount<'info>,
+
+    /// CHECK: VelocityTracker for the mint, may not exist
+    #[account(
+        mut,
+        seeds = [b"velocity", mint.key().as_ref()],
+        bump
+    )]
+    pub velocity_tracker: UncheckedAccount<'info>,
+
+    /// CHECK: CustodianApproval for the transfer authority, may not exist
+    #[account(
+        mut,
+        seeds = [b"custodian", source_token.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub custodian_approval: UncheckedAccount<'info>,
+
+    /// CHECK: BlacklistEntry for the destination owner, may not exist
+    #[account(
+        seeds = [b"blacklist", mint.key().as_ref(), destination_token.owner.as_ref()],
+        bump
+    )]
+    pub blacklist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: BlacklistEntry for the source owner, may not exist
+    #[account(
+        seeds = [b"blacklist", mint.key().as_ref(), source_token.owner.as_ref()],
+        bump
+    )]
+    pub source_blacklist_entry: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    /// CHECK: Factory WhitelistEntry for the destination owner, may not exist
+    #[account(
+        seeds = [b"factory_wl", token_data.factory.as_ref(), destination_token.owner.as_ref()],
+        bump
+    )]
+    pub factory_whitelist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: Factory WhitelistEntry for the source owner, may not exist
+    #[account(
+        seeds = [b"factory_wl", token_data.factory.as_ref(), source_token.owner.as_ref()],
+        bump
+    )]
+    pub source_factory_whitelist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: NftPass for the destination owner, may not exist
+    #[account(
+        seeds = [b"nft_pass", mint.key().as_ref(), destination_token.owner.as_ref()],
+        bump
+    )]
+    pub nft_pass: UncheckedAccount<'info>,
+
+    /// CHECK: NftPass for the source owner, may not exist
+    #[account(
+        seeds = [b"nft_pass", mint.key().as_ref(), source_token.owner.as_ref()],
+        bump
+    )]
+    pub source_nft_pass: UncheckedAccount<'info>,
+
+    /// CHECK: ActivityRecord for the source token account, may not exist
+    #[account(
+        mut,
+        seeds = [b"activity", source_token.key().as_ref()],
+        bump
+    )]
+    pub source_activity: UncheckedAccount<'info>,
+
+    /// CHECK: Account holding the destination owner's pass NFT, may not exist
+    #[account(
+        seeds = [b"nft_pass_account", mint.key().as_ref(), destination_token.owner.as_ref()],
+        bump
+    )]
+    pub nft_pass_account: UncheckedAccount<'info>,
+
+    /// CHECK: Account holding the source owner's pass NFT, may not exist
+    #[account(
+        seeds = [b"nft_pass_account", mint.key().as_ref(), source_token.owner.as_ref()],
+        bump
+    )]
+    pub source_nft_pass_account: UncheckedAccount<'info>,
+}
+
+#[derive(Clone, Copy)]
+enum TransferEnd {
+    Source,
+    Destination,
+}
+
+impl TransferHook<'_> {
+    // Applies the token's `WhitelistSource` to the owner at one end of the
+    // transfer; an NFT pass counts as one of the token's own lists
+    fn owner_whitelisted(
+        &self,
+        end: TransferEnd,
+        now: i64,
+        stats: &mut LookupStats,
+    ) -> Result<bool> {
+        let (owner, lookups) = match end {
+            TransferEnd::Destination => (
+                self.destination_token.owner,
+                OwnerLookups {
+                    factory_entry: &self.factory_whitelist_entry,
+                    entry: &self.whitelist_entry,
+                    credential: &self.credential,
+                    page: &self.whitelist,
+                    token_account_entry: &self.token_account_entry,
+                    nft_pass: &self.nft_pass,
+                    nft_pass_account: &self.nft_pass_account,
+                },
+            ),
+            TransferEnd::Source => (
+                self.source_token.owner,
+                OwnerLookups {
+                    factory_entry: &self.source_factory_whitelist_entry,
+                    entry: &self.source_whitelist_entry,
+                    credential: &self.source_credential,
+                    page: &self.source_whitelist,
+                    token_account_entry: &self.source_token_account_entry,
+                    nft_pass: &self.source_nft_pass,
+                    nft_pass_account: &self.source_nft_pass_account,
+                },
+            ),
+        };
+        whitelisted_by_source(&self.token_data, &owner, lookups, now, stats)
+    }
+}
+
+/// The optional accounts that can whitelist one owner of a token.
+pub(crate) struct OwnerLookups<'a, 'info> {
+    pub factory_entry: &'a UncheckedAccount<'info>,
+    pub entry: &'a UncheckedAccount<'info>,
+    pub credential: &'a UncheckedAccount<'info>,
+    pub page: &'a UncheckedAccount<'info>,
+    pub token_account_entry: &'a UncheckedAccount<'info>,
+    pub nft_pass: &'a UncheckedAccount<'info>,
+    pub nft_pass_account: &'a UncheckedAccount<'info>,
+}
+
+// The factory's entry, then the token's own lists, as the token's
+// `WhitelistSource` allows
+pub(crate) fn whitelisted_by_source(
+    token_data: &TokenData,
+    owner: &Pubkey,
+    lookups: OwnerLookups,
+    now: i64,
+    stats: &mut LookupStats,
+) -> Result<bool> {
+    let source = token_data.whitelist_source;
+    if factory_whitelisted(source, lookups.factory_entry, now, stats)? {
+        return Ok(true);
+    }
+    if !source.checks_token() {
+        return Ok(false);
+    }
+    Ok(is_whitelisted(
+        owner,
+        lookups.entry,
+        lookups.credential,
+        lookups.page,
+        lookups.token_account_entry,
+        now,
+        stats,
+    )? || nft_pass_held(
+        token_data,
+        owner,
+        lookups.nft_pass,
+        lookups.nft_pass_account,
+        stats,
+    )?)
+}
+
+fn check_is_transferring(ctx: &Context<TransferHook>) -> Result<()> {
+    let source_token_info = ctx.accounts.source_token.to_account_info();
+    let mut account_data_ref: std::cell::RefMut<&mut [u8]> =
+        source_token_info.try_borrow_mut_data()?;
+    let mut account = PodStateWithExtensionsMut::<PodAccount>::unpack(*account_data_ref)?;
+    let account_extension = account.get_extension_mut::<TransferHookAccount>()?;
+
+    if !bool::from(account_extension.transferring) {
+        return err!(ErrorCode::IsNotCurrentlyTransferring);
+    }
+
+    Ok(())
+}
+
+pub(crate) struct LookupStats {
+    /// Page scans are only counted when telemetry is on
+    enabled: bool,
+    rules_evaluated: u8,
+    lookup_iterations: u32,
+}
+
+impl LookupStats {
+    /// For whitelist checks outside the hook, which emit no telemetry
+    pub(crate) fn untracked() -> Self {
+        Self {
+            enabled: false,
+            rules_evaluated: 0,
+            lookup_iterations: 0,
+        }
+    }
+}
+
+// Checks the owner's own unexpired entry and credential, then its page,
+// falling back to an entry for the token account itself
+pub(crate) fn is_whitelisted(
+    owner: &Pubkey,
+    owner_entry: &UncheckedAccount,
+    credential: &UncheckedAccount,
+    page: &UncheckedAccount,
+    token_account_entry: &UncheckedAccount,
+    now: i64,
+    stats: &mut LookupStats,
+) -> Result<bool> {
+    stats.rules_evaluated += 1;
+    if whitelist_entry_active(owner_entry, now)? {
+        return Ok(true);
+    }
+    stats.rules_evaluated += 1;
+    if credential_held(credential, owner)? {
+        return Ok(true);
+    }
+    stats.rules_evaluated += 1;
+    if let Some(page) = load_whitelist_page(page)? {
+        if stats.enabled {
+            stats.lookup_iterations += page.entries_scanned(owner);
+        }
+        if page.is_allowed(owner) {
+            return Ok(true);
+        }
+    }
+    stats.rules_evaluated += 1;
+    entry_exists(token_account_entry)
+}
+
+// The factory's shared entry, for tokens whose `WhitelistSource` reads it
+pub(crate) fn factory_whitelisted(
+    source: WhitelistSource,
+    entry: &UncheckedAccount,
+    now: i64,
+    stats: &mut LookupStats,
+) -> Result<bool> {
+    if !source.checks_factory() {
+        return Ok(false);
+    }
+    stats.rules_evaluated += 1;
+    whitelist_entry_active(entry, now)
+}
+
+// A pass only counts for the collection the token currently gates on, and
+// only while the owner's pass account still holds its NFT
+pub(crate) fn nft_pass_held(
+    token_data: &TokenData,
+    owner: &Pubkey,
+    pass: &UncheckedAccount,
+    nft_account: &UncheckedAccount,
+    stats: &mut LookupStats,
+) -> Result<bool> {
+    let Some(collection) = token_data.nft_gate_collection else {
+        return Ok(false);
+    };
+    stats.rules_evaluated += 1;
+    if pass.data_is_empty() {
+        return Ok(false);
+    }
+    require_keys_eq!(*pass.owner, crate::ID, ErrorCode::Unauthorized);
+    let pass = NftPass::try_deserialize(&mut &pass.try_borrow_data()?[..])?;
+    if pass.collection != collection || nft_account.data_is_empty() {
+        return Ok(false);
+    }
+    require_keys_eq!(
+        nft_account.key(),
+        pass.nft_token_account,
+        ErrorCode::Unauthorized
+    );
+    require!(
+        *nft_account.owner == anchor_spl::token::ID
+            || *nft_account.owner == anchor_spl::token_2022::ID,
+        ErrorCode::Unauthorized
+    );
+    let account = TokenAccount::try_deserialize(&mut &nft_account.try_borrow_data()?[..])?;
+    Ok(account.mint == pass.nft_mint && account.owner == *owner && account.amount == 1)
+}
+
+// Draws `amount` from the owner's permit, if it has one that covers it
+fn consume_permit(
+    permit: &UncheckedAccount,
+    amount: u64,
+    now: i64,
+    stats: &mut LookupStats,
+) -> Result<bool> {
+    stats.rules_evaluated += 1;
+    if permit.data_is_empty() {
+        return Ok(false);
+    }
+    require_keys_eq!(*permit.owner, crate::ID, ErrorCode::Unauthorized);
+
+    let mut data = permit.try_borrow_mut_data()?;
+    let mut state = TransferPermit::try_deserialize(&mut &data[..])?;
+    if now >= state.expires_at || amount > state.remaining {
+        return Ok(false);
+    }
+    // A spent permit stays behind until the authority closes it
+    state.remaining -= amount;
+    state.try_serialize(&mut &mut data[..])?;
+    Ok(true)
+}
+
+// Without a window there's nowhere to record the volume, so the transfer
+// can't go through
+fn record_window_volume(
+    window: &UncheckedAccount,
+    limit: u64,
+    amount: u64,
+    now: i64,
+) -> Result<bool> {
+    if window.data_is_empty() {
+        return Ok(false);
+    }
+    require_keys_eq!(*window.owner, crate::ID, ErrorCode::Unauthorized);
+
+    let mut data = window.try_borrow_mut_data()?;
+    let mut state = TransferWindow::try_deserialize(&mut &data[..])?;
+    if !state.record(amount, limit, now) {
+        return Ok(false);
+    }
+    state.try_serialize(&mut &mut data[..])?;
+    Ok(true)
+}
+
+// Plain SPL delegates get no approval account, so they're turned away
+fn record_custodian_transfer(approval: &UncheckedAccount, amount: u64, now: i64) -> Result<bool> {
+    if approval.data_is_empty() {
+        return Ok(false);
+    }
+    require_keys_eq!(*approval.owner, crate::ID, ErrorCode::Unauthorized);
+
+    let mut data = approval.try_borrow_mut_data()?;
+    let mut state = CustodianApproval::try_deserialize(&mut &data[..])?;
+    if !state.record(amount, now) {
+        return Ok(false);
+    }
+    state.try_serialize(&mut &mut data[..])?;
+    Ok(true)
+}
+
+// Failing the transfer would roll the pause back with it, so the transfer
+// that trips the breaker still goes through and the ones after it don't.
+// A locked pause setting can't be flipped, not even by the breaker.
+fn trip_circuit_breaker(ctx: &mut Context<TransferHook>, amount: u64) -> Result<()> {
+    let tracker = &ctx.accounts.velocity_tracker;
+    if tracker.data_is_empty() {
+        return Ok(());
+    }
+    require_keys_eq!(*tracker.owner, crate::ID, ErrorCode::Unauthorized);
+
+    let mut data = tracker.try_borrow_mut_data()?;
+    let mut state = VelocityTracker::try_deserialize(&mut &data[..])?;
+    let tripped = state.record(amount, Clock::get()?.slot);
+    state.try_serialize(&mut &mut data[..])?;
+
+    let token_data = &mut ctx.accounts.token_data;
+    if tripped && token_data.config_lock & LOCK_PAUSE == 0 {
+        token_data.is_paused = true;
+        msg!("Circuit breaker tripped, token paused");
+        emit!(CircuitBreakerTripped {
+            mint: token_data.mint,
+            window_start_slot: state.window_start_slot,
+            transfers: state.transfers,
+            volume: state.volume,
+        });
+    }
+    Ok(())
+}
+
+// An outbound transfer shows the holder is still around
+fn contest_dormancy_notice(notice: &UncheckedAccount) -> Result<()> {
+    if notice.data_is_empty() {
+        return Ok(());
+    }
+    require_keys_eq!(*notice.owner, crate::ID, ErrorCode::Unauthorized);
+
+    let mut data = notice.try_borrow_mut_data()?;
+    let mut state = DormancyNotice::try_deserialize(&mut &data[..])?;
+    if !state.contested {
+        state.contested = true;
+        state.try_serialize(&mut &mut data[..])?;
+    }
+    Ok(())
+}
+
+// Untracked accounts can't be found dormant, so there's nothing to move
+fn record_activity(record: &UncheckedAccount, now: i64) -> Result<()> {
+    if record.data_is_empty() {
+        return Ok(());
+    }
+    require_keys_eq!(*record.owner, crate::ID, ErrorCode::Unauthorized);
+
+    let mut data = record.try_borrow_mut_data()?;
+    let mut state = ActivityRecord::try_deserialize(&mut &data[..])?;
+    state.last_activity_at = now;
+    state.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+fn load_whitelist_page(page: &UncheckedAccount) -> Result<Option<Whitelist>> {
+    // A page that was never created holds no addresses
+    if page.data_is_empty() {
+        return Ok(None);
+    }
+    require_keys_eq!(*page.owner, crate::ID, ErrorCode::Unauthorized);
+    let whitelist = Whitelist::try_deserialize(&mut &page.try_borrow_data()?[..])?;
+    Ok(Some(whitelist))
+}
+
+pub(crate) fn entry_exists(entry: &UncheckedAccount) -> Result<bool> {
+    // Marker PDAs hold no data; only their existence matters
+    if entry.data_is_empty() {
+        return Ok(false);
+    }
+    require_keys_eq!(*entry.owner, crate::ID, ErrorCode::Unauthorized);
+    Ok(true)
+}
+
+// An expired entry counts as no entry until someone prunes it
+fn whitelist_entry_active(entry: &UncheckedAccount, now: i64) -> Result<bool> {
+    if !entry_exists(entry)? {
+        return Ok(false);
+    }
+    let entry = WhitelistEntry::try_deserialize(&mut &entry.try_borrow_data()?[..])?;
+    Ok(entry.is_active(now))
+}
+
+fn credential_held(credential: &UncheckedAccount, owner: &Pubkey) -> Result<bool> {
+    if credential.data_is_empty() {
+        return Ok(false);
+    }
+    // Only this program can sign for the credential PDA, so an initialized
+    // token account there always holds the right credential mint
+    require_keys_eq!(
+        *credential.owner,
+        anchor_spl::token_2022::ID,
+        ErrorCode::Unauthorized
+    );
+    let account = TokenAccount::try_deserialize(&mut &credential.try_borrow_data()?[..])?;
+    Ok(account.owner == *owner && account.amount > 0 && !account.is_frozen())
+}
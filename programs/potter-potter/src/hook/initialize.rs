@@ -0,0 +1,448 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use spl_tlv_account_resolution::{
+    account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList,
+};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+
+/// Bump whenever `extra_account_metas` changes shape; published in the
+/// `HookManifest` so integrators know when to rebuild their resolution.
+pub const EXTRA_ACCOUNT_METAS_VERSION: u8 = 10;
+
+pub fn handle_initialize_extra_account_meta_list(
+    ctx: Context<InitializeExtraAccountMetaList>,
+) -> Result<()> {
+    let extra_account_metas =
+        InitializeExtraAccountMetaList::extra_account_metas(&ctx.accounts.token_data.key())?;
+
+    // Initialize ExtraAccountMetaList account with extra accounts
+    // Convert ProgramError to anchor_lang::error::Error
+    ExtraAccountMetaList::init::<ExecuteInstruction>(
+        &mut ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?,
+        &extra_account_metas,
+    )
+    .map_err(|e| {
+        msg!("Error initializing extra account meta list: {:?}", e);
+        error!(ErrorCode::InvalidAmount)
+    })?;
+
+    msg!(
+        "Transfer hook initialized for mint: {}",
+        ctx.accounts.mint.key()
+    );
+    Ok(())
+}
+
+// Mints created before a layout change keep resolving the old accounts
+// until their list is rewritten; the PDA is resized to fit first
+pub fn handle_update_extra_account_meta_list(
+    ctx: Context<UpdateExtraAccountMetaList>,
+) -> Result<()> {
+    let extra_account_metas =
+        InitializeExtraAccountMetaList::extra_account_metas(&ctx.accounts.token_data.key())?;
+    let new_len = ExtraAccountMetaList::size_of(extra_account_metas.len())
+        .map_err(|_| error!(ErrorCode::InvalidAmount))?;
+
+    grow_program_account(
+        &ctx.accounts.extra_account_meta_list,
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        new_len,
+    )?;
+
+    ExtraAccountMetaList::update::<ExecuteInstruction>(
+        &mut ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?,
+        &extra_account_metas,
+    )
+    .map_err(|e| {
+        msg!("Error updating extra account meta list: {:?}", e);
+        error!(ErrorCode::InvalidAmount)
+    })?;
+
+    if let Some(manifest) = &mut ctx.accounts.hook_manifest {
+        manifest.layout_version = EXTRA_ACCOUNT_METAS_VERSION;
+        manifest.refresh(&ctx.accounts.token_data)?;
+    }
+
+    msg!(
+        "Transfer hook accounts updated to layout {} for mint: {}",
+        EXTRA_ACCOUNT_METAS_VERSION,
+        ctx.accounts.mint.key()
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeExtraAccountMetaList<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: ExtraAccountMetaList Account
+    #[account(
+        init,
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump,
+        space = ExtraAccountMetaList::size_of(
+            InitializeExtraAccountMetaList::extra_account_metas(&token_data.key())?.len()
+        ).map_err(|_| error!(ErrorCode::InvalidAmount))?,
+        payer = payer
+    )]
+    pub extra_account_meta_list: AccountInfo<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(has_one = mint)]
+    pub token_data: Account<'info, TokenData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Leads with the accounts of the interface's update instruction: the list,
+// the mint and the signing authority
+#[derive(Accounts)]
+pub struct UpdateExtraAccountMetaList<'info> {
+    /// CHECK: ExtraAccountMetaList Account
+    #[account(
+        mut,
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_meta_list: AccountInfo<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(has_one = mint, has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"hook_manifest", mint.key().as_ref()],
+        bump
+    )]
+    pub hook_manifest: Option<Account<'info, HookManifest>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeExtraAccountMetaList<'info> {
+    pub fn extra_account_metas(token_data: &Pubkey) -> Result<Vec<ExtraAccountMeta>> {
+        // TokenData can't be derived from the mint, so its address is fixed;
+        // writable for the circuit breaker, not a signer
+        let token_data_meta = ExtraAccountMeta::new_with_pubkey(token_data, false, true)
+            .map_err(|_| error!(ErrorCode::InvalidAmount))?;
+
+        let mut metas = vec![token_data_meta];
+        metas.extend(Self::whitelist_metas(2)?); // destination token account
+        metas.extend(Self::whitelist_metas(0)?); // source token account
+
+        // The hook draws permits down, so this one is writable
+        let permit_meta = ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"permit".to_vec(),
+                },
+                Seed::AccountKey { index: 1 }, // mint
+                Seed::AccountData {
+                    account_index: 2, // destination token account
+                    data_index: 32,   // owner
+                    length: 32,
+                },
+            ],
+            false,
+            true,
+        )
+        .map_err(|_| error!(ErrorCode::InvalidAmount))?;
+        metas.push(permit_meta);
+
+        // Writable so an outbound transfer can contest a dormancy notice
+        let dormancy_notice_meta = ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"dormancy".to_vec(),
+                },
+                Seed::AccountKey { index: 0 }, // source token account
+            ],
+            false,
+            true,
+        )
+        .map_err(|_| error!(ErrorCode::InvalidAmount))?;
+        metas.push(dormancy_notice_meta);
+
+        // The hook adds each transfer to the sender's daily volume
+        let transfer_window_meta = ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"transfer_window".to_vec(),
+                },
+                Seed::AccountKey { index: 1 }, // mint
+                Seed::AccountData {
+                    account_index: 0, // source token account
+                    data_index: 32,   // owner
+                    length: 32,
+                },
+            ],
+            false,
+            true,
+        )
+        .map_err(|_| error!(ErrorCode::InvalidAmount))?;
+        metas.push(transfer_window_meta);
+
+        // The hook counts every transfer of the mint here
+        let velocity_meta = ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"velocity".to_vec(),
+                },
+                Seed::AccountKey { index: 1 }, // mint
+            ],
+            false,
+            true,
+        )
+        .map_err(|_| error!(ErrorCode::InvalidAmount))?;
+        metas.push(velocity_meta);
+
+        // Delegated transfers draw down the custodian's approval
+        let custodian_meta = ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"custodian".to_vec(),
+                },
+                Seed::AccountKey { index: 0 }, // source token account
+                Seed::AccountKey { index: 3 }, // transfer authority
+            ],
+            false,
+            true,
+        )
+        .map_err(|_| error!(ErrorCode::InvalidAmount))?;
+        metas.push(custodian_meta);
+
+        metas.push(Self::blacklist_meta(2)?); // destination token account
+        metas.push(Self::blacklist_meta(0)?); // source token account
+
+        // Program-wide kill switches, for `program_pause`
+        let program_config_meta = ExtraAccountMeta::new_with_seeds(
+            &[Seed::Literal {
+                bytes: b"program_config".to_vec(),
+            }],
+            false,
+            false,
+        )
+        .map_err(|_| error!(ErrorCode::InvalidAmount))?;
+        metas.push(program_config_meta);
+
+        metas.push(Self::factory_whitelist_meta(2)?); // destination token account
+        metas.push(Self::factory_whitelist_meta(0)?); // source token account
+
+        metas.push(Self::nft_pass_meta(2)?); // destination token account
+        metas.push(Self::nft_pass_meta(0)?); // source token account
+
+        // The hook stamps the source's last outbound transfer here
+        let activity_meta = ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"activity".to_vec(),
+                },
+                Seed::AccountKey { index: 0 }, // source token account
+            ],
+            false,
+            true,
+        )
+        .map_err(|_| error!(ErrorCode::InvalidAmount))?;
+        metas.push(activity_meta);
+
+        // A pass's NFT sits at an address derived from its owner, since
+        // reading it out of the pass wouldn't resolve for owners without one
+        metas.push(Self::nft_pass_account_meta(2)?); // destination token account
+        metas.push(Self::nft_pass_account_meta(0)?); // source token account
+
+        // Lands in the hook's remaining accounts for `unix_timestamp`
+        #[cfg(feature = "test-harness")]
+        metas.push(
+            ExtraAccountMeta::new_with_seeds(
+                &[Seed::Literal {
+                    bytes: b"mock_clock".to_vec(),
+                }],
+                false,
+                false,
+            )
+            .map_err(|_| error!(ErrorCode::InvalidAmount))?,
+        );
+        Ok(metas)
+    }
+
+    // Blacklist entry for the owner of the token account at
+    // `token_account_index`, always under the token's own mint
+    fn blacklist_meta(token_account_index: u8) -> Result<ExtraAccountMeta> {
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"blacklist".to_vec(),
+                },
+                Seed::AccountKey { index: 1 }, // mint
+                Seed::AccountData {
+                    account_index: token_account_index,
+                    data_index: 32, // owner
+                    length: 32,
+                },
+            ],
+            false,
+            false,
+        )
+        .map_err(|_| error!(ErrorCode::InvalidAmount))
+    }
+
+    // Factory-wide whitelist entry for the owner of the token account at
+    // `token_account_index`, under the factory recorded in the token data
+    fn factory_whitelist_meta(token_account_index: u8) -> Result<ExtraAccountMeta> {
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"factory_wl".to_vec(),
+                },
+                Seed::AccountData {
+                    account_index: 5, // token data
+                    data_index: TokenData::FACTORY_OFFSET as u8,
+                    length: 32,
+                },
+                Seed::AccountData {
+                    account_index: token_account_index,
+                    data_index: 32, // owner
+                    length: 32,
+                },
+            ],
+            false,
+            false,
+        )
+        .map_err(|_| error!(ErrorCode::InvalidAmount))
+    }
+
+    // NFT pass for the owner of the token account at `token_account_index`,
+    // under the token's own mint like its gate collection
+    fn nft_pass_meta(token_account_index: u8) -> Result<ExtraAccountMeta> {
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"nft_pass".to_vec(),
+                },
+                Seed::AccountKey { index: 1 }, // mint
+                Seed::AccountData {
+                    account_index: token_account_index,
+                    data_index: 32, // owner
+                    length: 32,
+                },
+            ],
+            false,
+            false,
+        )
+        .map_err(|_| error!(ErrorCode::InvalidAmount))
+    }
+
+    // Account holding the pass NFT of the owner of the token account at
+    // `token_account_index`
+    fn nft_pass_account_meta(token_account_index: u8) -> Result<ExtraAccountMeta> {
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"nft_pass_account".to_vec(),
+                },
+                Seed::AccountKey { index: 1 }, // mint
+                Seed::AccountData {
+                    account_index: token_account_index,
+                    data_index: 32, // owner
+                    length: 32,
+                },
+            ],
+            false,
+            false,
+        )
+        .map_err(|_| error!(ErrorCode::InvalidAmount))
+    }
+
+    // Whitelist accounts for the token account at `token_account_index`,
+    // all read-only and not signers: its owner's page, an optional entry for
+    // the token account itself, an optional per-owner entry and the owner's
+    // membership credential. Everything but the token account entry lives
+    // under whichever mint the token reads its whitelist from.
+    fn whitelist_metas(token_account_index: u8) -> Result<[ExtraAccountMeta; 4]> {
+        let whitelist_mint = Seed::AccountData {
+            account_index: 5, // token data
+            data_index: TokenData::WHITELIST_MINT_OFFSET as u8,
+            length: 32,
+        };
+
+        // Page derived from the owner's first byte
+        let page_meta = ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"whitelist".to_vec(),
+                },
+                whitelist_mint.clone(),
+                Seed::AccountData {
+                    account_index: token_account_index,
+                    data_index: 32, // owner
+                    length: 1,
+                },
+            ],
+            false,
+            false,
+        )
+        .map_err(|_| error!(ErrorCode::InvalidAmount))?;
+
+        let token_account_meta = ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"whitelisted_account".to_vec(),
+                },
+                Seed::AccountKey { index: 1 }, // mint
+                Seed::AccountKey {
+                    index: token_account_index,
+                },
+            ],
+            false,
+            false,
+        )
+        .map_err(|_| error!(ErrorCode::InvalidAmount))?;
+
+        let entry_meta = ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"wl".to_vec(),
+                },
+                whitelist_mint.clone(),
+                Seed::AccountData {
+                    account_index: token_account_index,
+                    data_index: 32, // owner
+                    length: 32,
+                },
+            ],
+            false,
+            false,
+        )
+        .map_err(|_| error!(ErrorCode::InvalidAmount))?;
+
+        let credential_meta = ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"credential".to_vec(),
+                },
+                whitelist_mint,
+                Seed::AccountData {
+                    account_index: token_account_index,
+                    data_index: 32, // owner
+                    length: 32,
+                },
+            ],
+            false,
+            false,
+        )
+        .map_err(|_| error!(ErrorCode::InvalidAmount))?;
+
+        Ok([page_meta, token_account_meta, entry_meta, credential_meta])
+    }
+}
@@ -0,0 +1,9 @@
+mod execute;
+mod initialize;
+mod receiver;
+#[cfg(test)]
+mod tests;
+
+pub use execute::*;
+pub use initialize::*;
+pub use receiver::*;
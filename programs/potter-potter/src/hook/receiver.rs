@@ -0,0 +1,154 @@
+use super::execute::*;
+use crate::errors::ErrorCode;
+use crate::state::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+/// Lookup accounts for a destination that receives tokens without a
+/// transfer, as a mint out of a vault or a swap, so it can be held to the
+/// rules the hook would apply. None of them need exist.
+#[derive(Accounts)]
+pub struct ReceiverChecks<'info> {
+    /// CHECK: BlacklistEntry for the destination owner
+    pub blacklist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: Whitelist page for the destination owner
+    pub whitelist: UncheckedAccount<'info>,
+
+    /// CHECK: WhitelistedTokenAccount entry for the destination
+    pub token_account_entry: UncheckedAccount<'info>,
+
+    /// CHECK: WhitelistEntry for the destination owner
+    pub whitelist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: Membership credential for the destination owner
+    pub credential: UncheckedAccount<'info>,
+
+    /// CHECK: Factory WhitelistEntry for the destination owner
+    pub factory_whitelist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: NftPass for the destination owner
+    pub nft_pass: UncheckedAccount<'info>,
+
+    /// CHECK: Account holding the destination owner's pass NFT
+    pub nft_pass_account: UncheckedAccount<'info>,
+}
+
+impl ReceiverChecks<'_> {
+    /// Fails unless `destination` could take `raw_amount` more base units
+    /// of the token through a transfer: the token isn't paused, the owner
+    /// isn't blacklisted, is whitelisted where the policy checks receivers
+    /// and stays under the holding cap.
+    pub fn require_can_receive(
+        &self,
+        token_data: &TokenData,
+        destination: &InterfaceAccount<TokenAccount>,
+        raw_amount: u64,
+        now: i64,
+    ) -> Result<()> {
+        self.verify_addresses(token_data, destination)?;
+        let owner = destination.owner;
+
+        require!(!token_data.is_paused, ErrorCode::TokenPaused);
+        require!(
+            !entry_exists(&self.blacklist_entry)?,
+            ErrorCode::AddressBlacklisted
+        );
+        if token_data.whitelist_policy.checks_destination() {
+            require!(
+                whitelisted_by_source(
+                    token_data,
+                    &owner,
+                    OwnerLookups {
+                        factory_entry: &self.factory_whitelist_entry,
+                        entry: &self.whitelist_entry,
+                        credential: &self.credential,
+                        page: &self.whitelist,
+                        token_account_entry: &self.token_account_entry,
+                        nft_pass: &self.nft_pass,
+                        nft_pass_account: &self.nft_pass_account,
+                    },
+                    now,
+                    &mut LookupStats::untracked(),
+                )?,
+                ErrorCode::AddressNotWhitelisted
+            );
+        }
+
+        let balance = destination
+            .amount
+            .checked_add(raw_amount)
+            .ok_or(ErrorCode::InvalidAmount)?;
+        require!(
+            !token_data.exceeds_wallet_cap(&owner, balance),
+            ErrorCode::WalletCapExceeded
+        );
+        Ok(())
+    }
+
+    // Same addresses the hook's extra account metas resolve to
+    fn verify_addresses(
+        &self,
+        token_data: &TokenData,
+        destination: &InterfaceAccount<TokenAccount>,
+    ) -> Result<()> {
+        let owner = destination.owner;
+        let destination_key = destination.key();
+        let expected: [(&UncheckedAccount, &[&[u8]]); 8] = [
+            (
+                &self.blacklist_entry,
+                &[b"blacklist", token_data.mint.as_ref(), owner.as_ref()],
+            ),
+            (
+                &self.whitelist,
+                &[
+                    b"whitelist",
+                    token_data.whitelist_mint.as_ref(),
+                    &[whitelist_page_of(&owner)],
+                ],
+            ),
+            (
+                &self.token_account_entry,
+                &[
+                    b"whitelisted_account",
+                    token_data.mint.as_ref(),
+                    destination_key.as_ref(),
+                ],
+            ),
+            (
+                &self.whitelist_entry,
+                &[b"wl", token_data.whitelist_mint.as_ref(), owner.as_ref()],
+            ),
+            (
+                &self.credential,
+                &[
+                    b"credential",
+                    token_data.whitelist_mint.as_ref(),
+                    owner.as_ref(),
+                ],
+            ),
+            (
+                &self.factory_whitelist_entry,
+                &[b"factory_wl", token_data.factory.as_ref(), owner.as_ref()],
+            ),
+            (
+                &self.nft_pass,
+                &[b"nft_pass", token_data.mint.as_ref(), owner.as_ref()],
+            ),
+            (
+                &self.nft_pass_account,
+                &[
+                    b"nft_pass_account",
+                    token_data.mint.as_ref(),
+                    owner.as_ref(),
+                ],
+            ),
+        ];
+        for (account, seeds) in expected {
+            let (address, _) = Pubkey::find_program_address(seeds, &crate::ID);
+            require_keys_eq!(account.key(), address, ErrorCode::Unauthorized);
+        }
+        Ok(())
+    }
+}
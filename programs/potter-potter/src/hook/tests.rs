@@ -0,0 +1,993 @@
+//! Runs the transfer hook against synthetic accounts, one rule at a time,
+//! with every lookup account missing unless a test creates it.
+
+use super::*;
+use crate::errors::ErrorCode;
+use crate::state::*;
+use crate::test_utils::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+use std::collections::{BTreeSet, HashMap};
+
+const DAY: i64 = 24 * 60 * 60;
+
+struct HookTest {
+    mint: Pubkey,
+    token_data: TokenData,
+    source_owner: Pubkey,
+    destination_owner: Pubkey,
+    /// Transfer authority; the source owner unless a custodian signs
+    signer: Pubkey,
+    source: Pubkey,
+    destination: Pubkey,
+    /// Balances as Token-2022 leaves them before calling the hook
+    source_balance: u64,
+    destination_balance: u64,
+    transferring: bool,
+    disabled_features: u16,
+    lookups: HashMap<Pubkey, &'static AccountInfo<'static>>,
+}
+
+impl HookTest {
+    fn new() -> Self {
+        let mint = Pubkey::new_unique();
+        let source_owner = Pubkey::new_unique();
+        Self {
+            mint,
+            token_data: token_data(mint, Pubkey::new_unique()),
+            source_owner,
+            destination_owner: Pubkey::new_unique(),
+            signer: source_owner,
+            source: Pubkey::new_unique(),
+            destination: Pubkey::new_unique(),
+            source_balance: 500,
+            destination_balance: 100,
+            transferring: true,
+            disabled_features: 0,
+            lookups: HashMap::new(),
+        }
+    }
+
+    /// A transfer to a destination on the token's whitelist page.
+    fn whitelisted() -> Self {
+        let mut test = Self::new();
+        test.list_on_page(test.destination_owner);
+        test
+    }
+
+    fn list_on_page(&mut self, owner: Pubkey) {
+        let page = self.page(&owner);
+        let mut whitelist = match self.lookups.get(&page) {
+            Some(info) => read_account::<Whitelist>(info),
+            None => Whitelist {
+                addresses: Vec::new(),
+                suspended: Vec::new(),
+            },
+        };
+        whitelist.addresses.push(owner);
+        self.add(page, &whitelist);
+    }
+
+    fn add<T: AccountSerialize>(
+        &mut self,
+        key: Pubkey,
+        value: &T,
+    ) -> &'static AccountInfo<'static> {
+        let info = program_account(key, value);
+        self.lookups.insert(key, info);
+        info
+    }
+
+    fn add_marker(&mut self, key: Pubkey) {
+        self.lookups.insert(key, marker_account(key));
+    }
+
+    fn page(&self, owner: &Pubkey) -> Pubkey {
+        pda(&[
+            b"whitelist",
+            self.token_data.whitelist_mint.as_ref(),
+            &[whitelist_page_of(owner)],
+        ])
+    }
+
+    fn entry(&self, owner: &Pubkey) -> Pubkey {
+        pda(&[
+            b"wl",
+            self.token_data.whitelist_mint.as_ref(),
+            owner.as_ref(),
+        ])
+    }
+
+    fn credential(&self, owner: &Pubkey) -> Pubkey {
+        pda(&[
+            b"credential",
+            self.token_data.whitelist_mint.as_ref(),
+            owner.as_ref(),
+        ])
+    }
+
+    fn token_account_entry(&self, token_account: &Pubkey) -> Pubkey {
+        pda(&[
+            b"whitelisted_account",
+            self.mint.as_ref(),
+            token_account.as_ref(),
+        ])
+    }
+
+    fn factory_entry(&self, owner: &Pubkey) -> Pubkey {
+        pda(&[
+            b"factory_wl",
+            self.token_data.factory.as_ref(),
+            owner.as_ref(),
+        ])
+    }
+
+    fn nft_pass(&self, owner: &Pubkey) -> Pubkey {
+        pda(&[b"nft_pass", self.mint.as_ref(), owner.as_ref()])
+    }
+
+    fn nft_pass_account(&self, owner: &Pubkey) -> Pubkey {
+        pda(&[b"nft_pass_account", self.mint.as_ref(), owner.as_ref()])
+    }
+
+    /// Registers a pass for `owner` whose account holds `amount` of its NFT.
+    fn add_nft_pass(&mut self, owner: Pubkey, collection: Pubkey, amount: u64) {
+        let nft_mint = Pubkey::new_unique();
+        let account = self.nft_pass_account(&owner);
+        self.add(
+            self.nft_pass(&owner),
+            &NftPass {
+                collection,
+                nft_mint,
+                nft_token_account: account,
+            },
+        );
+        self.lookups.insert(
+            account,
+            token_account(account, nft_mint, owner, amount, false),
+        );
+    }
+
+    fn blacklist(&self, owner: &Pubkey) -> Pubkey {
+        pda(&[b"blacklist", self.mint.as_ref(), owner.as_ref()])
+    }
+
+    fn permit(&self) -> Pubkey {
+        pda(&[
+            b"permit",
+            self.mint.as_ref(),
+            self.destination_owner.as_ref(),
+        ])
+    }
+
+    fn transfer_window(&self) -> Pubkey {
+        pda(&[
+            b"transfer_window",
+            self.mint.as_ref(),
+            self.source_owner.as_ref(),
+        ])
+    }
+
+    fn velocity_tracker(&self) -> Pubkey {
+        pda(&[b"velocity", self.mint.as_ref()])
+    }
+
+    fn custodian_approval(&self) -> Pubkey {
+        pda(&[b"custodian", self.source.as_ref(), self.signer.as_ref()])
+    }
+
+    fn dormancy_notice(&self) -> Pubkey {
+        pda(&[b"dormancy", self.source.as_ref()])
+    }
+
+    fn activity_record(&self) -> Pubkey {
+        pda(&[b"activity", self.source.as_ref()])
+    }
+
+    fn lookup(&self, key: Pubkey) -> AccountInfo<'static> {
+        self.lookups
+            .get(&key)
+            .copied()
+            .unwrap_or_else(|| missing_account(key))
+            .clone()
+    }
+
+    /// Runs the hook for `amount`, returning the token data as the hook
+    /// left it.
+    fn run(&self, amount: u64) -> Result<TokenData> {
+        use_fixed_clock();
+        let (source_owner, destination_owner) = (self.source_owner, self.destination_owner);
+        let infos = vec![
+            hooked_token_account(
+                self.source,
+                self.mint,
+                source_owner,
+                self.source_balance,
+                self.transferring,
+            )
+            .clone(),
+            mint_account(self.mint, 1_000_000, self.token_data.decimals).clone(),
+            token_account(
+                self.destination,
+                self.mint,
+                destination_owner,
+                self.destination_balance,
+                false,
+            )
+            .clone(),
+            missing_account(self.signer).clone(),
+            missing_account(pda(&[b"extra-account-metas", self.mint.as_ref()])).clone(),
+            program_account(Pubkey::new_unique(), &self.token_data).clone(),
+            self.lookup(self.page(&destination_owner)),
+            self.lookup(self.token_account_entry(&self.destination)),
+            self.lookup(self.entry(&destination_owner)),
+            self.lookup(self.credential(&destination_owner)),
+            self.lookup(self.page(&source_owner)),
+            self.lookup(self.token_account_entry(&self.source)),
+            self.lookup(self.entry(&source_owner)),
+            self.lookup(self.credential(&source_owner)),
+            self.lookup(self.permit()),
+            self.lookup(self.dormancy_notice()),
+            self.lookup(self.transfer_window()),
+            self.lookup(self.velocity_tracker()),
+            self.lookup(self.custodian_approval()),
+            self.lookup(self.blacklist(&destination_owner)),
+            self.lookup(self.blacklist(&source_owner)),
+            program_account(
+                pda(&[b"program_config"]),
+                &program_config(self.disabled_features),
+            )
+            .clone(),
+            self.lookup(self.factory_entry(&destination_owner)),
+            self.lookup(self.factory_entry(&source_owner)),
+            self.lookup(self.nft_pass(&destination_owner)),
+            self.lookup(self.nft_pass(&source_owner)),
+            self.lookup(self.activity_record()),
+            self.lookup(self.nft_pass_account(&destination_owner)),
+            self.lookup(self.nft_pass_account(&source_owner)),
+        ];
+        let infos: &'static [AccountInfo<'static>] = Box::leak(infos.into_boxed_slice());
+
+        let mut bumps = TransferHookBumps::default();
+        let mut accounts = TransferHook::try_accounts(
+            &crate::ID,
+            &mut &infos[..],
+            &[],
+            &mut bumps,
+            &mut BTreeSet::new(),
+        )?;
+        handle_transfer_hook(Context::new(&crate::ID, &mut accounts, &[], bumps), amount)?;
+        Ok((*accounts.token_data).clone())
+    }
+}
+
+fn assert_error<T>(result: Result<T>, expected: ErrorCode) {
+    match result {
+        Ok(_) => panic!("expected {expected:?}"),
+        Err(err) => assert_eq!(err, expected.into()),
+    }
+}
+
+#[test]
+fn whitelisted_destination_passes() {
+    HookTest::whitelisted().run(10).unwrap();
+}
+
+#[test]
+fn unlisted_destination_fails() {
+    assert_error(HookTest::new().run(10), ErrorCode::AddressNotWhitelisted);
+}
+
+#[test]
+fn calls_outside_a_transfer_fail() {
+    let mut test = HookTest::whitelisted();
+    test.transferring = false;
+    assert_error(test.run(10), ErrorCode::IsNotCurrentlyTransferring);
+}
+
+#[test]
+fn halted_transfers_fail() {
+    let mut test = HookTest::whitelisted();
+    test.disabled_features = FEATURE_TRANSFERS;
+    assert_error(test.run(10), ErrorCode::FeatureDisabled);
+
+    test.disabled_features = FEATURE_SWAPS;
+    test.run(10).unwrap();
+}
+
+#[test]
+fn paused_token_fails() {
+    let mut test = HookTest::whitelisted();
+    test.token_data.is_paused = true;
+    assert_error(test.run(10), ErrorCode::TokenPaused);
+}
+
+#[test]
+fn blacklisted_destination_fails_even_when_whitelisted() {
+    let mut test = HookTest::whitelisted();
+    test.add_marker(test.blacklist(&test.destination_owner));
+    assert_error(test.run(10), ErrorCode::AddressBlacklisted);
+}
+
+#[test]
+fn blacklisted_source_fails() {
+    let mut test = HookTest::whitelisted();
+    test.add_marker(test.blacklist(&test.source_owner));
+    assert_error(test.run(10), ErrorCode::AddressBlacklisted);
+}
+
+#[test]
+fn blacklist_entry_owned_elsewhere_is_rejected() {
+    let mut test = HookTest::whitelisted();
+    let key = test.blacklist(&test.destination_owner);
+    test.lookups.insert(
+        key,
+        foreign_account(
+            key,
+            &WhitelistEntry {
+                expires_at: None,
+                suspended: false,
+            },
+        ),
+    );
+    assert_error(test.run(10), ErrorCode::Unauthorized);
+}
+
+#[test]
+fn custodian_within_its_approval_passes() {
+    let mut test = HookTest::whitelisted();
+    test.signer = Pubkey::new_unique();
+    let approval = test.add(
+        test.custodian_approval(),
+        &CustodianApproval {
+            owner: test.source_owner,
+            custodian: test.signer,
+            cap: 50,
+            spent: 30,
+            expires_at: NOW + DAY,
+        },
+    );
+    test.run(20).unwrap();
+    assert_eq!(read_account::<CustodianApproval>(approval).spent, 50);
+}
+
+#[test]
+fn custodian_past_its_cap_fails() {
+    let mut test = HookTest::whitelisted();
+    test.signer = Pubkey::new_unique();
+    test.add(
+        test.custodian_approval(),
+        &CustodianApproval {
+            owner: test.source_owner,
+            custodian: test.signer,
+            cap: 50,
+            spent: 30,
+            expires_at: NOW + DAY,
+        },
+    );
+    assert_error(test.run(21), ErrorCode::CustodianLimitExceeded);
+}
+
+#[test]
+fn delegate_without_approval_fails() {
+    let mut test = HookTest::whitelisted();
+    test.signer = Pubkey::new_unique();
+    assert_error(test.run(10), ErrorCode::CustodianLimitExceeded);
+}
+
+#[test]
+fn expired_custodian_approval_fails() {
+    let mut test = HookTest::whitelisted();
+    test.signer = Pubkey::new_unique();
+    test.add(
+        test.custodian_approval(),
+        &CustodianApproval {
+            owner: test.source_owner,
+            custodian: test.signer,
+            cap: 50,
+            spent: 0,
+            expires_at: NOW,
+        },
+    );
+    assert_error(test.run(10), ErrorCode::CustodianLimitExceeded);
+}
+
+#[test]
+fn transfers_before_launch_fail() {
+    let mut test = HookTest::whitelisted();
+    test.token_data.trading_starts_at = NOW + 1;
+    assert_error(test.run(10), ErrorCode::TradingNotStarted);
+
+    test.token_data.trading_starts_at = NOW;
+    test.run(10).unwrap();
+}
+
+#[test]
+fn authority_can_transfer_before_launch() {
+    let mut test = HookTest::whitelisted();
+    test.token_data.trading_starts_at = NOW + DAY;
+    test.token_data.authority = test.source_owner;
+    test.run(10).unwrap();
+}
+
+#[test]
+fn transfer_below_minimum_fails() {
+    let mut test = HookTest::whitelisted();
+    test.token_data.min_transfer_amount = 10;
+    assert_error(test.run(9), ErrorCode::TransferBelowMinimum);
+    test.run(10).unwrap();
+}
+
+#[test]
+fn emptying_the_source_passes_below_minimum() {
+    let mut test = HookTest::whitelisted();
+    test.token_data.min_transfer_amount = 10;
+    test.source_balance = 0;
+    test.run(3).unwrap();
+}
+
+#[test]
+fn destination_over_wallet_cap_fails() {
+    let mut test = HookTest::whitelisted();
+    test.token_data.max_wallet_amount = 100;
+    test.run(10).unwrap();
+
+    test.destination_balance = 101;
+    assert_error(test.run(10), ErrorCode::WalletCapExceeded);
+}
+
+#[test]
+fn wallet_cap_exempts_treasury_and_authority() {
+    let mut test = HookTest::whitelisted();
+    test.token_data.max_wallet_amount = 100;
+    test.destination_balance = 1_000;
+    test.token_data.wallet_cap_treasury = Some(test.destination_owner);
+    test.run(10).unwrap();
+
+    test.token_data.wallet_cap_treasury = None;
+    test.token_data.authority = test.destination_owner;
+    test.run(10).unwrap();
+}
+
+#[test]
+fn source_only_policy_checks_the_sender() {
+    let mut test = HookTest::new();
+    test.token_data.whitelist_policy = WhitelistPolicy::SourceOnly;
+    assert_error(test.run(10), ErrorCode::AddressNotWhitelisted);
+
+    test.list_on_page(test.source_owner);
+    test.run(10).unwrap();
+}
+
+#[test]
+fn both_policy_checks_both_ends() {
+    let mut test = HookTest::whitelisted();
+    test.token_data.whitelist_policy = WhitelistPolicy::Both;
+    assert_error(test.run(10), ErrorCode::AddressNotWhitelisted);
+
+    test.list_on_page(test.source_owner);
+    test.run(10).unwrap();
+}
+
+#[test]
+fn disabled_policy_skips_whitelists() {
+    let mut test = HookTest::new();
+    test.token_data.whitelist_policy = WhitelistPolicy::Disabled;
+    test.run(10).unwrap();
+}
+
+#[test]
+fn suspended_page_entry_fails() {
+    let mut test = HookTest::new();
+    let owner = test.destination_owner;
+    test.add(
+        test.page(&owner),
+        &Whitelist {
+            addresses: vec![owner],
+            suspended: vec![owner],
+        },
+    );
+    assert_error(test.run(10), ErrorCode::AddressNotWhitelisted);
+}
+
+#[test]
+fn page_owned_elsewhere_is_rejected() {
+    let mut test = HookTest::new();
+    let page = test.page(&test.destination_owner);
+    test.lookups.insert(
+        page,
+        foreign_account(
+            page,
+            &Whitelist {
+                addresses: vec![test.destination_owner],
+                suspended: Vec::new(),
+            },
+        ),
+    );
+    assert_error(test.run(10), ErrorCode::Unauthorized);
+}
+
+#[test]
+fn whitelist_entry_passes_until_it_expires() {
+    let mut test = HookTest::new();
+    let entry = test.entry(&test.destination_owner);
+    test.add(
+        entry,
+        &WhitelistEntry {
+            expires_at: None,
+            suspended: false,
+        },
+    );
+    test.run(10).unwrap();
+
+    test.add(
+        entry,
+        &WhitelistEntry {
+            expires_at: Some(NOW + 1),
+            suspended: false,
+        },
+    );
+    test.run(10).unwrap();
+
+    test.add(
+        entry,
+        &WhitelistEntry {
+            expires_at: Some(NOW),
+            suspended: false,
+        },
+    );
+    assert_error(test.run(10), ErrorCode::AddressNotWhitelisted);
+}
+
+#[test]
+fn suspended_whitelist_entry_fails_until_restored() {
+    let mut test = HookTest::new();
+    let entry = test.entry(&test.destination_owner);
+    test.add(
+        entry,
+        &WhitelistEntry {
+            expires_at: Some(NOW + 1),
+            suspended: true,
+        },
+    );
+    assert_error(test.run(10), ErrorCode::AddressNotWhitelisted);
+
+    test.add(
+        entry,
+        &WhitelistEntry {
+            expires_at: Some(NOW + 1),
+            suspended: false,
+        },
+    );
+    test.run(10).unwrap();
+}
+
+#[test]
+fn whitelisted_token_account_passes() {
+    let mut test = HookTest::new();
+    test.add_marker(test.token_account_entry(&test.destination));
+    test.run(10).unwrap();
+
+    // The entry covers that one account, not every account of its owner
+    test.destination = Pubkey::new_unique();
+    assert_error(test.run(10), ErrorCode::AddressNotWhitelisted);
+}
+
+#[test]
+fn held_credential_passes() {
+    let mut test = HookTest::new();
+    let owner = test.destination_owner;
+    let key = test.credential(&owner);
+    test.lookups.insert(
+        key,
+        token_account(key, Pubkey::new_unique(), owner, 1, false),
+    );
+    test.run(10).unwrap();
+}
+
+#[test]
+fn empty_or_frozen_credential_fails() {
+    let mut test = HookTest::new();
+    let owner = test.destination_owner;
+    let key = test.credential(&owner);
+    test.lookups.insert(
+        key,
+        token_account(key, Pubkey::new_unique(), owner, 0, false),
+    );
+    assert_error(test.run(10), ErrorCode::AddressNotWhitelisted);
+
+    test.lookups.insert(
+        key,
+        token_account(key, Pubkey::new_unique(), owner, 1, true),
+    );
+    assert_error(test.run(10), ErrorCode::AddressNotWhitelisted);
+}
+
+#[test]
+fn permit_lets_an_unlisted_destination_receive() {
+    let mut test = HookTest::new();
+    let permit = test.add(
+        test.permit(),
+        &TransferPermit {
+            remaining: 25,
+            expires_at: NOW + DAY,
+        },
+    );
+    test.run(20).unwrap();
+    assert_eq!(read_account::<TransferPermit>(permit).remaining, 5);
+
+    assert_error(test.run(6), ErrorCode::AddressNotWhitelisted);
+    assert_eq!(read_account::<TransferPermit>(permit).remaining, 5);
+}
+
+#[test]
+fn expired_permit_fails() {
+    let mut test = HookTest::new();
+    test.add(
+        test.permit(),
+        &TransferPermit {
+            remaining: 25,
+            expires_at: NOW,
+        },
+    );
+    assert_error(test.run(10), ErrorCode::AddressNotWhitelisted);
+}
+
+#[test]
+fn daily_limit_counts_volume_across_transfers() {
+    let mut test = HookTest::whitelisted();
+    test.token_data.daily_transfer_limit = 100;
+    let window = test.add(
+        test.transfer_window(),
+        &TransferWindow {
+            payer: test.source_owner,
+            window_start: NOW - 60,
+            volume: 60,
+        },
+    );
+    test.run(40).unwrap();
+    assert_eq!(read_account::<TransferWindow>(window).volume, 100);
+
+    assert_error(test.run(1), ErrorCode::DailyTransferLimitExceeded);
+}
+
+#[test]
+fn daily_limit_resets_after_a_day() {
+    let mut test = HookTest::whitelisted();
+    test.token_data.daily_transfer_limit = 100;
+    let window = test.add(
+        test.transfer_window(),
+        &TransferWindow {
+            payer: test.source_owner,
+            window_start: NOW - DAY,
+            volume: 100,
+        },
+    );
+    test.run(30).unwrap();
+    let window = read_account::<TransferWindow>(window);
+    assert_eq!((window.window_start, window.volume), (NOW, 30));
+}
+
+#[test]
+fn daily_limit_needs_a_transfer_window() {
+    let mut test = HookTest::whitelisted();
+    test.token_data.daily_transfer_limit = 100;
+    assert_error(test.run(10), ErrorCode::DailyTransferLimitExceeded);
+
+    test.token_data.authority = test.source_owner;
+    test.run(10).unwrap();
+}
+
+#[test]
+fn circuit_breaker_pauses_after_the_tripping_transfer() {
+    let mut test = HookTest::whitelisted();
+    test.add(
+        test.velocity_tracker(),
+        &VelocityTracker {
+            window_slots: 100,
+            max_transfers: 0,
+            max_volume: 1_000,
+            window_start_slot: SLOT,
+            transfers: 3,
+            volume: 990,
+        },
+    );
+    assert!(!test.run(10).unwrap().is_paused);
+    assert!(test.run(1).unwrap().is_paused);
+}
+
+#[test]
+fn circuit_breaker_leaves_a_locked_pause_alone() {
+    let mut test = HookTest::whitelisted();
+    test.token_data.config_lock = LOCK_PAUSE;
+    let tracker = test.add(
+        test.velocity_tracker(),
+        &VelocityTracker {
+            window_slots: 100,
+            max_transfers: 1,
+            max_volume: 0,
+            window_start_slot: SLOT,
+            transfers: 1,
+            volume: 0,
+        },
+    );
+    assert!(!test.run(10).unwrap().is_paused);
+    assert_eq!(read_account::<VelocityTracker>(tracker).transfers, 2);
+}
+
+#[test]
+fn outbound_transfer_contests_dormancy_notice() {
+    let mut test = HookTest::whitelisted();
+    let notice = test.add(
+        test.dormancy_notice(),
+        &DormancyNotice {
+            mint: test.mint,
+            recoverable_at: NOW + DAY,
+            contested: false,
+            raw_amount: 500,
+        },
+    );
+    test.run(10).unwrap();
+    assert!(read_account::<DormancyNotice>(notice).contested);
+}
+
+#[test]
+fn outbound_transfer_records_activity() {
+    let mut test = HookTest::whitelisted();
+    let record = test.add(
+        test.activity_record(),
+        &ActivityRecord {
+            mint: test.mint,
+            last_activity_at: NOW - DAY,
+        },
+    );
+    test.run(10).unwrap();
+    assert_eq!(read_account::<ActivityRecord>(record).last_activity_at, NOW);
+}
+
+#[test]
+fn mirrored_token_reads_the_source_pages() {
+    let mut test = HookTest::new();
+    test.token_data.whitelist_mint = Pubkey::new_unique();
+    test.list_on_page(test.destination_owner);
+    test.run(10).unwrap();
+
+    // The mirror's own page no longer counts
+    test.lookups.clear();
+    let own_page = pda(&[
+        b"whitelist",
+        test.mint.as_ref(),
+        &[whitelist_page_of(&test.destination_owner)],
+    ]);
+    test.add(
+        own_page,
+        &Whitelist {
+            addresses: vec![test.destination_owner],
+            suspended: Vec::new(),
+        },
+    );
+    assert_error(test.run(10), ErrorCode::AddressNotWhitelisted);
+}
+
+#[test]
+fn factory_entry_needs_a_factory_source() {
+    let mut test = HookTest::new();
+    test.add(
+        test.factory_entry(&test.destination_owner),
+        &WhitelistEntry {
+            expires_at: None,
+            suspended: false,
+        },
+    );
+    assert_error(test.run(10), ErrorCode::AddressNotWhitelisted);
+
+    test.token_data.whitelist_source = WhitelistSource::Either;
+    test.run(10).unwrap();
+    test.token_data.whitelist_source = WhitelistSource::FactoryOnly;
+    test.run(10).unwrap();
+}
+
+#[test]
+fn factory_only_source_ignores_token_lists() {
+    let mut test = HookTest::whitelisted();
+    test.token_data.whitelist_source = WhitelistSource::FactoryOnly;
+    assert_error(test.run(10), ErrorCode::AddressNotWhitelisted);
+}
+
+#[test]
+fn expired_factory_entry_fails() {
+    let mut test = HookTest::new();
+    test.token_data.whitelist_source = WhitelistSource::FactoryOnly;
+    test.add(
+        test.factory_entry(&test.destination_owner),
+        &WhitelistEntry {
+            expires_at: Some(NOW - 1),
+            suspended: false,
+        },
+    );
+    assert_error(test.run(10), ErrorCode::AddressNotWhitelisted);
+}
+
+#[test]
+fn nft_pass_for_the_gate_collection_passes() {
+    let mut test = HookTest::new();
+    let collection = Pubkey::new_unique();
+    test.token_data.nft_gate_collection = Some(collection);
+    test.add_nft_pass(test.destination_owner, collection, 1);
+    test.run(10).unwrap();
+
+    test.token_data.nft_gate_collection = None;
+    assert_error(test.run(10), ErrorCode::AddressNotWhitelisted);
+}
+
+#[test]
+fn nft_pass_for_a_replaced_collection_fails() {
+    let mut test = HookTest::new();
+    test.token_data.nft_gate_collection = Some(Pubkey::new_unique());
+    test.add_nft_pass(test.destination_owner, Pubkey::new_unique(), 1);
+    assert_error(test.run(10), ErrorCode::AddressNotWhitelisted);
+}
+
+#[test]
+fn nft_pass_whose_nft_moved_out_fails() {
+    let mut test = HookTest::new();
+    let collection = Pubkey::new_unique();
+    test.token_data.nft_gate_collection = Some(collection);
+    test.add_nft_pass(test.destination_owner, collection, 0);
+    assert_error(test.run(10), ErrorCode::AddressNotWhitelisted);
+
+    // Closing the account doesn't leave the pass standing either
+    test.lookups
+        .remove(&test.nft_pass_account(&test.destination_owner));
+    assert_error(test.run(10), ErrorCode::AddressNotWhitelisted);
+}
+
+#[test]
+fn nft_pass_account_handed_to_another_owner_fails() {
+    let mut test = HookTest::new();
+    let collection = Pubkey::new_unique();
+    test.token_data.nft_gate_collection = Some(collection);
+    test.add_nft_pass(test.destination_owner, collection, 1);
+    let key = test.nft_pass_account(&test.destination_owner);
+    let pass = read_account::<NftPass>(test.lookups[&test.nft_pass(&test.destination_owner)]);
+    test.lookups.insert(
+        key,
+        token_account(key, pass.nft_mint, Pubkey::new_unique(), 1, false),
+    );
+    assert_error(test.run(10), ErrorCode::AddressNotWhitelisted);
+}
+
+#[test]
+fn lookup_at_the_wrong_address_is_rejected() {
+    let mut test = HookTest::whitelisted();
+    let page = test.page(&test.destination_owner);
+    let info = test.lookups.remove(&page).unwrap();
+    let wrong = Pubkey::new_unique();
+    test.lookups.insert(
+        page,
+        program_account(wrong, &read_account::<Whitelist>(info)),
+    );
+    assert!(test.run(10).is_err());
+}
+
+/// A destination receiving a mint or vault payout instead of a transfer.
+struct ReceiverTest {
+    hook: HookTest,
+    raw_amount: u64,
+    /// Passed in place of the owner's whitelist page
+    misplaced_page: Option<&'static AccountInfo<'static>>,
+}
+
+impl ReceiverTest {
+    fn new() -> Self {
+        Self {
+            hook: HookTest::new(),
+            raw_amount: 10,
+            misplaced_page: None,
+        }
+    }
+
+    fn whitelisted() -> Self {
+        Self {
+            hook: HookTest::whitelisted(),
+            raw_amount: 10,
+            misplaced_page: None,
+        }
+    }
+
+    fn run(&self) -> Result<()> {
+        let test = &self.hook;
+        let owner = test.destination_owner;
+        let lookup = |key| unchecked(Box::leak(Box::new(test.lookup(key))));
+        let receiver = ReceiverChecks {
+            blacklist_entry: lookup(test.blacklist(&owner)),
+            whitelist: match self.misplaced_page {
+                Some(page) => unchecked(page),
+                None => lookup(test.page(&owner)),
+            },
+            token_account_entry: lookup(test.token_account_entry(&test.destination)),
+            whitelist_entry: lookup(test.entry(&owner)),
+            credential: lookup(test.credential(&owner)),
+            factory_whitelist_entry: lookup(test.factory_entry(&owner)),
+            nft_pass: lookup(test.nft_pass(&owner)),
+            nft_pass_account: lookup(test.nft_pass_account(&owner)),
+        };
+        let destination = InterfaceAccount::try_from(token_account(
+            test.destination,
+            test.mint,
+            owner,
+            test.destination_balance,
+            false,
+        ))?;
+        receiver.require_can_receive(&test.token_data, &destination, self.raw_amount, NOW)
+    }
+}
+
+#[test]
+fn whitelisted_receiver_passes() {
+    ReceiverTest::whitelisted().run().unwrap();
+}
+
+#[test]
+fn unlisted_receiver_fails() {
+    assert_error(ReceiverTest::new().run(), ErrorCode::AddressNotWhitelisted);
+}
+
+#[test]
+fn receiver_of_a_paused_token_fails() {
+    let mut test = ReceiverTest::whitelisted();
+    test.hook.token_data.is_paused = true;
+    assert_error(test.run(), ErrorCode::TokenPaused);
+}
+
+#[test]
+fn blacklisted_receiver_fails() {
+    let mut test = ReceiverTest::whitelisted();
+    let hook = &mut test.hook;
+    hook.add_marker(hook.blacklist(&hook.destination_owner));
+    assert_error(test.run(), ErrorCode::AddressBlacklisted);
+}
+
+#[test]
+fn receiver_is_held_to_the_wallet_cap() {
+    let mut test = ReceiverTest::whitelisted();
+    test.hook.token_data.max_wallet_amount = 110;
+    test.run().unwrap();
+
+    // The payout counts toward the cap, unlike a transfer's
+    test.raw_amount = 11;
+    assert_error(test.run(), ErrorCode::WalletCapExceeded);
+}
+
+#[test]
+fn receiver_whitelisting_follows_the_policy() {
+    let mut test = ReceiverTest::new();
+    test.hook.token_data.whitelist_policy = WhitelistPolicy::SourceOnly;
+    test.run().unwrap();
+
+    let hook = &mut test.hook;
+    hook.token_data.whitelist_policy = WhitelistPolicy::DestinationOnly;
+    hook.token_data.whitelist_source = WhitelistSource::FactoryOnly;
+    hook.add(
+        hook.factory_entry(&hook.destination_owner),
+        &WhitelistEntry {
+            expires_at: None,
+            suspended: false,
+        },
+    );
+    test.run().unwrap();
+}
+
+#[test]
+fn receiver_lookup_at_the_wrong_address_is_rejected() {
+    let mut test = ReceiverTest::new();
+    // A page that lists the owner, but isn't the one its address maps to
+    test.misplaced_page = Some(program_account(
+        Pubkey::new_unique(),
+        &Whitelist {
+            addresses: vec![test.hook.destination_owner],
+            suspended: Vec::new(),
+        },
+    ));
+    assert_error(test.run(), ErrorCode::Unauthorized);
+}
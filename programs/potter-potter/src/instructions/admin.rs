@@ -0,0 +1,1005 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+
+pub fn handle_pause_minting(
+    ctx: Context<PauseMintingCTX>,
+    idempotency_key: Option<[u8; 16]>,
+) -> Result<()> {
+    if is_duplicate_request(&mut ctx.accounts.idempotency_log, idempotency_key)? {
+        return Ok(());
+    }
+    let paused = !ctx.accounts.token_data.is_minting_paused;
+    let action = AdminAction::SetMintingPaused { paused };
+    require_not_timelocked(&ctx.accounts.timelock, &action)?;
+    let authority = ctx.accounts.authority.key();
+    let event = apply_admin_action(
+        &ctx.accounts.program_config,
+        &mut ctx.accounts.token_data,
+        &mut ctx.accounts.hook_manifest,
+        authority,
+        action,
+    )?;
+    emit_cpi!(event);
+    Ok(())
+}
+
+pub fn handle_pause_token(
+    ctx: Context<PauseTokenCTX>,
+    idempotency_key: Option<[u8; 16]>,
+) -> Result<()> {
+    if is_duplicate_request(&mut ctx.accounts.idempotency_log, idempotency_key)? {
+        return Ok(());
+    }
+    let paused = !ctx.accounts.token_data.is_paused;
+    let action = AdminAction::SetPaused { paused };
+    require_not_timelocked(&ctx.accounts.timelock, &action)?;
+    let authority = ctx.accounts.authority.key();
+    let event = apply_admin_action(
+        &ctx.accounts.program_config,
+        &mut ctx.accounts.token_data,
+        &mut ctx.accounts.hook_manifest,
+        authority,
+        action,
+    )?;
+    emit_cpi!(event);
+    Ok(())
+}
+
+pub fn handle_propose_authority(
+    ctx: Context<ProposeAuthorityCTX>,
+    new_authority: Pubkey,
+) -> Result<()> {
+    let action = AdminAction::ProposeAuthority { new_authority };
+    require_not_timelocked(&ctx.accounts.timelock, &action)?;
+    let authority = ctx.accounts.authority.key();
+    let event = apply_admin_action(
+        &ctx.accounts.program_config,
+        &mut ctx.accounts.token_data,
+        &mut ctx.accounts.hook_manifest,
+        authority,
+        action,
+    )?;
+    emit_cpi!(event);
+    Ok(())
+}
+
+pub fn handle_accept_authority(ctx: Context<AcceptAuthorityCTX>) -> Result<()> {
+    let token_data = &mut ctx.accounts.token_data;
+    // The lock may have been set after the proposal was made
+    token_data.require_unlocked(LOCK_AUTHORITY)?;
+
+    // The mint authority PDA is seeded by `creator`, so it signs for the
+    // new authority unchanged
+    let old_authority = token_data.authority;
+    let new_authority = ctx.accounts.new_authority.key();
+    token_data.authority = new_authority;
+    token_data.pending_authority = None;
+
+    msg!(
+        "Authority transferred from {} to {}",
+        old_authority,
+        new_authority
+    );
+    emit_cpi!(AuthorityAccepted {
+        mint: token_data.mint,
+        old_authority,
+        new_authority,
+    });
+    Ok(())
+}
+
+pub fn handle_cancel_authority_transfer(ctx: Context<CancelAuthorityTransferCTX>) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let event = apply_admin_action(
+        &ctx.accounts.program_config,
+        &mut ctx.accounts.token_data,
+        &mut ctx.accounts.hook_manifest,
+        authority,
+        AdminAction::CancelAuthorityTransfer,
+    )?;
+    emit_cpi!(event);
+    Ok(())
+}
+
+pub fn handle_lock_config(ctx: Context<LockConfigCTX>, mask: u16) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let event = apply_admin_action(
+        &ctx.accounts.program_config,
+        &mut ctx.accounts.token_data,
+        &mut ctx.accounts.hook_manifest,
+        authority,
+        AdminAction::LockConfig { mask },
+    )?;
+    emit_cpi!(event);
+    Ok(())
+}
+
+pub fn handle_admin_execute(ctx: Context<AdminExecuteCTX>, action: AdminAction) -> Result<()> {
+    // No lock bit covers the cap, so it only moves through a queued action,
+    // even for tokens that have no timelock yet
+    require!(
+        !matches!(action, AdminAction::SetMaxSupply { .. }),
+        ErrorCode::ActionTimelocked
+    );
+    require_not_timelocked(&ctx.accounts.timelock, &action)?;
+    let authority = ctx.accounts.authority.key();
+    let event = apply_admin_action(
+        &ctx.accounts.program_config,
+        &mut ctx.accounts.token_data,
+        &mut ctx.accounts.hook_manifest,
+        authority,
+        action,
+    )?;
+    emit_cpi!(event);
+    Ok(())
+}
+
+pub fn handle_set_token_account_whitelisting(
+    ctx: Context<SetTokenAccountWhitelistingCTX>,
+    enabled: bool,
+) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let event = apply_admin_action(
+        &ctx.accounts.program_config,
+        &mut ctx.accounts.token_data,
+        &mut ctx.accounts.hook_manifest,
+        authority,
+        AdminAction::SetTokenAccountWhitelisting { enabled },
+    )?;
+    emit_cpi!(event);
+    Ok(())
+}
+
+pub fn handle_set_metadata_delay(
+    ctx: Context<SetMetadataDelayCTX>,
+    delay_seconds: i64,
+) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let event = apply_admin_action(
+        &ctx.accounts.program_config,
+        &mut ctx.accounts.token_data,
+        &mut ctx.accounts.hook_manifest,
+        authority,
+        AdminAction::SetMetadataDelay { delay_seconds },
+    )?;
+    emit_cpi!(event);
+    Ok(())
+}
+
+pub fn handle_set_debug_telemetry(ctx: Context<SetDebugTelemetryCTX>, enabled: bool) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let event = apply_admin_action(
+        &ctx.accounts.program_config,
+        &mut ctx.accounts.token_data,
+        &mut ctx.accounts.hook_manifest,
+        authority,
+        AdminAction::SetDebugTelemetry { enabled },
+    )?;
+    emit_cpi!(event);
+    Ok(())
+}
+
+pub fn handle_set_whitelist_root(
+    ctx: Context<SetWhitelistRootCTX>,
+    root: Option<[u8; 32]>,
+) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let event = apply_admin_action(
+        &ctx.accounts.program_config,
+        &mut ctx.accounts.token_data,
+        &mut ctx.accounts.hook_manifest,
+        authority,
+        AdminAction::SetWhitelistRoot { root },
+    )?;
+    emit_cpi!(event);
+    Ok(())
+}
+
+pub fn handle_set_min_transfer_amount(
+    ctx: Context<SetMinTransferAmountCTX>,
+    min_amount: u64,
+) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let event = apply_admin_action(
+        &ctx.accounts.program_config,
+        &mut ctx.accounts.token_data,
+        &mut ctx.accounts.hook_manifest,
+        authority,
+        AdminAction::SetMinTransferAmount { min_amount },
+    )?;
+    emit_cpi!(event);
+    Ok(())
+}
+
+pub fn handle_set_whitelist_policy(
+    ctx: Context<SetWhitelistPolicyCTX>,
+    policy: WhitelistPolicy,
+) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let event = apply_admin_action(
+        &ctx.accounts.program_config,
+        &mut ctx.accounts.token_data,
+        &mut ctx.accounts.hook_manifest,
+        authority,
+        AdminAction::SetWhitelistPolicy { policy },
+    )?;
+    emit_cpi!(event);
+    Ok(())
+}
+
+pub fn handle_set_whitelist_source(
+    ctx: Context<SetWhitelistSourceCTX>,
+    source: WhitelistSource,
+) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let event = apply_admin_action(
+        &ctx.accounts.program_config,
+        &mut ctx.accounts.token_data,
+        &mut ctx.accounts.hook_manifest,
+        authority,
+        AdminAction::SetWhitelistSource { source },
+    )?;
+    emit_cpi!(event);
+    Ok(())
+}
+
+pub fn handle_set_nft_gate_collection(
+    ctx: Context<SetNftGateCollectionCTX>,
+    collection: Option<Pubkey>,
+) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let event = apply_admin_action(
+        &ctx.accounts.program_config,
+        &mut ctx.accounts.token_data,
+        &mut ctx.accounts.hook_manifest,
+        authority,
+        AdminAction::SetNftGateCollection { collection },
+    )?;
+    emit_cpi!(event);
+    Ok(())
+}
+
+pub fn handle_set_max_wallet_amount(
+    ctx: Context<SetMaxWalletAmountCTX>,
+    max_amount: u64,
+    treasury: Option<Pubkey>,
+) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let event = apply_admin_action(
+        &ctx.accounts.program_config,
+        &mut ctx.accounts.token_data,
+        &mut ctx.accounts.hook_manifest,
+        authority,
+        AdminAction::SetWalletCap {
+            max: max_amount,
+            treasury,
+        },
+    )?;
+    emit_cpi!(event);
+    Ok(())
+}
+
+pub fn handle_set_daily_transfer_limit(
+    ctx: Context<SetDailyTransferLimitCTX>,
+    limit: u64,
+) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let event = apply_admin_action(
+        &ctx.accounts.program_config,
+        &mut ctx.accounts.token_data,
+        &mut ctx.accounts.hook_manifest,
+        authority,
+        AdminAction::SetDailyTransferLimit { limit },
+    )?;
+    emit_cpi!(event);
+    Ok(())
+}
+
+pub fn handle_set_trading_starts_at(
+    ctx: Context<SetTradingStartsAtCTX>,
+    starts_at: i64,
+) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let event = apply_admin_action(
+        &ctx.accounts.program_config,
+        &mut ctx.accounts.token_data,
+        &mut ctx.accounts.hook_manifest,
+        authority,
+        AdminAction::SetTradingStartsAt { starts_at },
+    )?;
+    emit_cpi!(event);
+    Ok(())
+}
+
+pub fn handle_set_burn_allowed_while_paused(
+    ctx: Context<SetBurnAllowedWhilePausedCTX>,
+    allowed: bool,
+) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let event = apply_admin_action(
+        &ctx.accounts.program_config,
+        &mut ctx.accounts.token_data,
+        &mut ctx.accounts.hook_manifest,
+        authority,
+        AdminAction::SetBurnAllowedWhilePaused { allowed },
+    )?;
+    emit_cpi!(event);
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct PauseMintingCTX<'info> {
+    #[account(
+        mut,
+        constraint = token_data.authority == authority.key() || role.is_some() @ ErrorCode::Unauthorized
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"idempotency", token_data.key().as_ref()],
+        bump
+    )]
+    pub idempotency_log: Option<Account<'info, IdempotencyLog>>,
+
+    #[account(
+        mut,
+        seeds = [b"hook_manifest", token_data.mint.as_ref()],
+        bump
+    )]
+    pub hook_manifest: Option<Account<'info, HookManifest>>,
+
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    /// CHECK: Timelock config, only checked for existence
+    #[account(
+        seeds = [b"timelock", token_data.mint.as_ref()],
+        bump
+    )]
+    pub timelock: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+
+    /// Lets a `Pauser` act in place of the token authority
+    #[account(
+        seeds = [b"role", token_data.key().as_ref(), authority.key().as_ref(), &[Role::Pauser as u8]],
+        bump
+    )]
+    pub role: Option<Account<'info, RoleGrant>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct PauseTokenCTX<'info> {
+    #[account(
+        mut,
+        constraint = token_data.authority == authority.key() || role.is_some() @ ErrorCode::Unauthorized
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"idempotency", token_data.key().as_ref()],
+        bump
+    )]
+    pub idempotency_log: Option<Account<'info, IdempotencyLog>>,
+
+    #[account(
+        mut,
+        seeds = [b"hook_manifest", token_data.mint.as_ref()],
+        bump
+    )]
+    pub hook_manifest: Option<Account<'info, HookManifest>>,
+
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    /// CHECK: Timelock config, only checked for existence
+    #[account(
+        seeds = [b"timelock", token_data.mint.as_ref()],
+        bump
+    )]
+    pub timelock: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+
+    /// Lets a `Pauser` act in place of the token authority
+    #[account(
+        seeds = [b"role", token_data.key().as_ref(), authority.key().as_ref(), &[Role::Pauser as u8]],
+        bump
+    )]
+    pub role: Option<Account<'info, RoleGrant>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeAuthorityCTX<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"hook_manifest", token_data.mint.as_ref()],
+        bump
+    )]
+    pub hook_manifest: Option<Account<'info, HookManifest>>,
+
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    /// CHECK: Timelock config, only checked for existence
+    #[account(
+        seeds = [b"timelock", token_data.mint.as_ref()],
+        bump
+    )]
+    pub timelock: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptAuthorityCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        constraint = token_data.pending_authority == Some(new_authority.key()) @ ErrorCode::NotPendingAuthority
+    )]
+    pub token_data: Account<'info, TokenData>,
+    pub new_authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelAuthorityTransferCTX<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"hook_manifest", token_data.mint.as_ref()],
+        bump
+    )]
+    pub hook_manifest: Option<Account<'info, HookManifest>>,
+
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct LockConfigCTX<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"hook_manifest", token_data.mint.as_ref()],
+        bump
+    )]
+    pub hook_manifest: Option<Account<'info, HookManifest>>,
+
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AdminExecuteCTX<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"hook_manifest", token_data.mint.as_ref()],
+        bump
+    )]
+    pub hook_manifest: Option<Account<'info, HookManifest>>,
+
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    /// CHECK: Timelock config, only checked for existence
+    #[account(
+        seeds = [b"timelock", token_data.mint.as_ref()],
+        bump
+    )]
+    pub timelock: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetTokenAccountWhitelistingCTX<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"hook_manifest", token_data.mint.as_ref()],
+        bump
+    )]
+    pub hook_manifest: Option<Account<'info, HookManifest>>,
+
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetMetadataDelayCTX<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"hook_manifest", token_data.mint.as_ref()],
+        bump
+    )]
+    pub hook_manifest: Option<Account<'info, HookManifest>>,
+
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetDebugTelemetryCTX<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"hook_manifest", token_data.mint.as_ref()],
+        bump
+    )]
+    pub hook_manifest: Option<Account<'info, HookManifest>>,
+
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetWhitelistRootCTX<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"hook_manifest", token_data.mint.as_ref()],
+        bump
+    )]
+    pub hook_manifest: Option<Account<'info, HookManifest>>,
+
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetMinTransferAmountCTX<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"hook_manifest", token_data.mint.as_ref()],
+        bump
+    )]
+    pub hook_manifest: Option<Account<'info, HookManifest>>,
+
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetWhitelistPolicyCTX<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"hook_manifest", token_data.mint.as_ref()],
+        bump
+    )]
+    pub hook_manifest: Option<Account<'info, HookManifest>>,
+
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetWhitelistSourceCTX<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"hook_manifest", token_data.mint.as_ref()],
+        bump
+    )]
+    pub hook_manifest: Option<Account<'info, HookManifest>>,
+
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetNftGateCollectionCTX<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"hook_manifest", token_data.mint.as_ref()],
+        bump
+    )]
+    pub hook_manifest: Option<Account<'info, HookManifest>>,
+
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetMaxWalletAmountCTX<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"hook_manifest", token_data.mint.as_ref()],
+        bump
+    )]
+    pub hook_manifest: Option<Account<'info, HookManifest>>,
+
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetDailyTransferLimitCTX<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"hook_manifest", token_data.mint.as_ref()],
+        bump
+    )]
+    pub hook_manifest: Option<Account<'info, HookManifest>>,
+
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetTradingStartsAtCTX<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"hook_manifest", token_data.mint.as_ref()],
+        bump
+    )]
+    pub hook_manifest: Option<Account<'info, HookManifest>>,
+
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetBurnAllowedWhilePausedCTX<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"hook_manifest", token_data.mint.as_ref()],
+        bump
+    )]
+    pub hook_manifest: Option<Account<'info, HookManifest>>,
+
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AdminAction {
+    SetPaused {
+        paused: bool,
+    },
+    SetMintingPaused {
+        paused: bool,
+    },
+    ProposeAuthority {
+        new_authority: Pubkey,
+    },
+    CancelAuthorityTransfer,
+    LockConfig {
+        mask: u16,
+    },
+    SetTokenAccountWhitelisting {
+        enabled: bool,
+    },
+    SetMetadataDelay {
+        delay_seconds: i64,
+    },
+    SetDebugTelemetry {
+        enabled: bool,
+    },
+    SetWhitelistRoot {
+        root: Option<[u8; 32]>,
+    },
+    SetMinTransferAmount {
+        min_amount: u64,
+    },
+    SetWhitelistPolicy {
+        policy: WhitelistPolicy,
+    },
+    SetWhitelistSource {
+        source: WhitelistSource,
+    },
+    SetNftGateCollection {
+        collection: Option<Pubkey>,
+    },
+    SetBurnAllowedWhilePaused {
+        allowed: bool,
+    },
+    SetWalletCap {
+        max: u64,
+        treasury: Option<Pubkey>,
+    },
+    SetDailyTransferLimit {
+        limit: u64,
+    },
+    SetTradingStartsAt {
+        starts_at: i64,
+    },
+    /// Only reachable through the timelock
+    SetMaxSupply {
+        max_supply: Option<u64>,
+    },
+}
+
+impl AdminAction {
+    /// Actions that have to wait out the token's `TimelockConfig` delay.
+    /// Pausing stays immediate for incidents; only unpausing is delayed.
+    pub fn is_timelocked(&self) -> bool {
+        matches!(
+            self,
+            AdminAction::SetPaused { paused: false }
+                | AdminAction::SetMintingPaused { paused: false }
+                | AdminAction::ProposeAuthority { .. }
+                | AdminAction::SetMaxSupply { .. }
+        )
+    }
+}
+
+pub(crate) fn apply_admin_action(
+    config: &ProgramConfig,
+    token_data: &mut TokenData,
+    hook_manifest: &mut Option<Account<HookManifest>>,
+    authority: Pubkey,
+    action: AdminAction,
+) -> Result<AdminActionApplied> {
+    config.require_feature_enabled(FEATURE_ADMIN)?;
+    match action {
+        AdminAction::SetPaused { paused } => {
+            token_data.require_unlocked(LOCK_PAUSE)?;
+            token_data.is_paused = paused;
+            msg!("Token paused: {}", paused);
+        }
+        AdminAction::SetMintingPaused { paused } => {
+            token_data.require_unlocked(LOCK_PAUSE)?;
+            token_data.is_minting_paused = paused;
+            msg!("Minting paused: {}", paused);
+        }
+        AdminAction::ProposeAuthority { new_authority } => {
+            token_data.require_unlocked(LOCK_AUTHORITY)?;
+            // A new proposal replaces any outstanding one
+            token_data.pending_authority = Some(new_authority);
+            msg!("Authority transfer proposed to {}", new_authority);
+        }
+        AdminAction::CancelAuthorityTransfer => {
+            token_data.pending_authority = None;
+            msg!("Authority transfer cancelled");
+        }
+        AdminAction::LockConfig { mask } => {
+            require!(
+                mask != 0 && mask & !LOCK_ALL == 0,
+                ErrorCode::InvalidLockMask
+            );
+            // Locks are one-way: bits can be added but never cleared
+            token_data.config_lock |= mask;
+            msg!("Config lock set to {:#06x}", token_data.config_lock);
+        }
+        AdminAction::SetTokenAccountWhitelisting { enabled } => {
+            token_data.require_unlocked(LOCK_WHITELIST)?;
+            // The hook honours entries regardless, so they must go first
+            require!(
+                enabled || token_data.token_account_entries == 0,
+                ErrorCode::TokenAccountEntriesExist
+            );
+            token_data.token_account_whitelisting = enabled;
+            msg!("Token account whitelisting: {}", enabled);
+        }
+        AdminAction::SetMetadataDelay { delay_seconds } => {
+            token_data.require_unlocked(LOCK_METADATA)?;
+            // Shortening the delay would let changes skip the notice period
+            require!(
+                delay_seconds >= token_data.metadata_delay,
+                ErrorCode::MetadataDelayDecrease
+            );
+            token_data.metadata_delay = delay_seconds;
+            msg!("Metadata update delay set to {}s", delay_seconds);
+        }
+        AdminAction::SetDebugTelemetry { enabled } => {
+            // Diagnostics only, so not behind any config lock
+            token_data.debug_telemetry = enabled;
+            msg!("Hook debug telemetry: {}", enabled);
+        }
+        AdminAction::SetWhitelistRoot { root } => {
+            token_data.require_unlocked(LOCK_WHITELIST)?;
+            // Entries already claimed stay until removed individually
+            token_data.whitelist_root = root;
+            msg!("Whitelist Merkle root set: {}", root.is_some());
+        }
+        AdminAction::SetMinTransferAmount { min_amount } => {
+            token_data.require_unlocked(LOCK_TRANSFER_RULES)?;
+            token_data.min_transfer_amount = min_amount;
+            msg!("Minimum transfer amount set to {} base units", min_amount);
+        }
+        AdminAction::SetWhitelistPolicy { policy } => {
+            token_data.require_unlocked(LOCK_WHITELIST)?;
+            token_data.whitelist_policy = policy;
+            msg!("Whitelist policy set to {:?}", policy);
+        }
+        AdminAction::SetWhitelistSource { source } => {
+            token_data.require_unlocked(LOCK_WHITELIST)?;
+            token_data.whitelist_source = source;
+            msg!("Whitelist source set to {:?}", source);
+        }
+        AdminAction::SetNftGateCollection { collection } => {
+            token_data.require_unlocked(LOCK_WHITELIST)?;
+            // Passes verified against a previous collection stop counting
+            token_data.nft_gate_collection = collection;
+            msg!("NFT gate collection set to {:?}", collection);
+        }
+        AdminAction::SetBurnAllowedWhilePaused { allowed } => {
+            token_data.require_unlocked(LOCK_PAUSE)?;
+            token_data.burn_allowed_while_paused = allowed;
+            msg!("Burning allowed while paused: {}", allowed);
+        }
+        AdminAction::SetWalletCap { max, treasury } => {
+            token_data.require_unlocked(LOCK_TRANSFER_RULES)?;
+            // Wallets already above a new cap keep their balance but can't
+            // receive more
+            token_data.max_wallet_amount = max;
+            token_data.wallet_cap_treasury = treasury;
+            msg!("Max wallet amount set to {} base units", max);
+        }
+        AdminAction::SetDailyTransferLimit { limit } => {
+            token_data.require_unlocked(LOCK_TRANSFER_RULES)?;
+            token_data.daily_transfer_limit = limit;
+            msg!("Daily transfer limit set to {} base units", limit);
+        }
+        AdminAction::SetTradingStartsAt { starts_at } => {
+            token_data.require_unlocked(LOCK_TRANSFER_RULES)?;
+            token_data.trading_starts_at = starts_at;
+            msg!("Trading starts at {}", starts_at);
+        }
+        AdminAction::SetMaxSupply { max_supply } => {
+            require!(
+                max_supply.is_none_or(|max| max >= token_data.total_supply),
+                ErrorCode::SupplyCapExceeded
+            );
+            token_data.max_supply = max_supply;
+            msg!("Max supply set to {:?}", max_supply);
+        }
+    }
+    if let Some(manifest) = hook_manifest {
+        manifest.refresh(token_data)?;
+    }
+    Ok(AdminActionApplied {
+        mint: token_data.mint,
+        authority,
+        action,
+    })
+}
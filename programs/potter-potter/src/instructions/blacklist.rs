@@ -0,0 +1,101 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+// Unlike whitelist changes, not behind `LOCK_WHITELIST`: blocking a bad
+// actor has to stay possible on a token whose whitelist is locked
+pub fn handle_blacklist_address(ctx: Context<BlacklistAddressCTX>, address: Pubkey) -> Result<()> {
+    msg!("Address blacklisted: {}", address);
+    emit_cpi!(BlacklistUpdated {
+        mint: ctx.accounts.token_data.mint,
+        address,
+        blacklisted: true,
+    });
+    Ok(())
+}
+
+pub fn handle_unblacklist_address(
+    ctx: Context<UnblacklistAddressCTX>,
+    address: Pubkey,
+) -> Result<()> {
+    msg!("Address removed from blacklist: {}", address);
+    emit_cpi!(BlacklistUpdated {
+        mint: ctx.accounts.token_data.mint,
+        address,
+        blacklisted: false,
+    });
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(address: Pubkey)]
+pub struct BlacklistAddressCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        constraint = token_data.authority == authority.key() || role.is_some() @ ErrorCode::Unauthorized
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = BlacklistEntry::SPACE,
+        seeds = [b"blacklist", token_data.mint.as_ref(), address.as_ref()],
+        bump
+    )]
+    pub entry: Account<'info, BlacklistEntry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+
+    /// Lets a `WhitelistManager` act in place of the token authority
+    #[account(
+        seeds = [b"role", token_data.key().as_ref(), authority.key().as_ref(), &[Role::WhitelistManager as u8]],
+        bump
+    )]
+    pub role: Option<Account<'info, RoleGrant>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(address: Pubkey)]
+pub struct UnblacklistAddressCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        constraint = token_data.authority == authority.key() || role.is_some() @ ErrorCode::Unauthorized
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"blacklist", token_data.mint.as_ref(), address.as_ref()],
+        bump
+    )]
+    pub entry: Account<'info, BlacklistEntry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Lets a `WhitelistManager` act in place of the token authority
+    #[account(
+        seeds = [b"role", token_data.key().as_ref(), authority.key().as_ref(), &[Role::WhitelistManager as u8]],
+        bump
+    )]
+    pub role: Option<Account<'info, RoleGrant>>,
+}
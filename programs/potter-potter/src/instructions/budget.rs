@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+
+pub fn handle_estimate_budget(
+    _ctx: Context<EstimateBudgetCTX>,
+    instruction_kind: InstructionKind,
+    item_count: u32,
+) -> Result<u32> {
+    let units = instruction_kind.estimate_compute_units(item_count);
+    msg!("Estimated compute units: {}", units);
+    Ok(units)
+}
+
+#[derive(Accounts)]
+pub struct EstimateBudgetCTX {}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum InstructionKind {
+    CreateToken,
+    MintTokens,
+    BurnTokens,
+    SwapTokens,
+    AddToWhitelist,
+    RemoveFromWhitelist,
+    Transfer,
+    MintTokensToMany,
+    /// One `append_whitelist_import` chunk or `commit_whitelist_import`
+    /// batch, counted in addresses
+    ImportWhitelist,
+}
+
+// (base, per item) compute costs, padded for headroom over what the
+// "compute budget" tests in tests/potter-potter.ts measure. Those tests
+// fail once a flow outgrows its estimate, so bump the constant with them.
+const MAX_COMPUTE_UNITS: u64 = 1_400_000;
+
+impl InstructionKind {
+    fn compute_cost(self) -> (u64, u64) {
+        match self {
+            InstructionKind::CreateToken => (200_000, 0),
+            InstructionKind::MintTokens => (40_000, 0),
+            InstructionKind::BurnTokens => (30_000, 0),
+            // Burn, receiver lookups and mint
+            InstructionKind::SwapTokens => (70_000, 0),
+            InstructionKind::AddToWhitelist => (20_000, 3_000),
+            InstructionKind::RemoveFromWhitelist => (20_000, 3_000),
+            // Token-2022 resolving the hook's extra accounts, then the hook
+            InstructionKind::Transfer => (120_000, 0),
+            // An opt-out PDA lookup, a mint and an event per recipient
+            InstructionKind::MintTokensToMany => (25_000, 18_000),
+            InstructionKind::ImportWhitelist => (20_000, 4_000),
+        }
+    }
+
+    pub fn estimate_compute_units(self, item_count: u32) -> u32 {
+        let (base, per_item) = self.compute_cost();
+        let units = base.saturating_add(per_item.saturating_mul(item_count as u64));
+        units.min(MAX_COMPUTE_UNITS) as u32
+    }
+}
@@ -0,0 +1,117 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use solana_sha256_hasher::hashv;
+
+// The hash folds in the previous head, the program config, then each
+// token's supply, whitelist root and switches in the order passed, so an
+// indexer can recompute it from its own reconstruction of the same slot.
+pub fn handle_checkpoint_state<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CheckpointStateCTX<'info>>,
+    token_count: u32,
+) -> Result<()> {
+    require!(
+        token_count as usize == ctx.remaining_accounts.len(),
+        ErrorCode::InvalidAmount
+    );
+
+    let log = &mut ctx.accounts.checkpoint_log;
+    let sequence = log.next_sequence;
+    let previous = log.head;
+    let slot = Clock::get()?.slot;
+
+    let config = &ctx.accounts.program_config;
+    let limits = config.limits;
+    let mut state_hash = hashv(&[
+        &previous,
+        &sequence.to_le_bytes(),
+        &slot.to_le_bytes(),
+        config.authority.as_ref(),
+        &[config.v1_disabled as u8],
+        &config.disabled_features.to_le_bytes(),
+        &limits.max_fee_basis_points.to_le_bytes(),
+        &[limits.max_decimals],
+        &limits.max_batch_size.to_le_bytes(),
+        &limits.max_whitelist_page_size.to_le_bytes(),
+        &limits.max_vesting_duration.to_le_bytes(),
+    ])
+    .to_bytes();
+
+    for info in ctx.remaining_accounts {
+        let token_data = Account::<TokenData>::try_from(info)?;
+        let flags = [
+            token_data.is_paused as u8,
+            token_data.is_minting_paused as u8,
+            token_data.supply_locked as u8,
+        ];
+        state_hash = hashv(&[
+            &state_hash,
+            info.key.as_ref(),
+            token_data.mint.as_ref(),
+            &token_data.total_supply.to_le_bytes(),
+            &token_data.circulating_supply.to_le_bytes(),
+            &token_data.whitelist_root.unwrap_or_default(),
+            &token_data.config_lock.to_le_bytes(),
+            &flags,
+        ])
+        .to_bytes();
+    }
+
+    ctx.accounts.checkpoint.set_inner(StateCheckpoint {
+        sequence,
+        slot,
+        token_count,
+        previous,
+        state_hash,
+    });
+    log.next_sequence = sequence.checked_add(1).ok_or(ErrorCode::Overflow)?;
+    log.head = state_hash;
+
+    emit_cpi!(StateCheckpointed {
+        sequence,
+        slot,
+        token_count,
+        state_hash,
+    });
+    msg!(
+        "Checkpoint {} at slot {} over {} tokens",
+        sequence,
+        slot,
+        token_count
+    );
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CheckpointStateCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        has_one = authority
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = CheckpointLog::SPACE,
+        seeds = [b"checkpoint_log"],
+        bump
+    )]
+    pub checkpoint_log: Account<'info, CheckpointLog>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = StateCheckpoint::SPACE,
+        seeds = [b"checkpoint".as_ref(), &checkpoint_log.next_sequence.to_le_bytes()],
+        bump
+    )]
+    pub checkpoint: Account<'info, StateCheckpoint>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
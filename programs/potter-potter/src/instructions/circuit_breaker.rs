@@ -0,0 +1,64 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+// Starts a fresh window so the new thresholds aren't judged on old counts
+pub fn handle_set_circuit_breaker(
+    ctx: Context<SetCircuitBreakerCTX>,
+    window_slots: u64,
+    max_transfers: u32,
+    max_volume: u64,
+) -> Result<()> {
+    ctx.accounts.token_data.require_unlocked(LOCK_PAUSE)?;
+
+    ctx.accounts.velocity_tracker.set_inner(VelocityTracker {
+        window_slots,
+        max_transfers,
+        max_volume,
+        window_start_slot: Clock::get()?.slot,
+        transfers: 0,
+        volume: 0,
+    });
+
+    msg!(
+        "Circuit breaker: {} transfers or {} base units per {} slots",
+        max_transfers,
+        max_volume,
+        window_slots
+    );
+    emit_cpi!(CircuitBreakerUpdated {
+        mint: ctx.accounts.token_data.mint,
+        window_slots,
+        max_transfers,
+        max_volume,
+    });
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetCircuitBreakerCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = VelocityTracker::SPACE,
+        seeds = [b"velocity", token_data.mint.as_ref()],
+        bump
+    )]
+    pub velocity_tracker: Account<'info, VelocityTracker>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,127 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::spl_token_2022::extension::permanent_delegate::PermanentDelegate;
+use anchor_spl::token_interface::{
+    burn, get_mint_extension_data, mint_to, Burn, Mint, MintTo, TokenAccount, TokenInterface,
+};
+
+pub fn handle_clawback(ctx: Context<ClawbackCTX>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let mint_authority = ctx.accounts.mint_authority_pda.key();
+    let delegate =
+        get_mint_extension_data::<PermanentDelegate>(&ctx.accounts.mint.to_account_info())
+            .map_err(|_| error!(ErrorCode::ClawbackDisabled))?
+            .delegate;
+    require!(
+        Option::<Pubkey>::from(delegate) == Some(mint_authority),
+        ErrorCode::ClawbackDisabled
+    );
+
+    let raw_amount = amount
+        .checked_mul(10u64.pow(ctx.accounts.token_data.decimals as u32))
+        .ok_or(ErrorCode::InvalidAmount)?;
+
+    let creator = ctx.accounts.token_data.creator;
+    let seeds = &[
+        b"mint_authority",
+        creator.as_ref(),
+        &[ctx.bumps.mint_authority_pda],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    burn(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.mint.to_account_info(),
+                from: ctx.accounts.from.to_account_info(),
+                authority: ctx.accounts.mint_authority_pda.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        raw_amount,
+    )?;
+
+    // Reissuing keeps supply unchanged, so counters only move on a plain burn
+    match &ctx.accounts.destination {
+        Some(destination) => {
+            ctx.accounts
+                .program_config
+                .require_feature_enabled(FEATURE_MINTING)?;
+            require!(
+                !ctx.accounts.token_data.supply_locked,
+                ErrorCode::SupplyLocked
+            );
+            mint_to(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    MintTo {
+                        mint: ctx.accounts.mint.to_account_info(),
+                        to: destination.to_account_info(),
+                        authority: ctx.accounts.mint_authority_pda.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                raw_amount,
+            )?;
+        }
+        None => ctx.accounts.token_data.record_burn(amount)?,
+    }
+
+    emit_cpi!(TokensClawedBack {
+        mint: ctx.accounts.mint.key(),
+        from: ctx.accounts.from.key(),
+        amount,
+        raw_amount,
+        reissued_to: ctx.accounts.destination.as_ref().map(|d| d.key()),
+    });
+    msg!("Clawed back {} tokens", amount);
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClawbackCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = from.mint == token_data.mint
+    )]
+    pub from: InterfaceAccount<'info, TokenAccount>,
+
+    /// Receives the clawed-back amount; burned outright when omitted
+    #[account(
+        mut,
+        constraint = destination.mint == token_data.mint
+    )]
+    pub destination: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as mint authority and permanent delegate
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
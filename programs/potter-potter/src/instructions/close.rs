@@ -0,0 +1,170 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::spl_token_2022::extension::mint_close_authority::MintCloseAuthority;
+use anchor_spl::token_interface::{
+    close_account, get_mint_extension_data, CloseAccount, Mint, TokenAccount, TokenInterface,
+};
+
+// Only a token with nothing in circulation can go: the hook reads TokenData
+// and the meta list on every transfer, so closing them under live balances
+// would strand every holder. That rules out supply-locked tokens too.
+pub fn handle_close_token<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CloseTokenCTX<'info>>,
+    pages: Vec<u8>,
+) -> Result<()> {
+    require!(ctx.accounts.mint.supply == 0, ErrorCode::SupplyNotZero);
+    // Mirroring tokens read these pages on every transfer
+    require!(
+        ctx.accounts.token_data.whitelist_mirrors == 0,
+        ErrorCode::WhitelistMirrored
+    );
+    require!(
+        pages.len() == ctx.remaining_accounts.len(),
+        ErrorCode::InvalidAmount
+    );
+
+    let mint_key = ctx.accounts.mint.key();
+    let whitelist_mint = ctx.accounts.token_data.whitelist_mint;
+    if whitelist_mint != mint_key {
+        let source = ctx
+            .accounts
+            .whitelist_source_token_data
+            .as_mut()
+            .ok_or(ErrorCode::InvalidWhitelistSource)?;
+        require_keys_eq!(
+            source.mint,
+            whitelist_mint,
+            ErrorCode::InvalidWhitelistSource
+        );
+        source.whitelist_mirrors = source.whitelist_mirrors.saturating_sub(1);
+    }
+
+    let destination = ctx.accounts.authority.to_account_info();
+    for (page_info, page) in ctx.remaining_accounts.iter().zip(&pages) {
+        let (expected, _) =
+            Pubkey::find_program_address(&[b"whitelist", mint_key.as_ref(), &[*page]], &crate::ID);
+        require_keys_eq!(page_info.key(), expected, ErrorCode::WrongWhitelistPage);
+        close_program_account(page_info, &destination)?;
+    }
+
+    let meta_list = ctx.accounts.extra_account_meta_list.to_account_info();
+    if !meta_list.data_is_empty() {
+        close_program_account(&meta_list, &destination)?;
+    }
+
+    // Tokens created before treasuries existed have none to close
+    if let Some(treasury) = &ctx.accounts.treasury {
+        let treasury_seeds = &[
+            b"treasury_authority",
+            mint_key.as_ref(),
+            &[ctx.bumps.treasury_authority],
+        ];
+        close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: treasury.to_account_info(),
+                destination: destination.clone(),
+                authority: ctx.accounts.treasury_authority.to_account_info(),
+            },
+            &[&treasury_seeds[..]],
+        ))?;
+    }
+
+    // Mints only carry a close authority if created `closable`
+    let mint_authority = ctx.accounts.mint_authority_pda.key();
+    let mint_closable =
+        get_mint_extension_data::<MintCloseAuthority>(&ctx.accounts.mint.to_account_info())
+            .is_ok_and(|ext| Option::<Pubkey>::from(ext.close_authority) == Some(mint_authority));
+    if mint_closable {
+        let creator = ctx.accounts.token_data.creator;
+        let seeds = &[
+            b"mint_authority",
+            creator.as_ref(),
+            &[ctx.bumps.mint_authority_pda],
+        ];
+        close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.mint.to_account_info(),
+                destination,
+                authority: ctx.accounts.mint_authority_pda.to_account_info(),
+            },
+            &[&seeds[..]],
+        ))?;
+    }
+
+    emit_cpi!(TokenClosed {
+        mint: mint_key,
+        authority: ctx.accounts.authority.key(),
+        mint_closed: mint_closable,
+    });
+    msg!(
+        "Token closed: {} ({} whitelist pages, mint closed: {})",
+        mint_key,
+        pages.len(),
+        mint_closable
+    );
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseTokenCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        close = authority,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Token whose whitelist this one mirrors, if any
+    #[account(mut)]
+    pub whitelist_source_token_data: Option<Account<'info, TokenData>>,
+
+    /// CHECK: ExtraAccountMetaList account, may already be gone
+    #[account(
+        mut,
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"treasury_authority", mint.key().as_ref()],
+        bump
+    )]
+    /// CHECK: PDA that owns the treasury
+    pub treasury_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump
+    )]
+    pub treasury: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as mint close authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,209 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::TokenInterface;
+use mpl_token_metadata::instructions::{
+    CreateV1, CreateV1InstructionArgs, MintV1, MintV1InstructionArgs,
+};
+use mpl_token_metadata::types::{CollectionDetails, PrintSupply, TokenStandard};
+use mpl_token_metadata::ID as MPL_TOKEN_METADATA_ID;
+
+pub fn handle_initialize_factory_collection(
+    ctx: Context<InitializeFactoryCollectionCTX>,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Result<()> {
+    require!(name.len() <= 32, ErrorCode::NameTooLong);
+    require!(symbol.len() <= 10, ErrorCode::SymbolTooLong);
+    require!(uri.len() <= 200, ErrorCode::UriTooLong);
+    require!(
+        ctx.accounts.factory.collection_mint.is_none(),
+        ErrorCode::CollectionAlreadyInitialized
+    );
+
+    let bump_seed = [ctx.bumps.mint_authority_pda];
+    let signer_seeds = &[&[
+        b"mint_authority",
+        ctx.accounts.authority.key.as_ref(),
+        &bump_seed,
+    ][..]];
+
+    // Token Metadata creates the collection mint, with the PDA as its
+    // mint and update authority
+    let create_ix = CreateV1 {
+        metadata: ctx.accounts.collection_metadata.key(),
+        master_edition: Some(ctx.accounts.collection_master_edition.key()),
+        mint: (ctx.accounts.collection_mint.key(), true),
+        authority: ctx.accounts.mint_authority_pda.key(),
+        payer: ctx.accounts.authority.key(),
+        update_authority: (ctx.accounts.mint_authority_pda.key(), true),
+        system_program: ctx.accounts.system_program.key(),
+        sysvar_instructions: sysvar::instructions::ID,
+        spl_token_program: Some(ctx.accounts.token_program.key()),
+    }
+    .instruction(CreateV1InstructionArgs {
+        name,
+        symbol,
+        uri,
+        seller_fee_basis_points: 0,
+        creators: None,
+        primary_sale_happened: false,
+        is_mutable: true,
+        token_standard: TokenStandard::NonFungible,
+        collection: None,
+        uses: None,
+        collection_details: Some(CollectionDetails::V1 { size: 0 }),
+        rule_set: None,
+        decimals: Some(0),
+        print_supply: Some(PrintSupply::Zero),
+    });
+
+    invoke_signed(
+        &create_ix,
+        &[
+            ctx.accounts.collection_metadata.to_account_info(),
+            ctx.accounts.collection_master_edition.to_account_info(),
+            ctx.accounts.collection_mint.to_account_info(),
+            ctx.accounts.mint_authority_pda.to_account_info(),
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.sysvar_instructions.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.token_metadata_program.to_account_info(),
+        ],
+        signer_seeds,
+    )?;
+
+    // Mint the single collection token to the PDA
+    let mint_ix = MintV1 {
+        token: ctx.accounts.collection_token.key(),
+        token_owner: Some(ctx.accounts.mint_authority_pda.key()),
+        metadata: ctx.accounts.collection_metadata.key(),
+        master_edition: Some(ctx.accounts.collection_master_edition.key()),
+        token_record: None,
+        mint: ctx.accounts.collection_mint.key(),
+        authority: ctx.accounts.mint_authority_pda.key(),
+        delegate_record: None,
+        payer: ctx.accounts.authority.key(),
+        system_program: ctx.accounts.system_program.key(),
+        sysvar_instructions: sysvar::instructions::ID,
+        spl_token_program: ctx.accounts.token_program.key(),
+        spl_ata_program: ctx.accounts.associated_token_program.key(),
+        authorization_rules_program: None,
+        authorization_rules: None,
+    }
+    .instruction(MintV1InstructionArgs {
+        amount: 1,
+        authorization_data: None,
+    });
+
+    invoke_signed(
+        &mint_ix,
+        &[
+            ctx.accounts.collection_token.to_account_info(),
+            ctx.accounts.mint_authority_pda.to_account_info(),
+            ctx.accounts.collection_metadata.to_account_info(),
+            ctx.accounts.collection_master_edition.to_account_info(),
+            ctx.accounts.collection_mint.to_account_info(),
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.sysvar_instructions.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.associated_token_program.to_account_info(),
+            ctx.accounts.token_metadata_program.to_account_info(),
+        ],
+        signer_seeds,
+    )?;
+
+    ctx.accounts.factory.collection_mint = Some(ctx.accounts.collection_mint.key());
+    msg!(
+        "Factory collection created: {}",
+        ctx.accounts.collection_mint.key()
+    );
+    emit_cpi!(FactoryCollectionInitialized {
+        factory: ctx.accounts.factory.key(),
+        collection_mint: ctx.accounts.collection_mint.key(),
+    });
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitializeFactoryCollectionCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_TOKEN_CREATION) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"factory", authority.key().as_ref()],
+        bump
+    )]
+    pub factory: Account<'info, TokenFactory>,
+
+    /// Created and initialized by the token metadata program
+    #[account(mut)]
+    pub collection_mint: Signer<'info>,
+
+    /// CHECK: Validated by token metadata program
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            MPL_TOKEN_METADATA_ID.as_ref(),
+            collection_mint.key().as_ref()
+        ],
+        bump,
+        seeds::program = MPL_TOKEN_METADATA_ID
+    )]
+    pub collection_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by token metadata program
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            MPL_TOKEN_METADATA_ID.as_ref(),
+            collection_mint.key().as_ref(),
+            b"edition"
+        ],
+        bump,
+        seeds::program = MPL_TOKEN_METADATA_ID
+    )]
+    pub collection_master_edition: UncheckedAccount<'info>,
+
+    /// CHECK: PDA's collection token account, created by token metadata program
+    #[account(mut)]
+    pub collection_token: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"mint_authority", authority.key().as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as collection mint and update authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// CHECK: Instructions sysvar
+    #[account(address = sysvar::instructions::ID)]
+    pub sysvar_instructions: UncheckedAccount<'info>,
+
+    /// CHECK: Token Metadata Program
+    #[account(address = MPL_TOKEN_METADATA_ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+}
@@ -0,0 +1,200 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+pub fn handle_initialize_program_config(
+    ctx: Context<InitializeProgramConfigCTX>,
+    is_devnet: bool,
+) -> Result<()> {
+    // Mainnet builds can never enable test-only instructions
+    require!(
+        !(cfg!(feature = "mainnet") && is_devnet),
+        ErrorCode::DevnetOnly
+    );
+
+    ctx.accounts.program_config.set_inner(ProgramConfig {
+        authority: ctx.accounts.authority.key(),
+        v1_disabled: false,
+        is_devnet,
+        disabled_features: 0,
+        program_paused: false,
+        limits: Limits::DEFAULT,
+    });
+    msg!(
+        "Program config initialized with authority: {} (devnet: {})",
+        ctx.accounts.authority.key(),
+        is_devnet
+    );
+    Ok(())
+}
+
+pub fn handle_set_v1_disabled(ctx: Context<UpdateProgramConfigCTX>, disabled: bool) -> Result<()> {
+    ctx.accounts.program_config.v1_disabled = disabled;
+    msg!("V1 instructions disabled: {}", disabled);
+    emit_cpi!(ProgramConfigUpdated {
+        authority: ctx.accounts.authority.key(),
+        v1_disabled: disabled,
+        disabled_features: ctx.accounts.program_config.disabled_features,
+    });
+    Ok(())
+}
+
+// Replaces the whole mask, so re-enabling is just clearing the bit. A
+// program pause holds the mask until `program_resume` has run every phase.
+pub fn handle_set_disabled_features(ctx: Context<UpdateProgramConfigCTX>, mask: u16) -> Result<()> {
+    require!(mask & !FEATURE_ALL == 0, ErrorCode::InvalidFeatureMask);
+    let config = &mut ctx.accounts.program_config;
+    require!(!config.program_paused, ErrorCode::ProgramPaused);
+    config.disabled_features = mask;
+    msg!("Disabled features set to {:#06x}", mask);
+    emit_cpi!(ProgramConfigUpdated {
+        authority: ctx.accounts.authority.key(),
+        v1_disabled: config.v1_disabled,
+        disabled_features: mask,
+    });
+    Ok(())
+}
+
+// Upgrade-authority kill switch for incident response: everything behind a
+// `FEATURE_*` flag stops, reads and `program_resume` keep working
+pub fn handle_program_pause(ctx: Context<ProgramPauseCTX>) -> Result<()> {
+    let config = &mut ctx.accounts.program_config;
+    config.disabled_features = FEATURE_ALL;
+    config.program_paused = true;
+    msg!("Program paused by {}", ctx.accounts.authority.key());
+    emit_cpi!(ProgramPaused {
+        authority: ctx.accounts.authority.key(),
+    });
+    Ok(())
+}
+
+// Only undoes a `program_pause`; masks set through `set_disabled_features`
+// are the config authority's to clear
+pub fn handle_program_resume(ctx: Context<ProgramPauseCTX>, phase: ResumePhase) -> Result<()> {
+    let config = &mut ctx.accounts.program_config;
+    require!(config.program_paused, ErrorCode::ProgramNotPaused);
+    require!(
+        config.disabled_features & phase.prerequisites() == 0,
+        ErrorCode::ResumeOutOfOrder
+    );
+    config.disabled_features &= !phase.features();
+    config.program_paused = config.disabled_features != 0;
+    msg!(
+        "Program resumed {:?}, disabled features now {:#06x}",
+        phase,
+        config.disabled_features
+    );
+    emit_cpi!(ProgramResumed {
+        authority: ctx.accounts.authority.key(),
+        phase,
+        disabled_features: config.disabled_features,
+    });
+    Ok(())
+}
+
+pub fn handle_set_limits(ctx: Context<UpdateProgramConfigCTX>, limits: Limits) -> Result<()> {
+    limits.validate()?;
+    ctx.accounts.program_config.limits = limits;
+    msg!("Limits updated: {:?}", limits);
+    emit_cpi!(LimitsUpdated {
+        authority: ctx.accounts.authority.key(),
+        limits,
+    });
+    Ok(())
+}
+
+// Handlers read it through `unix_timestamp`, with the clock passed as a
+// remaining account; the hook gets it from the extra account metas
+#[cfg(feature = "test-harness")]
+pub fn handle_set_mock_clock(ctx: Context<SetMockClockCTX>, unix_timestamp: i64) -> Result<()> {
+    ctx.accounts.program_config.require_devnet()?;
+    ctx.accounts.mock_clock.unix_timestamp = unix_timestamp;
+    msg!("Mock clock set to {}", unix_timestamp);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeProgramConfigCTX<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = ProgramConfig::SPACE,
+        seeds = [b"program_config"],
+        bump
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::PotterPotter>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+            @ ErrorCode::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateProgramConfigCTX<'info> {
+    #[account(
+        mut,
+        seeds = [b"program_config"],
+        bump,
+        has_one = authority
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProgramPauseCTX<'info> {
+    #[account(
+        mut,
+        seeds = [b"program_config"],
+        bump
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    // Named apart from the `program` account `event_cpi` adds
+    #[account(constraint = potter_potter.programdata_address()? == Some(program_data.key()))]
+    pub potter_potter: Program<'info, crate::program::PotterPotter>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+            @ ErrorCode::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub authority: Signer<'info>,
+}
+
+#[cfg(feature = "test-harness")]
+#[derive(Accounts)]
+pub struct SetMockClockCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        has_one = authority
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = MockClock::SPACE,
+        seeds = [b"mock_clock"],
+        bump
+    )]
+    pub mock_clock: Account<'info, MockClock>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,872 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::system_program::{create_account, CreateAccount};
+use anchor_spl::associated_token;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::spl_token_2022::extension::ExtensionType;
+use anchor_spl::token_interface::spl_token_2022::state::{
+    Account as TokenAccountState, AccountState,
+};
+use anchor_spl::token_interface::{
+    default_account_state_initialize, find_mint_account_size, initialize_account3,
+    initialize_mint2, interest_bearing_mint_initialize, mint_close_authority_initialize, mint_to,
+    permanent_delegate_initialize, thaw_account, transfer_fee_initialize, transfer_hook_initialize,
+    DefaultAccountStateInitialize, InitializeAccount3, InitializeMint2,
+    InterestBearingMintInitialize, MintCloseAuthorityInitialize, MintTo,
+    PermanentDelegateInitialize, ThawAccount, TokenInterface, TransferFeeInitialize,
+    TransferHookInitialize,
+};
+use mpl_token_metadata::instructions::{CreateV1, CreateV1InstructionArgs, VerifyCollectionV1};
+use mpl_token_metadata::types::{Collection, PrintSupply, TokenStandard};
+use mpl_token_metadata::ID as MPL_TOKEN_METADATA_ID;
+
+pub fn handle_create_token(
+    ctx: Context<CreateTokenCTX>,
+    total_supply: u64,
+    name: String,
+    symbol: String,
+    uri: String,
+    default_address: Pubkey,
+) -> Result<()> {
+    ctx.accounts.program_config.require_v1_enabled()?;
+    handle_create_token_v2(
+        ctx,
+        CreateTokenParams {
+            total_supply,
+            name,
+            symbol,
+            uri,
+            default_address,
+            asset_standard: AssetStandard::Fungible,
+            decimals: None,
+            max_supply: None,
+            transfer_fee: None,
+            clawback: false,
+            default_frozen: false,
+            interest_rate_bps: None,
+            closable: false,
+        },
+    )
+}
+
+pub fn handle_create_token_v2(
+    ctx: Context<CreateTokenCTX>,
+    params: CreateTokenParams,
+) -> Result<()> {
+    ctx.accounts
+        .program_config
+        .require_feature_enabled(FEATURE_TOKEN_CREATION)?;
+    params.validate(&ctx.accounts.program_config.limits)?;
+    if let Some(prepared) = &ctx.accounts.prepared_config {
+        require!(prepared.params == params, ErrorCode::PreparedConfigMismatch);
+    }
+
+    let decimals = params.decimals();
+    let CreateTokenParams {
+        total_supply,
+        name,
+        symbol,
+        uri,
+        default_address,
+        asset_standard,
+        max_supply,
+        transfer_fee,
+        clawback,
+        default_frozen,
+        interest_rate_bps,
+        closable,
+        ..
+    } = params;
+
+    let extensions = initialize_mint(
+        &ctx,
+        decimals,
+        transfer_fee,
+        clawback,
+        default_frozen,
+        interest_rate_bps,
+        closable,
+    )?;
+
+    let factory = &mut ctx.accounts.factory;
+    let token_count = factory.token_count;
+
+    // Tokens join the factory collection when it exists
+    let collection = match factory.collection_mint {
+        Some(collection_mint) => {
+            require_keys_eq!(
+                ctx.accounts
+                    .collection_mint
+                    .as_ref()
+                    .ok_or(ErrorCode::InvalidCollection)?
+                    .key(),
+                collection_mint,
+                ErrorCode::InvalidCollection
+            );
+            Some(Collection {
+                verified: false,
+                key: collection_mint,
+            })
+        }
+        None => None,
+    };
+
+    // Initialize token data
+    ctx.accounts.token_data.set_inner(TokenData {
+        token_index: token_count,
+        mint: ctx.accounts.mint.key(),
+        authority: factory.authority,
+        total_supply,
+        decimals,
+        is_paused: false,
+        is_minting_paused: false,
+        config_lock: 0,
+        token_account_whitelisting: false,
+        token_account_entries: 0,
+        metadata_delay: 0,
+        whitelist_mint: ctx.accounts.mint.key(),
+        factory: factory.key(),
+        pending_authority: None,
+        max_supply,
+        debug_telemetry: false,
+        whitelist_root: None,
+        min_transfer_amount: 0,
+        whitelist_policy: WhitelistPolicy::DestinationOnly,
+        supply_locked: false,
+        burn_allowed_while_paused: false,
+        max_wallet_amount: 0,
+        wallet_cap_treasury: None,
+        daily_transfer_limit: 0,
+        trading_starts_at: 0,
+        name: name.clone(),
+        symbol: symbol.clone(),
+        uri: uri.clone(),
+        circulating_supply: total_supply,
+        whitelist_source: WhitelistSource::TokenOnly,
+        nft_gate_collection: None,
+        whitelist_mirrors: 0,
+        creator: factory.authority,
+    });
+
+    // Initialize the default address's whitelist page
+    ctx.accounts.whitelist.set_inner(Whitelist {
+        addresses: vec![default_address],
+        suspended: vec![],
+    });
+
+    factory.token_count = token_count.checked_add(1).ok_or(ErrorCode::Overflow)?;
+    let stats = &mut ctx.accounts.protocol_stats;
+    stats.tokens = stats.tokens.saturating_add(1);
+    stats.record_mint(total_supply);
+
+    // Create associated token account for the authority
+    let cpi_accounts = associated_token::Create {
+        payer: ctx.accounts.authority.to_account_info(),
+        associated_token: ctx.accounts.ata.to_account_info(),
+        authority: ctx.accounts.authority.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+    };
+    associated_token::create(CpiContext::new(
+        ctx.accounts.associated_token_program.to_account_info(),
+        cpi_accounts,
+    ))?;
+    initialize_treasury(&ctx, &extensions)?;
+
+    // Create metadata
+    let bump_seed = [ctx.bumps.mint_authority_pda];
+    let signer_seeds = &[&[
+        b"mint_authority",
+        ctx.accounts.authority.key.as_ref(),
+        &bump_seed,
+    ][..]];
+
+    // Token Metadata only creates editions for non-fungible standards. One
+    // would also take over the mint and freeze authorities and need a
+    // supply of at most 1, so `FungibleAsset` tokens go without one.
+    let ix = CreateV1 {
+        metadata: ctx.accounts.metadata.key(),
+        master_edition: None,
+        mint: (ctx.accounts.mint.key(), false),
+        authority: ctx.accounts.mint_authority_pda.key(),
+        payer: ctx.accounts.authority.key(),
+        update_authority: (ctx.accounts.mint_authority_pda.key(), true),
+        system_program: ctx.accounts.system_program.key(),
+        sysvar_instructions: sysvar::instructions::ID,
+        spl_token_program: Some(ctx.accounts.token_program.key()),
+    }
+    .instruction(CreateV1InstructionArgs {
+        name,
+        symbol,
+        uri,
+        seller_fee_basis_points: 0,
+        creators: None,
+        primary_sale_happened: false,
+        is_mutable: true,
+        token_standard: asset_standard.token_standard(),
+        collection: collection.clone(),
+        uses: None,
+        collection_details: None,
+        rule_set: None,
+        decimals: Some(decimals),
+        print_supply: Some(PrintSupply::Zero),
+    });
+
+    invoke_signed(
+        &ix,
+        &[
+            ctx.accounts.metadata.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.mint_authority_pda.to_account_info(),
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.sysvar_instructions.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.token_metadata_program.to_account_info(),
+        ],
+        signer_seeds,
+    )?;
+
+    if collection.is_some() {
+        let (Some(collection_mint), Some(collection_metadata), Some(collection_master_edition)) = (
+            &ctx.accounts.collection_mint,
+            &ctx.accounts.collection_metadata,
+            &ctx.accounts.collection_master_edition,
+        ) else {
+            return err!(ErrorCode::InvalidCollection);
+        };
+
+        // The PDA is the collection's update authority, so it can verify
+        let verify_ix = VerifyCollectionV1 {
+            authority: ctx.accounts.mint_authority_pda.key(),
+            delegate_record: None,
+            metadata: ctx.accounts.metadata.key(),
+            collection_mint: collection_mint.key(),
+            collection_metadata: Some(collection_metadata.key()),
+            collection_master_edition: Some(collection_master_edition.key()),
+            system_program: ctx.accounts.system_program.key(),
+            sysvar_instructions: sysvar::instructions::ID,
+        }
+        .instruction();
+
+        invoke_signed(
+            &verify_ix,
+            &[
+                ctx.accounts.mint_authority_pda.to_account_info(),
+                ctx.accounts.metadata.to_account_info(),
+                collection_mint.to_account_info(),
+                collection_metadata.to_account_info(),
+                collection_master_edition.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.sysvar_instructions.to_account_info(),
+                ctx.accounts.token_metadata_program.to_account_info(),
+            ],
+            signer_seeds,
+        )?;
+    }
+
+    // Mint initial supply using PDA authority
+    msg!("Minting initial supply: {} tokens", total_supply);
+
+    let raw_supply = total_supply
+        .checked_mul(10u64.pow(ctx.accounts.token_data.decimals as u32))
+        .ok_or(ErrorCode::InvalidAmount)?;
+
+    // The authority's own account has to be live to receive the supply,
+    // and the treasury to take deposits
+    if default_frozen {
+        for account in [&ctx.accounts.ata, &ctx.accounts.treasury] {
+            thaw_account(CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                ThawAccount {
+                    account: account.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    authority: ctx.accounts.mint_authority_pda.to_account_info(),
+                },
+                signer_seeds,
+            ))?;
+        }
+    }
+
+    mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.ata.to_account_info(),
+                authority: ctx.accounts.mint_authority_pda.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        raw_supply,
+    )?;
+
+    emit_cpi!(TokenCreated {
+        mint: ctx.accounts.mint.key(),
+        authority: ctx.accounts.token_data.authority,
+        token_index: ctx.accounts.token_data.token_index,
+        total_supply,
+        decimals: ctx.accounts.token_data.decimals,
+    });
+    msg!("Token created successfully");
+    Ok(())
+}
+
+/// Arguments for `create_token_v2`. New fields are appended at the end so
+/// the leading fields keep the v1 `create_token` layout.
+#[derive(AnchorSerialize, Clone, PartialEq)]
+pub struct CreateTokenParams {
+    pub total_supply: u64,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub default_address: Pubkey,
+    pub asset_standard: AssetStandard,
+    /// Defaults to the asset standard's decimals when omitted
+    pub decimals: Option<u8>,
+    /// Whole-token cap on total supply; uncapped when omitted
+    pub max_supply: Option<u64>,
+    /// Enables the Token-2022 transfer fee extension
+    pub transfer_fee: Option<TransferFeeParams>,
+    /// Makes the mint authority PDA a permanent delegate, enabling `clawback`
+    pub clawback: bool,
+    /// New token accounts start frozen until `thaw_account` finds their
+    /// owner whitelisted
+    pub default_frozen: bool,
+    /// Enables the Token-2022 interest-bearing extension at this rate
+    pub interest_rate_bps: Option<i16>,
+    /// Lets `close_token` also close the mint once its supply is zero
+    pub closable: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub struct TransferFeeParams {
+    pub basis_points: u16,
+    /// Fee ceiling per transfer, in base units
+    pub maximum_fee: u64,
+}
+
+impl CreateTokenParams {
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+            .unwrap_or(self.asset_standard.default_decimals())
+    }
+
+    pub fn validate(&self, limits: &Limits) -> Result<()> {
+        match self.issues(limits).first() {
+            Some(issue) => Err(issue.error().into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Every check `create_token_v2` makes, collected rather than failing
+    /// on the first.
+    pub fn issues(&self, limits: &Limits) -> Vec<TokenParamIssue> {
+        let decimals = self.decimals();
+        let mut issues = Vec::new();
+        if self.name.len() > 32 {
+            issues.push(TokenParamIssue::NameTooLong);
+        }
+        if self.symbol.len() > 10 {
+            issues.push(TokenParamIssue::SymbolTooLong);
+        }
+        if self.uri.len() > 200 {
+            issues.push(TokenParamIssue::UriTooLong);
+        }
+        if self.total_supply == 0 {
+            issues.push(TokenParamIssue::ZeroSupply);
+        }
+        if decimals > limits.max_decimals {
+            issues.push(TokenParamIssue::TooManyDecimals);
+        }
+        if self.asset_standard == AssetStandard::FungibleAsset && decimals != 0 {
+            issues.push(TokenParamIssue::DecimalsNotAllowed);
+        }
+        if self.max_supply.is_some_and(|max| self.total_supply > max) {
+            issues.push(TokenParamIssue::SupplyAboveCap);
+        }
+        if self
+            .transfer_fee
+            .is_some_and(|fee| fee.basis_points > limits.max_fee_basis_points)
+        {
+            issues.push(TokenParamIssue::TransferFeeTooHigh);
+        }
+        issues
+    }
+}
+
+/// A problem `validate_token_params` found with `CreateTokenParams`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TokenParamIssue {
+    NameTooLong,
+    SymbolTooLong,
+    UriTooLong,
+    ZeroSupply,
+    TooManyDecimals,
+    DecimalsNotAllowed,
+    SupplyAboveCap,
+    TransferFeeTooHigh,
+}
+
+impl TokenParamIssue {
+    /// The error `create_token_v2` fails with for this issue
+    pub fn error(self) -> ErrorCode {
+        match self {
+            TokenParamIssue::NameTooLong => ErrorCode::NameTooLong,
+            TokenParamIssue::SymbolTooLong => ErrorCode::SymbolTooLong,
+            TokenParamIssue::UriTooLong => ErrorCode::UriTooLong,
+            TokenParamIssue::ZeroSupply => ErrorCode::InvalidAmount,
+            TokenParamIssue::TooManyDecimals | TokenParamIssue::DecimalsNotAllowed => {
+                ErrorCode::InvalidDecimals
+            }
+            TokenParamIssue::SupplyAboveCap => ErrorCode::SupplyCapExceeded,
+            TokenParamIssue::TransferFeeTooHigh => ErrorCode::InvalidTransferFee,
+        }
+    }
+}
+
+// Appended fields default when absent, so v1 args and older v2 clients
+// still deserialize
+impl AnchorDeserialize for CreateTokenParams {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        Ok(Self {
+            total_supply: AnchorDeserialize::deserialize_reader(reader)?,
+            name: AnchorDeserialize::deserialize_reader(reader)?,
+            symbol: AnchorDeserialize::deserialize_reader(reader)?,
+            uri: AnchorDeserialize::deserialize_reader(reader)?,
+            default_address: AnchorDeserialize::deserialize_reader(reader)?,
+            asset_standard: deserialize_trailing(reader)?,
+            decimals: deserialize_trailing(reader)?,
+            max_supply: deserialize_trailing(reader)?,
+            transfer_fee: deserialize_trailing(reader)?,
+            clawback: deserialize_trailing(reader)?,
+            default_frozen: deserialize_trailing(reader)?,
+            interest_rate_bps: deserialize_trailing(reader)?,
+            closable: deserialize_trailing(reader)?,
+        })
+    }
+}
+
+// Anchor's `mint::` constraints can't add most extensions, so the mint is
+// built by hand. Extensions have to be initialized before the mint itself.
+fn initialize_mint(
+    ctx: &Context<CreateTokenCTX>,
+    decimals: u8,
+    transfer_fee: Option<TransferFeeParams>,
+    clawback: bool,
+    default_frozen: bool,
+    interest_rate_bps: Option<i16>,
+    closable: bool,
+) -> Result<Vec<ExtensionType>> {
+    let mint_authority = ctx.accounts.mint_authority_pda.key();
+    let mut extensions = vec![ExtensionType::TransferHook];
+    if transfer_fee.is_some() {
+        extensions.push(ExtensionType::TransferFeeConfig);
+    }
+    if clawback {
+        extensions.push(ExtensionType::PermanentDelegate);
+    }
+    if default_frozen {
+        extensions.push(ExtensionType::DefaultAccountState);
+    }
+    if interest_rate_bps.is_some() {
+        extensions.push(ExtensionType::InterestBearingConfig);
+    }
+    if closable {
+        extensions.push(ExtensionType::MintCloseAuthority);
+    }
+    let space = find_mint_account_size(Some(&extensions))?;
+
+    create_account(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            CreateAccount {
+                from: ctx.accounts.authority.to_account_info(),
+                to: ctx.accounts.mint.to_account_info(),
+            },
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        &ctx.accounts.token_program.key(),
+    )?;
+
+    transfer_hook_initialize(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferHookInitialize {
+                token_program_id: ctx.accounts.token_program.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+            },
+        ),
+        Some(mint_authority),
+        Some(crate::ID),
+    )?;
+
+    // The PDA both tunes the fee and withdraws what it withholds
+    if let Some(fee) = transfer_fee {
+        transfer_fee_initialize(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferFeeInitialize {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                },
+            ),
+            Some(&mint_authority),
+            Some(&mint_authority),
+            fee.basis_points,
+            fee.maximum_fee,
+        )?;
+    }
+
+    if clawback {
+        permanent_delegate_initialize(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                PermanentDelegateInitialize {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                },
+            ),
+            &mint_authority,
+        )?;
+    }
+
+    if default_frozen {
+        default_account_state_initialize(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                DefaultAccountStateInitialize {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                },
+            ),
+            &AccountState::Frozen,
+        )?;
+    }
+
+    if let Some(rate) = interest_rate_bps {
+        interest_bearing_mint_initialize(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                InterestBearingMintInitialize {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                },
+            ),
+            Some(mint_authority),
+            rate,
+        )?;
+    }
+
+    if closable {
+        mint_close_authority_initialize(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                MintCloseAuthorityInitialize {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                },
+            ),
+            Some(&mint_authority),
+        )?;
+    }
+
+    initialize_mint2(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            InitializeMint2 {
+                mint: ctx.accounts.mint.to_account_info(),
+            },
+        ),
+        decimals,
+        &mint_authority,
+        Some(&mint_authority),
+    )?;
+    Ok(extensions)
+}
+
+// Same story as the mint: the account can only be created once the mint
+// exists, and is sized for whatever account extensions its mint requires.
+fn initialize_treasury(
+    ctx: &Context<CreateTokenCTX>,
+    mint_extensions: &[ExtensionType],
+) -> Result<()> {
+    let space = ExtensionType::try_calculate_account_len::<TokenAccountState>(
+        &ExtensionType::get_required_init_account_extensions(mint_extensions),
+    )?;
+    let mint_key = ctx.accounts.mint.key();
+    let seeds = &[b"treasury", mint_key.as_ref(), &[ctx.bumps.treasury]];
+
+    create_account(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            CreateAccount {
+                from: ctx.accounts.authority.to_account_info(),
+                to: ctx.accounts.treasury.to_account_info(),
+            },
+            &[&seeds[..]],
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        &ctx.accounts.token_program.key(),
+    )?;
+
+    initialize_account3(CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        InitializeAccount3 {
+            account: ctx.accounts.treasury.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.treasury_authority.to_account_info(),
+        },
+    ))
+}
+
+/// Metadata standard for a factory token.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AssetStandard {
+    #[default]
+    Fungible,
+    /// Semi-fungible, stackable item token with zero decimals. It has no
+    /// edition account, which Metaplex keeps for supply-of-one mints.
+    FungibleAsset,
+}
+
+impl AssetStandard {
+    pub fn default_decimals(self) -> u8 {
+        match self {
+            AssetStandard::Fungible => 9,
+            AssetStandard::FungibleAsset => 0,
+        }
+    }
+
+    fn token_standard(self) -> TokenStandard {
+        match self {
+            AssetStandard::Fungible => TokenStandard::Fungible,
+            AssetStandard::FungibleAsset => TokenStandard::FungibleAsset,
+        }
+    }
+}
+
+// v1 `create_token` args are a prefix of `CreateTokenParams`
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(params: CreateTokenParams)]
+pub struct CreateTokenCTX<'info> {
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_stats"],
+        bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"factory", authority.key().as_ref()],
+        bump
+    )]
+    pub factory: Account<'info, TokenFactory>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = TokenData::SPACE,
+        seeds = [b"token", authority.key().as_ref(), &factory.token_count.to_le_bytes()],
+        bump
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    /// New mint keypair, created and initialized by the handler
+    #[account(mut)]
+    pub mint: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Whitelist::space(10, 0),
+        seeds = [b"whitelist", mint.key().as_ref(), &[whitelist_page_of(&params.default_address)]],
+        bump
+    )]
+    pub whitelist: Account<'info, Whitelist>,
+
+    #[account(
+        seeds = [b"mint_authority", authority.key().as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as mint authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    /// CHECK: Created via CPI to associated token program
+    #[account(mut)]
+    pub ata: UncheckedAccount<'info>,
+
+    /// CHECK: Mint treasury, created by the handler
+    #[account(
+        mut,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"treasury_authority", mint.key().as_ref()],
+        bump
+    )]
+    /// CHECK: PDA that owns the treasury
+    pub treasury_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by token metadata program
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            MPL_TOKEN_METADATA_ID.as_ref(),
+            mint.key().as_ref()
+        ],
+        bump,
+        seeds::program = MPL_TOKEN_METADATA_ID
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(address = anchor_spl::associated_token::ID)]
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+
+    /// CHECK: Instructions sysvar
+    #[account(address = sysvar::instructions::ID)]
+    pub sysvar_instructions: UncheckedAccount<'info>,
+
+    /// CHECK: Factory collection mint, required once the factory has one
+    pub collection_mint: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Validated by token metadata program
+    #[account(mut)]
+    pub collection_metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Validated by token metadata program
+    pub collection_master_edition: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token Metadata Program
+    #[account(address = MPL_TOKEN_METADATA_ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    /// Reviewed config from `prepare_token_config`, consumed on success
+    #[account(
+        mut,
+        close = authority,
+        has_one = authority,
+        seeds = [b"prepared_token", authority.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub prepared_config: Option<Account<'info, PreparedTokenConfig>>,
+}
+
+pub fn handle_prepare_token_config(
+    ctx: Context<PrepareTokenConfigCTX>,
+    mint: Pubkey,
+    params: CreateTokenParams,
+) -> Result<()> {
+    params.validate(&ctx.accounts.program_config.limits)?;
+    ctx.accounts.prepared_config.set_inner(PreparedTokenConfig {
+        authority: ctx.accounts.authority.key(),
+        params,
+    });
+    msg!("Token config prepared for mint: {}", mint);
+    Ok(())
+}
+
+// Symbols aren't unique across the factory, so there is no symbol check
+pub fn handle_validate_token_params(
+    ctx: Context<ValidateTokenParamsCTX>,
+    params: CreateTokenParams,
+) -> Result<Vec<TokenParamIssue>> {
+    let issues = params.issues(&ctx.accounts.program_config.limits);
+    for issue in &issues {
+        msg!("Invalid token params: {:?}", issue);
+    }
+    Ok(issues)
+}
+
+pub fn handle_execute_token_create(
+    ctx: Context<CreateTokenCTX>,
+    params: CreateTokenParams,
+) -> Result<()> {
+    require!(
+        ctx.accounts.prepared_config.is_some(),
+        ErrorCode::PreparedConfigMismatch
+    );
+    handle_create_token_v2(ctx, params)
+}
+
+pub fn handle_cancel_token_config(_ctx: Context<CancelTokenConfigCTX>, mint: Pubkey) -> Result<()> {
+    msg!("Prepared token config cancelled for mint: {}", mint);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(mint: Pubkey, params: CreateTokenParams)]
+pub struct PrepareTokenConfigCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_TOKEN_CREATION) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = PreparedTokenConfig::space(&params)?,
+        seeds = [b"prepared_token", authority.key().as_ref(), mint.as_ref()],
+        bump
+    )]
+    pub prepared_config: Account<'info, PreparedTokenConfig>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(mint: Pubkey)]
+pub struct CancelTokenConfigCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_TOKEN_CREATION) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        close = authority,
+        has_one = authority,
+        seeds = [b"prepared_token", authority.key().as_ref(), mint.as_ref()],
+        bump
+    )]
+    pub prepared_config: Account<'info, PreparedTokenConfig>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ValidateTokenParamsCTX<'info> {
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+}
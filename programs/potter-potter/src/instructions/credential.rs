@@ -0,0 +1,268 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{create_account, CreateAccount};
+use anchor_spl::token_interface::spl_token_2022::extension::ExtensionType;
+use anchor_spl::token_interface::{
+    find_mint_account_size, freeze_account, initialize_mint2, mint_to,
+    non_transferable_mint_initialize, thaw_account, FreezeAccount, InitializeMint2, Mint, MintTo,
+    NonTransferableMintInitialize, ThawAccount, TokenAccount, TokenInterface,
+};
+
+// Credentials are non-transferable Token-2022 tokens, one per whitelisted
+// owner, held in a token account at a PDA of this program. The hook accepts
+// a live credential in place of list membership.
+
+pub fn handle_initialize_credential_mint(ctx: Context<InitializeCredentialMintCTX>) -> Result<()> {
+    let mint_authority = ctx.accounts.mint_authority_pda.key();
+    let space = find_mint_account_size(Some(&vec![ExtensionType::NonTransferable]))?;
+
+    let token_mint = ctx.accounts.token_data.mint;
+    let seeds = &[
+        b"credential_mint",
+        token_mint.as_ref(),
+        &[ctx.bumps.credential_mint],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    create_account(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            CreateAccount {
+                from: ctx.accounts.authority.to_account_info(),
+                to: ctx.accounts.credential_mint.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        &ctx.accounts.token_program.key(),
+    )?;
+
+    non_transferable_mint_initialize(CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        NonTransferableMintInitialize {
+            token_program_id: ctx.accounts.token_program.to_account_info(),
+            mint: ctx.accounts.credential_mint.to_account_info(),
+        },
+    ))?;
+
+    // The PDA freezes credentials to revoke them
+    initialize_mint2(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            InitializeMint2 {
+                mint: ctx.accounts.credential_mint.to_account_info(),
+            },
+        ),
+        0,
+        &mint_authority,
+        Some(&mint_authority),
+    )?;
+
+    msg!(
+        "Credential mint initialized: {}",
+        ctx.accounts.credential_mint.key()
+    );
+    Ok(())
+}
+
+pub fn handle_issue_credential(ctx: Context<IssueCredentialCTX>, owner: Pubkey) -> Result<()> {
+    ctx.accounts
+        .program_config
+        .require_feature_enabled(FEATURE_MINTING)?;
+    ctx.accounts.token_data.require_unlocked(LOCK_WHITELIST)?;
+
+    let creator = ctx.accounts.token_data.creator;
+    let seeds = &[
+        b"mint_authority",
+        creator.as_ref(),
+        &[ctx.bumps.mint_authority_pda],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    // Reissuing a revoked credential thaws it rather than minting another
+    let credential = &ctx.accounts.credential;
+    if credential.is_frozen() {
+        thaw_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            ThawAccount {
+                account: credential.to_account_info(),
+                mint: ctx.accounts.credential_mint.to_account_info(),
+                authority: ctx.accounts.mint_authority_pda.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+    }
+    if credential.amount == 0 {
+        mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.credential_mint.to_account_info(),
+                    to: credential.to_account_info(),
+                    authority: ctx.accounts.mint_authority_pda.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            1,
+        )?;
+    }
+
+    msg!("Credential issued: {}", owner);
+    emit_cpi!(WhitelistUpdated {
+        mint: ctx.accounts.token_data.mint,
+        change: WhitelistChange::CredentialIssued { owner },
+    });
+    Ok(())
+}
+
+pub fn handle_revoke_credential(ctx: Context<RevokeCredentialCTX>, owner: Pubkey) -> Result<()> {
+    ctx.accounts.token_data.require_unlocked(LOCK_WHITELIST)?;
+
+    let creator = ctx.accounts.token_data.creator;
+    let seeds = &[
+        b"mint_authority",
+        creator.as_ref(),
+        &[ctx.bumps.mint_authority_pda],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    // The holder can't burn or move it, so a frozen credential stays visible
+    // in the wallet but no longer passes the hook
+    if !ctx.accounts.credential.is_frozen() {
+        freeze_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            FreezeAccount {
+                account: ctx.accounts.credential.to_account_info(),
+                mint: ctx.accounts.credential_mint.to_account_info(),
+                authority: ctx.accounts.mint_authority_pda.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+    }
+
+    msg!("Credential revoked: {}", owner);
+    emit_cpi!(WhitelistUpdated {
+        mint: ctx.accounts.token_data.mint,
+        change: WhitelistChange::CredentialRevoked { owner },
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeCredentialMintCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    /// CHECK: Created and initialized by the handler
+    #[account(
+        mut,
+        seeds = [b"credential_mint", token_data.mint.as_ref()],
+        bump
+    )]
+    pub credential_mint: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as credential mint and freeze authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct IssueCredentialCTX<'info> {
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"credential_mint", token_data.mint.as_ref()],
+        bump
+    )]
+    pub credential_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Credential holder, need not sign
+    #[account(address = owner)]
+    pub holder: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [b"credential", token_data.mint.as_ref(), owner.as_ref()],
+        bump,
+        token::mint = credential_mint,
+        token::authority = holder,
+        token::token_program = token_program
+    )]
+    pub credential: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as credential mint and freeze authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct RevokeCredentialCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        seeds = [b"credential_mint", token_data.mint.as_ref()],
+        bump
+    )]
+    pub credential_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"credential", token_data.mint.as_ref(), owner.as_ref()],
+        bump
+    )]
+    pub credential: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as credential mint and freeze authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
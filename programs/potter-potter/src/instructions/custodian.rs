@@ -0,0 +1,154 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token_interface::{
+    approve, revoke, Approve, Mint, Revoke, TokenAccount, TokenInterface,
+};
+
+// The custodian then moves tokens with an ordinary Token-2022 transfer as
+// delegate. The hook holds it to the cap and expiry on top of every other
+// rule; this program can't do the transfer itself, as the hook would
+// re-enter it. Approving again replaces the delegate and resets `spent`.
+pub fn handle_approve_custodian(
+    ctx: Context<ApproveCustodianCTX>,
+    custodian: Pubkey,
+    cap: u64,
+    expires_at: i64,
+) -> Result<()> {
+    require!(cap > 0, ErrorCode::InvalidAmount);
+    require!(
+        expires_at > unix_timestamp(ctx.remaining_accounts)?,
+        ErrorCode::InvalidAmount
+    );
+
+    approve(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Approve {
+                to: ctx.accounts.token_account.to_account_info(),
+                delegate: ctx.accounts.delegate.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ),
+        cap,
+    )?;
+
+    ctx.accounts.approval.set_inner(CustodianApproval {
+        owner: ctx.accounts.owner.key(),
+        custodian,
+        cap,
+        spent: 0,
+        expires_at,
+    });
+
+    emit_cpi!(CustodianApproved {
+        mint: ctx.accounts.mint.key(),
+        token_account: ctx.accounts.token_account.key(),
+        custodian,
+        cap,
+        expires_at,
+    });
+    msg!(
+        "Custodian {} approved for {} base units until {}",
+        custodian,
+        cap,
+        expires_at
+    );
+    Ok(())
+}
+
+pub fn handle_revoke_custodian(ctx: Context<RevokeCustodianCTX>, custodian: Pubkey) -> Result<()> {
+    // A later approval may already have replaced this custodian as delegate
+    if ctx.accounts.token_account.delegate == COption::Some(custodian) {
+        revoke(CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Revoke {
+                source: ctx.accounts.token_account.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ))?;
+    }
+
+    emit_cpi!(CustodianRevoked {
+        mint: ctx.accounts.mint.key(),
+        token_account: ctx.accounts.token_account.key(),
+        custodian,
+    });
+    msg!("Custodian {} revoked", custodian);
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(custodian: Pubkey)]
+pub struct ApproveCustodianCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = owner
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: The custodian, only used as the delegate address
+    #[account(address = custodian)]
+    pub delegate: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = CustodianApproval::SPACE,
+        seeds = [b"custodian", token_account.key().as_ref(), custodian.as_ref()],
+        bump
+    )]
+    pub approval: Account<'info, CustodianApproval>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(custodian: Pubkey)]
+pub struct RevokeCustodianCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = owner
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"custodian", token_account.key().as_ref(), custodian.as_ref()],
+        bump
+    )]
+    pub approval: Account<'info, CustodianApproval>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
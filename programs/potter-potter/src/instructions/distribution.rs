@@ -0,0 +1,74 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+pub fn handle_opt_out_of_distributions(ctx: Context<OptOutOfDistributionsCTX>) -> Result<()> {
+    msg!("{} opted out of distributions", ctx.accounts.owner.key());
+    emit_cpi!(DistributionOptOutUpdated {
+        mint: ctx.accounts.token_data.mint,
+        owner: ctx.accounts.owner.key(),
+        opted_out: true,
+    });
+    Ok(())
+}
+
+pub fn handle_opt_into_distributions(ctx: Context<OptIntoDistributionsCTX>) -> Result<()> {
+    msg!("{} opted back into distributions", ctx.accounts.owner.key());
+    emit_cpi!(DistributionOptOutUpdated {
+        mint: ctx.accounts.token_data.mint,
+        owner: ctx.accounts.owner.key(),
+        opted_out: false,
+    });
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct OptOutOfDistributionsCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_TRANSFERS) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = DistributionOptOut::SPACE,
+        seeds = [b"opt_out", token_data.mint.as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub opt_out: Account<'info, DistributionOptOut>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct OptIntoDistributionsCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_TRANSFERS) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"opt_out", token_data.mint.as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub opt_out: Account<'info, DistributionOptOut>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
@@ -0,0 +1,404 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::spl_token_2022::extension::permanent_delegate::PermanentDelegate;
+use anchor_spl::token_interface::{
+    burn, get_mint_extension_data, mint_to, Burn, Mint, MintTo, TokenAccount, TokenInterface,
+};
+
+pub fn handle_set_escheatment_policy(
+    ctx: Context<SetEscheatmentPolicyCTX>,
+    dormancy_period: i64,
+    waiting_period: i64,
+) -> Result<()> {
+    ctx.accounts
+        .token_data
+        .require_unlocked(LOCK_TRANSFER_RULES)?;
+    require!(
+        dormancy_period > 0 && waiting_period > 0,
+        ErrorCode::InvalidAmount
+    );
+
+    ctx.accounts.policy.set_inner(EscheatmentPolicy {
+        custody: ctx.accounts.custody.key(),
+        dormancy_period,
+        waiting_period,
+    });
+
+    msg!(
+        "Escheatment policy set: dormant after {}s, recoverable {}s after notice",
+        dormancy_period,
+        waiting_period
+    );
+    emit_cpi!(EscheatmentPolicyUpdated {
+        mint: ctx.accounts.token_data.mint,
+        custody: ctx.accounts.custody.key(),
+        dormancy_period,
+        waiting_period,
+    });
+    Ok(())
+}
+
+// Tracking starts when the record is opened, so an account is never dormant
+// for longer than the program has been watching it
+pub fn handle_open_activity_record(ctx: Context<OpenActivityRecordCTX>) -> Result<()> {
+    let last_activity_at = unix_timestamp(ctx.remaining_accounts)?;
+    ctx.accounts.activity.set_inner(ActivityRecord {
+        mint: ctx.accounts.token_account.mint,
+        last_activity_at,
+    });
+
+    msg!(
+        "Tracking activity of {} from {}",
+        ctx.accounts.token_account.key(),
+        last_activity_at
+    );
+    Ok(())
+}
+
+pub fn handle_file_dormancy_notice(ctx: Context<FileDormancyNoticeCTX>) -> Result<()> {
+    let now = unix_timestamp(ctx.remaining_accounts)?;
+    let policy = &ctx.accounts.policy;
+    let last_activity_at = ctx.accounts.activity.last_activity_at;
+    require!(
+        ctx.accounts
+            .activity
+            .is_dormant(policy.dormancy_period, now),
+        ErrorCode::AccountNotDormant
+    );
+    let raw_amount = ctx.accounts.token_account.amount;
+    require!(raw_amount > 0, ErrorCode::InvalidAmount);
+
+    let recoverable_at = now.saturating_add(policy.waiting_period);
+    ctx.accounts.notice.set_inner(DormancyNotice {
+        mint: ctx.accounts.token_data.mint,
+        recoverable_at,
+        contested: false,
+        raw_amount,
+    });
+
+    msg!(
+        "Dormancy notice filed for {}, recoverable at {}",
+        ctx.accounts.token_account.key(),
+        recoverable_at
+    );
+    emit_cpi!(DormancyNoticeFiled {
+        mint: ctx.accounts.token_data.mint,
+        token_account: ctx.accounts.token_account.key(),
+        owner: ctx.accounts.token_account.owner,
+        last_activity_at,
+        recoverable_at,
+        raw_amount,
+    });
+    Ok(())
+}
+
+// The holder's answer to a notice without having to move tokens; signing
+// is activity too, so the dormancy clock starts over
+pub fn handle_contest_dormancy_notice(ctx: Context<ContestDormancyNoticeCTX>) -> Result<()> {
+    ctx.accounts.notice.contested = true;
+    ctx.accounts.activity.last_activity_at = unix_timestamp(ctx.remaining_accounts)?;
+
+    msg!(
+        "Dormancy notice contested for {}",
+        ctx.accounts.token_account.key()
+    );
+    Ok(())
+}
+
+pub fn handle_cancel_dormancy_notice(ctx: Context<CancelDormancyNoticeCTX>) -> Result<()> {
+    msg!(
+        "Dormancy notice cancelled for {}",
+        ctx.accounts.token_account.key()
+    );
+    Ok(())
+}
+
+// Recovery burns through the permanent delegate and mints the same amount
+// into custody; a delegate transfer would re-enter this program via the hook
+pub fn handle_recover_dormant_balance(ctx: Context<RecoverDormantBalanceCTX>) -> Result<()> {
+    let notice = &ctx.accounts.notice;
+    require!(!notice.contested, ErrorCode::DormancyNoticeContested);
+    require!(
+        unix_timestamp(ctx.remaining_accounts)? >= notice.recoverable_at,
+        ErrorCode::TimelockNotElapsed
+    );
+    require!(
+        !ctx.accounts.token_data.supply_locked,
+        ErrorCode::SupplyLocked
+    );
+
+    let mint_authority = ctx.accounts.mint_authority_pda.key();
+    let delegate =
+        get_mint_extension_data::<PermanentDelegate>(&ctx.accounts.mint.to_account_info())
+            .map_err(|_| error!(ErrorCode::ClawbackDisabled))?
+            .delegate;
+    require!(
+        Option::<Pubkey>::from(delegate) == Some(mint_authority),
+        ErrorCode::ClawbackDisabled
+    );
+
+    // Anything received after the notice was filed stays with the holder
+    let raw_amount = notice.recoverable_amount(ctx.accounts.token_account.amount);
+    require!(raw_amount > 0, ErrorCode::InvalidAmount);
+    let creator = ctx.accounts.token_data.creator;
+    let seeds = &[
+        b"mint_authority",
+        creator.as_ref(),
+        &[ctx.bumps.mint_authority_pda],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    burn(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.mint.to_account_info(),
+                from: ctx.accounts.token_account.to_account_info(),
+                authority: ctx.accounts.mint_authority_pda.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        raw_amount,
+    )?;
+    mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.custody.to_account_info(),
+                authority: ctx.accounts.mint_authority_pda.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        raw_amount,
+    )?;
+
+    msg!(
+        "Recovered {} base units from {}",
+        raw_amount,
+        ctx.accounts.token_account.key()
+    );
+    emit_cpi!(DormantBalanceRecovered {
+        mint: ctx.accounts.mint.key(),
+        token_account: ctx.accounts.token_account.key(),
+        owner: ctx.accounts.token_account.owner,
+        custody: ctx.accounts.custody.key(),
+        raw_amount,
+    });
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetEscheatmentPolicyCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = EscheatmentPolicy::SPACE,
+        seeds = [b"escheatment", token_data.mint.as_ref()],
+        bump
+    )]
+    pub policy: Account<'info, EscheatmentPolicy>,
+
+    #[account(constraint = custody.mint == token_data.mint)]
+    pub custody: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenActivityRecordCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = ActivityRecord::SPACE,
+        seeds = [b"activity", token_account.key().as_ref()],
+        bump
+    )]
+    pub activity: Account<'info, ActivityRecord>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FileDormancyNoticeCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        seeds = [b"escheatment", token_data.mint.as_ref()],
+        bump
+    )]
+    pub policy: Account<'info, EscheatmentPolicy>,
+
+    #[account(constraint = token_account.mint == token_data.mint)]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"activity", token_account.key().as_ref()],
+        bump
+    )]
+    pub activity: Account<'info, ActivityRecord>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = DormancyNotice::SPACE,
+        seeds = [b"dormancy", token_account.key().as_ref()],
+        bump
+    )]
+    pub notice: Account<'info, DormancyNotice>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Contesting is the holder's counterpart to a transfer, so it stops with
+// transfers rather than with admin
+#[derive(Accounts)]
+pub struct ContestDormancyNoticeCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_TRANSFERS) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(constraint = token_account.owner == holder.key() @ ErrorCode::Unauthorized)]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"dormancy", token_account.key().as_ref()],
+        bump
+    )]
+    pub notice: Account<'info, DormancyNotice>,
+
+    #[account(
+        mut,
+        seeds = [b"activity", token_account.key().as_ref()],
+        bump
+    )]
+    pub activity: Account<'info, ActivityRecord>,
+
+    pub holder: Signer<'info>,
+}
+
+// Also how a contested notice is cleared so a new one can be filed
+#[derive(Accounts)]
+pub struct CancelDormancyNoticeCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    /// CHECK: Only used to derive the notice address
+    pub token_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = authority,
+        constraint = notice.mint == token_data.mint,
+        seeds = [b"dormancy", token_account.key().as_ref()],
+        bump
+    )]
+    pub notice: Account<'info, DormancyNotice>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RecoverDormantBalanceCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_MINTING) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        has_one = authority,
+        has_one = mint
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"escheatment", mint.key().as_ref()],
+        bump,
+        has_one = custody
+    )]
+    pub policy: Account<'info, EscheatmentPolicy>,
+
+    #[account(
+        mut,
+        constraint = token_account.mint == mint.key()
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub custody: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"dormancy", token_account.key().as_ref()],
+        bump
+    )]
+    pub notice: Account<'info, DormancyNotice>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as mint authority and permanent delegate
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,200 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+
+pub fn handle_create_factory(ctx: Context<CreateFactoryCTX>) -> Result<()> {
+    ctx.accounts.factory.set_inner(TokenFactory {
+        authority: ctx.accounts.authority.key(),
+        token_count: 0,
+        collection_mint: None,
+    });
+    let stats = &mut ctx.accounts.protocol_stats;
+    stats.factories = stats.factories.saturating_add(1);
+    msg!(
+        "Factory created with authority: {}",
+        ctx.accounts.authority.key()
+    );
+    emit_cpi!(FactoryCreated {
+        factory: ctx.accounts.factory.key(),
+        authority: ctx.accounts.authority.key(),
+    });
+    Ok(())
+}
+
+// Shared by every token the factory created whose `WhitelistSource` lets
+// the hook look past its own lists
+pub fn handle_add_factory_whitelist_entry(
+    ctx: Context<AddFactoryWhitelistEntryCTX>,
+    owner: Pubkey,
+    expires_at: Option<i64>,
+) -> Result<()> {
+    if let Some(expires_at) = expires_at {
+        require!(
+            expires_at > unix_timestamp(ctx.remaining_accounts)?,
+            ErrorCode::InvalidWhitelistExpiry
+        );
+    }
+    ctx.accounts.entry.expires_at = expires_at;
+    msg!(
+        "Factory whitelist entry added: {} (expires: {:?})",
+        owner,
+        expires_at
+    );
+    emit_cpi!(FactoryWhitelistUpdated {
+        factory: ctx.accounts.factory.key(),
+        change: WhitelistChange::EntryAdded { owner },
+    });
+    Ok(())
+}
+
+pub fn handle_remove_factory_whitelist_entry(
+    ctx: Context<RemoveFactoryWhitelistEntryCTX>,
+    owner: Pubkey,
+) -> Result<()> {
+    msg!("Factory whitelist entry removed: {}", owner);
+    emit_cpi!(FactoryWhitelistUpdated {
+        factory: ctx.accounts.factory.key(),
+        change: WhitelistChange::EntryRemoved { owner },
+    });
+    Ok(())
+}
+
+pub fn handle_prune_expired_factory_entry(
+    ctx: Context<PruneExpiredFactoryEntryCTX>,
+    owner: Pubkey,
+) -> Result<()> {
+    require!(
+        ctx.accounts
+            .entry
+            .is_expired(unix_timestamp(ctx.remaining_accounts)?),
+        ErrorCode::WhitelistEntryNotExpired
+    );
+    msg!("Expired factory whitelist entry pruned: {}", owner);
+    emit_cpi!(FactoryWhitelistUpdated {
+        factory: ctx.accounts.factory.key(),
+        change: WhitelistChange::EntryExpired { owner },
+    });
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreateFactoryCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_TOKEN_CREATION) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = TokenFactory::SPACE,
+        seeds = [b"factory", authority.key().as_ref()],
+        bump
+    )]
+    pub factory: Account<'info, TokenFactory>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_stats"],
+        bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct AddFactoryWhitelistEntryCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        has_one = authority,
+        seeds = [b"factory", authority.key().as_ref()],
+        bump
+    )]
+    pub factory: Account<'info, TokenFactory>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = WhitelistEntry::SPACE,
+        seeds = [b"factory_wl", factory.key().as_ref(), owner.as_ref()],
+        bump
+    )]
+    pub entry: Account<'info, WhitelistEntry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct RemoveFactoryWhitelistEntryCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        has_one = authority,
+        seeds = [b"factory", authority.key().as_ref()],
+        bump
+    )]
+    pub factory: Account<'info, TokenFactory>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"factory_wl", factory.key().as_ref(), owner.as_ref()],
+        bump
+    )]
+    pub entry: Account<'info, WhitelistEntry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct PruneExpiredFactoryEntryCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub factory: Account<'info, TokenFactory>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"factory_wl", factory.key().as_ref(), owner.as_ref()],
+        bump
+    )]
+    pub entry: Account<'info, WhitelistEntry>,
+
+    /// CHECK: receives the rent, only checked against the factory
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+}
@@ -0,0 +1,442 @@
+use crate::errors::ErrorCode;
+use crate::hook::{whitelisted_by_source, LookupStats, OwnerLookups};
+use crate::state::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    freeze_account, thaw_account, FreezeAccount, Mint, ThawAccount, TokenAccount, TokenInterface,
+};
+
+pub fn handle_freeze_batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, FreezeBatchCTX<'info>>,
+) -> Result<()> {
+    set_frozen(ctx, true)
+}
+
+pub fn handle_thaw_batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, FreezeBatchCTX<'info>>,
+) -> Result<()> {
+    set_frozen(ctx, false)
+}
+
+// Token accounts to update are passed as remaining accounts, one page per
+// call. Accounts already in the target state are skipped, so a campaign can
+// be resumed or retried from any page.
+#[derive(Accounts)]
+pub struct FreezeBatchCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        has_one = authority,
+        has_one = mint
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as freeze authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+fn set_frozen<'info>(
+    ctx: Context<'_, '_, 'info, 'info, FreezeBatchCTX<'info>>,
+    freeze: bool,
+) -> Result<()> {
+    ctx.accounts.token_data.require_unlocked(LOCK_PAUSE)?;
+    require!(!ctx.remaining_accounts.is_empty(), ErrorCode::InvalidAmount);
+    ctx.accounts
+        .program_config
+        .limits
+        .require_batch_size(ctx.remaining_accounts.len())?;
+
+    let creator = ctx.accounts.token_data.creator;
+    let seeds = &[
+        b"mint_authority",
+        creator.as_ref(),
+        &[ctx.bumps.mint_authority_pda],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    let mut updated = 0u32;
+    for account_info in ctx.remaining_accounts {
+        let token_account = InterfaceAccount::<TokenAccount>::try_from(account_info)?;
+        require_keys_eq!(
+            token_account.mint,
+            ctx.accounts.mint.key(),
+            ErrorCode::Unauthorized
+        );
+        if token_account.is_frozen() == freeze {
+            continue;
+        }
+
+        let token_program = ctx.accounts.token_program.to_account_info();
+        if freeze {
+            freeze_account(CpiContext::new_with_signer(
+                token_program,
+                FreezeAccount {
+                    account: account_info.clone(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    authority: ctx.accounts.mint_authority_pda.to_account_info(),
+                },
+                signer_seeds,
+            ))?;
+        } else {
+            thaw_account(CpiContext::new_with_signer(
+                token_program,
+                ThawAccount {
+                    account: account_info.clone(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    authority: ctx.accounts.mint_authority_pda.to_account_info(),
+                },
+                signer_seeds,
+            ))?;
+        }
+        updated += 1;
+    }
+
+    msg!(
+        "{} {} of {} token accounts",
+        if freeze { "Froze" } else { "Thawed" },
+        updated,
+        ctx.remaining_accounts.len()
+    );
+    Ok(())
+}
+
+// For mints created with `default_frozen`. Both are permissionless: anyone
+// can thaw an account whose owner is whitelisted, or refreeze one whose
+// owner no longer is, short of the authority's and program addresses'.
+pub fn handle_thaw_account(ctx: Context<WhitelistGateCTX>) -> Result<()> {
+    require!(
+        ctx.accounts
+            .owner_whitelisted(unix_timestamp(ctx.remaining_accounts)?)?,
+        ErrorCode::AddressNotWhitelisted
+    );
+    if !ctx.accounts.token_account.is_frozen() {
+        return Ok(());
+    }
+
+    let creator = ctx.accounts.token_data.creator;
+    let seeds = &[
+        b"mint_authority",
+        creator.as_ref(),
+        &[ctx.bumps.mint_authority_pda],
+    ];
+    thaw_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        ThawAccount {
+            account: ctx.accounts.token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.mint_authority_pda.to_account_info(),
+        },
+        &[&seeds[..]],
+    ))?;
+
+    msg!("Thawed token account {}", ctx.accounts.token_account.key());
+    Ok(())
+}
+
+pub fn handle_refreeze_account(ctx: Context<WhitelistGateCTX>) -> Result<()> {
+    // Treasury and vault owners are PDAs, which are never whitelisted;
+    // freezing them, or the authority's account, would stall their payouts
+    let owner = ctx.accounts.token_account.owner;
+    require!(
+        owner != ctx.accounts.token_data.authority && owner.is_on_curve(),
+        ErrorCode::RefreezeExempt
+    );
+    require!(
+        !ctx.accounts
+            .owner_whitelisted(unix_timestamp(ctx.remaining_accounts)?)?,
+        ErrorCode::AddressStillWhitelisted
+    );
+    if ctx.accounts.token_account.is_frozen() {
+        return Ok(());
+    }
+
+    let creator = ctx.accounts.token_data.creator;
+    let seeds = &[
+        b"mint_authority",
+        creator.as_ref(),
+        &[ctx.bumps.mint_authority_pda],
+    ];
+    freeze_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        FreezeAccount {
+            account: ctx.accounts.token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.mint_authority_pda.to_account_info(),
+        },
+        &[&seeds[..]],
+    ))?;
+
+    msg!("Refroze token account {}", ctx.accounts.token_account.key());
+    Ok(())
+}
+
+// Whitelist accounts mirror the hook's for the token account's owner
+#[derive(Accounts)]
+pub struct WhitelistGateCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = mint)]
+    pub token_data: Account<'info, TokenData>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Whitelist page for the owner, may not exist yet
+    #[account(
+        seeds = [b"whitelist", token_data.whitelist_mint.as_ref(), &[whitelist_page_of(&token_account.owner)]],
+        bump
+    )]
+    pub whitelist: UncheckedAccount<'info>,
+
+    /// CHECK: WhitelistedTokenAccount entry, may not exist
+    #[account(
+        seeds = [b"whitelisted_account", mint.key().as_ref(), token_account.key().as_ref()],
+        bump
+    )]
+    pub token_account_entry: UncheckedAccount<'info>,
+
+    /// CHECK: WhitelistEntry for the owner, may not exist
+    #[account(
+        seeds = [b"wl", token_data.whitelist_mint.as_ref(), token_account.owner.as_ref()],
+        bump
+    )]
+    pub whitelist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: Membership credential for the owner, may not exist
+    #[account(
+        seeds = [b"credential", token_data.whitelist_mint.as_ref(), token_account.owner.as_ref()],
+        bump
+    )]
+    pub credential: UncheckedAccount<'info>,
+
+    /// CHECK: Factory WhitelistEntry for the owner, may not exist
+    #[account(
+        seeds = [b"factory_wl", token_data.factory.as_ref(), token_account.owner.as_ref()],
+        bump
+    )]
+    pub factory_whitelist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: NftPass for the owner, may not exist
+    #[account(
+        seeds = [b"nft_pass", mint.key().as_ref(), token_account.owner.as_ref()],
+        bump
+    )]
+    pub nft_pass: UncheckedAccount<'info>,
+
+    /// CHECK: Account holding the owner's pass NFT, may not exist
+    #[account(
+        seeds = [b"nft_pass_account", mint.key().as_ref(), token_account.owner.as_ref()],
+        bump
+    )]
+    pub nft_pass_account: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as freeze authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl WhitelistGateCTX<'_> {
+    // With whitelisting disabled every owner counts as whitelisted
+    fn owner_whitelisted(&self, now: i64) -> Result<bool> {
+        if self.token_data.whitelist_policy == WhitelistPolicy::Disabled {
+            return Ok(true);
+        }
+        whitelisted_by_source(
+            &self.token_data,
+            &self.token_account.owner,
+            OwnerLookups {
+                factory_entry: &self.factory_whitelist_entry,
+                entry: &self.whitelist_entry,
+                credential: &self.credential,
+                page: &self.whitelist,
+                token_account_entry: &self.token_account_entry,
+                nft_pass: &self.nft_pass,
+                nft_pass_account: &self.nft_pass_account,
+            },
+            now,
+            &mut LookupStats::untracked(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use std::collections::BTreeSet;
+
+    struct Gate {
+        token_data: TokenData,
+        owner: Pubkey,
+        token_account: Pubkey,
+        lookups: Vec<(Pubkey, &'static AccountInfo<'static>)>,
+    }
+
+    impl Gate {
+        /// A token gated on an NFT collection, whose owner holds a pass.
+        fn with_nft_pass() -> Self {
+            let mint = Pubkey::new_unique();
+            let owner = wallet();
+            let collection = Pubkey::new_unique();
+            let mut token_data = token_data(mint, Pubkey::new_unique());
+            token_data.nft_gate_collection = Some(collection);
+
+            let nft_mint = Pubkey::new_unique();
+            let pass_account = pda(&[b"nft_pass_account", mint.as_ref(), owner.as_ref()]);
+            let pass = NftPass {
+                collection,
+                nft_mint,
+                nft_token_account: pass_account,
+            };
+            let pass_key = pda(&[b"nft_pass", mint.as_ref(), owner.as_ref()]);
+            Self {
+                token_data,
+                owner,
+                token_account: Pubkey::new_unique(),
+                lookups: vec![
+                    (pass_key, program_account(pass_key, &pass)),
+                    (
+                        pass_account,
+                        token_account(pass_account, nft_mint, owner, 1, false),
+                    ),
+                ],
+            }
+        }
+
+        fn lookup(&self, seeds: &[&[u8]]) -> AccountInfo<'static> {
+            let key = pda(seeds);
+            self.lookups
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map_or_else(|| missing_account(key), |(_, info)| *info)
+                .clone()
+        }
+
+        fn thaw(&self) -> Result<()> {
+            self.run(false, handle_thaw_account)
+        }
+
+        fn refreeze(&self) -> Result<()> {
+            self.run(true, handle_refreeze_account)
+        }
+
+        // The account already sits in the state the handler would move it
+        // to, so only the whitelist check runs: CPIs aren't available here
+        fn run(
+            &self,
+            frozen: bool,
+            handler: fn(Context<WhitelistGateCTX>) -> Result<()>,
+        ) -> Result<()> {
+            use_fixed_clock();
+            let (mint, owner) = (self.token_data.mint, self.owner);
+            let whitelist_mint = self.token_data.whitelist_mint;
+            let infos = vec![
+                program_account(pda(&[b"program_config"]), &program_config(0)).clone(),
+                program_account(Pubkey::new_unique(), &self.token_data).clone(),
+                mint_account(mint, 1_000_000, self.token_data.decimals).clone(),
+                token_account(self.token_account, mint, owner, 100, frozen).clone(),
+                self.lookup(&[
+                    b"whitelist",
+                    whitelist_mint.as_ref(),
+                    &[whitelist_page_of(&owner)],
+                ]),
+                self.lookup(&[
+                    b"whitelisted_account",
+                    mint.as_ref(),
+                    self.token_account.as_ref(),
+                ]),
+                self.lookup(&[b"wl", whitelist_mint.as_ref(), owner.as_ref()]),
+                self.lookup(&[b"credential", whitelist_mint.as_ref(), owner.as_ref()]),
+                self.lookup(&[
+                    b"factory_wl",
+                    self.token_data.factory.as_ref(),
+                    owner.as_ref(),
+                ]),
+                self.lookup(&[b"nft_pass", mint.as_ref(), owner.as_ref()]),
+                self.lookup(&[b"nft_pass_account", mint.as_ref(), owner.as_ref()]),
+                missing_account(pda(&[b"mint_authority", self.token_data.creator.as_ref()]))
+                    .clone(),
+                executable_account(anchor_spl::token_2022::ID).clone(),
+            ];
+            let infos: &'static [AccountInfo<'static>] = Box::leak(infos.into_boxed_slice());
+
+            let mut bumps = WhitelistGateCTXBumps::default();
+            let mut accounts = WhitelistGateCTX::try_accounts(
+                &crate::ID,
+                &mut &infos[..],
+                &[],
+                &mut bumps,
+                &mut BTreeSet::new(),
+            )?;
+            handler(Context::new(&crate::ID, &mut accounts, &[], bumps))
+        }
+    }
+
+    #[test]
+    fn nft_pass_thaws_under_a_token_source() {
+        let gate = Gate::with_nft_pass();
+        gate.thaw().unwrap();
+        assert_eq!(
+            gate.refreeze().unwrap_err(),
+            ErrorCode::AddressStillWhitelisted.into()
+        );
+    }
+
+    #[test]
+    fn nft_pass_is_ignored_under_a_factory_only_source() {
+        let mut gate = Gate::with_nft_pass();
+        gate.token_data.whitelist_source = WhitelistSource::FactoryOnly;
+        assert_eq!(
+            gate.thaw().unwrap_err(),
+            ErrorCode::AddressNotWhitelisted.into()
+        );
+        gate.refreeze().unwrap();
+    }
+
+    #[test]
+    fn authority_and_program_accounts_are_never_refrozen() {
+        let mut gate = Gate::with_nft_pass();
+        gate.token_data.nft_gate_collection = None;
+        gate.owner = gate.token_data.authority;
+        assert_eq!(
+            gate.refreeze().unwrap_err(),
+            ErrorCode::RefreezeExempt.into()
+        );
+
+        gate.owner = pda(&[b"treasury_authority", gate.token_data.mint.as_ref()]);
+        assert_eq!(
+            gate.refreeze().unwrap_err(),
+            ErrorCode::RefreezeExempt.into()
+        );
+    }
+}
@@ -0,0 +1,41 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+pub fn handle_initialize_idempotency_log(ctx: Context<InitializeIdempotencyLogCTX>) -> Result<()> {
+    ctx.accounts.idempotency_log.set_inner(IdempotencyLog {
+        keys: [[0u8; 16]; IDEMPOTENCY_LOG_SIZE],
+        next: 0,
+    });
+    msg!(
+        "Idempotency log created for mint: {}",
+        ctx.accounts.token_data.mint
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeIdempotencyLogCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + (16 * IDEMPOTENCY_LOG_SIZE) + 1,
+        seeds = [b"idempotency", token_data.key().as_ref()],
+        bump
+    )]
+    pub idempotency_log: Account<'info, IdempotencyLog>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
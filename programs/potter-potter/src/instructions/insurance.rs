@@ -0,0 +1,313 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::hook::*;
+use crate::state::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+pub fn handle_create_insurance_fund(
+    ctx: Context<CreateInsuranceFundCTX>,
+    guardian: Pubkey,
+    fee_share_bps: u16,
+    veto_period: i64,
+) -> Result<()> {
+    require!(fee_share_bps <= 10_000, ErrorCode::InvalidFeeShare);
+    require!(veto_period >= 0, ErrorCode::InvalidAmount);
+
+    VaultMint {
+        token_program: &ctx.accounts.token_program,
+        mint: &ctx.accounts.mint,
+        mint_authority_pda: &ctx.accounts.mint_authority_pda,
+        creator: ctx.accounts.token_data.creator,
+        mint_authority_bump: ctx.bumps.mint_authority_pda,
+    }
+    .thaw_if_frozen(&ctx.accounts.vault)?;
+
+    ctx.accounts.insurance_fund.set_inner(InsuranceFund {
+        mint: ctx.accounts.mint.key(),
+        guardian,
+        fee_share_bps,
+        veto_period,
+        next_claim_id: 0,
+    });
+
+    emit_cpi!(InsuranceFundCreated {
+        mint: ctx.accounts.mint.key(),
+        guardian,
+        fee_share_bps,
+        veto_period,
+    });
+    msg!(
+        "Insurance fund created with {} bps of fees, guardian {}",
+        fee_share_bps,
+        guardian
+    );
+    Ok(())
+}
+
+// Payouts are two-step: the authority queues one with its evidence, and it
+// can only be paid once the guardian has had `veto_period` to object
+pub fn handle_payout_claim(
+    ctx: Context<PayoutClaimCTX>,
+    recipient: Pubkey,
+    raw_amount: u64,
+    evidence_hash: [u8; 32],
+) -> Result<()> {
+    require!(raw_amount > 0, ErrorCode::InvalidAmount);
+
+    let fund = &mut ctx.accounts.insurance_fund;
+    let claim_id = fund.next_claim_id;
+    let ready_at = unix_timestamp(ctx.remaining_accounts)?.saturating_add(fund.veto_period);
+    ctx.accounts.payout_claim.set_inner(PayoutClaim {
+        claim_id,
+        recipient,
+        raw_amount,
+        evidence_hash,
+        ready_at,
+    });
+    fund.next_claim_id = claim_id.checked_add(1).ok_or(ErrorCode::Overflow)?;
+
+    emit_cpi!(PayoutClaimQueued {
+        mint: fund.mint,
+        claim_id,
+        recipient,
+        raw_amount,
+        evidence_hash,
+        ready_at,
+    });
+    msg!("Payout claim {} queued, payable at {}", claim_id, ready_at);
+    Ok(())
+}
+
+pub fn handle_veto_payout_claim(ctx: Context<VetoPayoutClaimCTX>, claim_id: u64) -> Result<()> {
+    emit_cpi!(PayoutClaimVetoed {
+        mint: ctx.accounts.insurance_fund.mint,
+        claim_id,
+        guardian: ctx.accounts.guardian.key(),
+    });
+    msg!("Payout claim {} vetoed", claim_id);
+    Ok(())
+}
+
+// As with the treasury, the vault pays out through `VaultMint`, since a
+// transfer CPI would re-enter this program through the hook
+pub fn handle_execute_payout_claim(
+    ctx: Context<ExecutePayoutClaimCTX>,
+    claim_id: u64,
+) -> Result<()> {
+    ctx.accounts
+        .program_config
+        .require_feature_enabled(FEATURE_MINTING)?;
+    let claim = &ctx.accounts.payout_claim;
+    let now = unix_timestamp(ctx.remaining_accounts)?;
+    require!(now >= claim.ready_at, ErrorCode::PayoutVetoPeriodActive);
+    // The payout is minted, skipping the hook, so its rules run here
+    ctx.accounts.receiver.require_can_receive(
+        &ctx.accounts.token_data,
+        &ctx.accounts.recipient,
+        claim.raw_amount,
+        now,
+    )?;
+
+    let mint_key = ctx.accounts.mint.key();
+    let fund_seeds = &[b"insurance", mint_key.as_ref(), &[ctx.bumps.insurance_fund]];
+    VaultMint {
+        token_program: &ctx.accounts.token_program,
+        mint: &ctx.accounts.mint,
+        mint_authority_pda: &ctx.accounts.mint_authority_pda,
+        creator: ctx.accounts.token_data.creator,
+        mint_authority_bump: ctx.bumps.mint_authority_pda,
+    }
+    .release(
+        &ctx.accounts.vault,
+        &ctx.accounts.insurance_fund.to_account_info(),
+        fund_seeds,
+        &ctx.accounts.recipient,
+        claim.raw_amount,
+    )?;
+
+    emit_cpi!(PayoutClaimPaid {
+        mint: mint_key,
+        claim_id,
+        recipient: claim.recipient,
+        raw_amount: claim.raw_amount,
+        evidence_hash: claim.evidence_hash,
+    });
+    msg!("Payout claim {} paid", claim_id);
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreateInsuranceFundCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority, has_one = mint)]
+    pub token_data: Account<'info, TokenData>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = InsuranceFund::SPACE,
+        seeds = [b"insurance", mint.key().as_ref()],
+        bump
+    )]
+    pub insurance_fund: Account<'info, InsuranceFund>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"insurance_vault", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = insurance_fund,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as freeze authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct PayoutClaimCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"insurance", token_data.mint.as_ref()],
+        bump
+    )]
+    pub insurance_fund: Account<'info, InsuranceFund>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = PayoutClaim::SPACE,
+        seeds = [
+            b"payout_claim",
+            token_data.mint.as_ref(),
+            &insurance_fund.next_claim_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub payout_claim: Account<'info, PayoutClaim>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(claim_id: u64)]
+pub struct VetoPayoutClaimCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = guardian)]
+    pub insurance_fund: Account<'info, InsuranceFund>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"payout_claim", insurance_fund.mint.as_ref(), &claim_id.to_le_bytes()],
+        bump
+    )]
+    pub payout_claim: Account<'info, PayoutClaim>,
+
+    #[account(has_one = authority, constraint = token_data.mint == insurance_fund.mint)]
+    pub token_data: Account<'info, TokenData>,
+
+    /// CHECK: Gets the claim's rent back; checked against the token data
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+
+    pub guardian: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(claim_id: u64)]
+pub struct ExecutePayoutClaimCTX<'info> {
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority, has_one = mint)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"insurance", mint.key().as_ref()],
+        bump
+    )]
+    pub insurance_fund: Account<'info, InsuranceFund>,
+
+    #[account(
+        mut,
+        seeds = [b"insurance_vault", mint.key().as_ref()],
+        bump
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"payout_claim", mint.key().as_ref(), &claim_id.to_le_bytes()],
+        bump
+    )]
+    pub payout_claim: Account<'info, PayoutClaim>,
+
+    #[account(
+        mut,
+        address = payout_claim.recipient,
+        constraint = recipient.mint == mint.key()
+    )]
+    pub recipient: InterfaceAccount<'info, TokenAccount>,
+
+    /// Lookups for `recipient`
+    pub receiver: ReceiverChecks<'info>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as mint authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,71 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    interest_bearing_mint_update_rate, InterestBearingMintUpdateRate, Mint, TokenInterface,
+};
+
+pub fn handle_update_interest_rate(
+    ctx: Context<UpdateInterestRateCTX>,
+    rate_bps: i16,
+) -> Result<()> {
+    let creator = ctx.accounts.token_data.creator;
+    let seeds = &[
+        b"mint_authority",
+        creator.as_ref(),
+        &[ctx.bumps.mint_authority_pda],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    // Interest only changes the displayed UI amount; balances stay the same
+    interest_bearing_mint_update_rate(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            InterestBearingMintUpdateRate {
+                token_program_id: ctx.accounts.token_program.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                rate_authority: ctx.accounts.mint_authority_pda.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        rate_bps,
+    )?;
+
+    emit_cpi!(InterestRateUpdated {
+        mint: ctx.accounts.mint.key(),
+        rate_bps,
+    });
+    msg!("Interest rate set to {} bps", rate_bps);
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateInterestRateCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        has_one = authority,
+        has_one = mint
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as interest rate authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,51 @@
+use crate::errors::ErrorCode;
+use crate::hook::EXTRA_ACCOUNT_METAS_VERSION;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+// Permissionless: the manifest only restates on-chain state, so anyone can
+// create it or bring it up to date
+pub fn handle_sync_hook_manifest(ctx: Context<SyncHookManifestCTX>) -> Result<()> {
+    let manifest = &mut ctx.accounts.hook_manifest;
+    manifest.mint = ctx.accounts.token_data.mint;
+    manifest.hook_program = crate::ID;
+    manifest.extra_account_meta_list = ctx.accounts.extra_account_meta_list.key();
+    manifest.extra_account_meta_list_bump = ctx.bumps.extra_account_meta_list;
+    manifest.layout_version = EXTRA_ACCOUNT_METAS_VERSION;
+    manifest.refresh(&ctx.accounts.token_data)?;
+
+    msg!("Hook manifest synced for mint {}", manifest.mint);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SyncHookManifestCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub token_data: Account<'info, TokenData>,
+
+    /// CHECK: Only its address and bump are recorded
+    #[account(
+        seeds = [b"extra-account-metas", token_data.mint.as_ref()],
+        bump
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = HookManifest::SPACE,
+        seeds = [b"hook_manifest", token_data.mint.as_ref()],
+        bump
+    )]
+    pub hook_manifest: Account<'info, HookManifest>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,521 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::token_interface::Mint;
+use mpl_token_metadata::instructions::{UpdateV1, UpdateV1InstructionArgs};
+use mpl_token_metadata::types::{
+    CollectionDetailsToggle, CollectionToggle, Data, RuleSetToggle, UsesToggle,
+};
+use mpl_token_metadata::ID as MPL_TOKEN_METADATA_ID;
+
+pub fn handle_queue_metadata_update(
+    ctx: Context<QueueMetadataUpdateCTX>,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Result<()> {
+    ctx.accounts.token_data.require_unlocked(LOCK_METADATA)?;
+    require!(name.len() <= 32, ErrorCode::NameTooLong);
+    require!(symbol.len() <= 10, ErrorCode::SymbolTooLong);
+    require!(uri.len() <= 200, ErrorCode::UriTooLong);
+
+    let ready_at = unix_timestamp(ctx.remaining_accounts)?
+        .checked_add(ctx.accounts.token_data.metadata_delay)
+        .ok_or(ErrorCode::InvalidAmount)?;
+
+    ctx.accounts
+        .pending_update
+        .set_inner(PendingMetadataUpdate {
+            name: name.clone(),
+            symbol: symbol.clone(),
+            uri: uri.clone(),
+            ready_at,
+        });
+
+    emit_cpi!(MetadataUpdateQueued {
+        mint: ctx.accounts.token_data.mint,
+        name,
+        symbol,
+        uri,
+        ready_at,
+    });
+    msg!("Metadata update queued, ready at {}", ready_at);
+    Ok(())
+}
+
+pub fn handle_apply_metadata_update(ctx: Context<ApplyMetadataUpdateCTX>) -> Result<()> {
+    ctx.accounts.token_data.require_unlocked(LOCK_METADATA)?;
+    let pending = &ctx.accounts.pending_update;
+    require!(
+        unix_timestamp(ctx.remaining_accounts)? >= pending.ready_at,
+        ErrorCode::TimelockNotElapsed
+    );
+
+    let bump_seed = [ctx.bumps.mint_authority_pda];
+    let signer_seeds = &[&[
+        b"mint_authority",
+        ctx.accounts.token_data.creator.as_ref(),
+        &bump_seed,
+    ][..]];
+
+    MetadataCpiAccounts {
+        mint_authority_pda: ctx.accounts.mint_authority_pda.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        metadata: ctx.accounts.metadata.to_account_info(),
+        payer: ctx.accounts.authority.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        sysvar_instructions: ctx.accounts.sysvar_instructions.to_account_info(),
+        token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+    }
+    .update(
+        pending.name.clone(),
+        pending.symbol.clone(),
+        pending.uri.clone(),
+        signer_seeds,
+    )?;
+
+    let token_data = &mut ctx.accounts.token_data;
+    token_data.name = pending.name.clone();
+    token_data.symbol = pending.symbol.clone();
+    token_data.uri = pending.uri.clone();
+
+    emit_cpi!(MetadataUpdateApplied {
+        mint: token_data.mint,
+        name: token_data.name.clone(),
+        symbol: token_data.symbol.clone(),
+        uri: token_data.uri.clone(),
+    });
+    msg!("Metadata update applied");
+    Ok(())
+}
+
+pub fn handle_update_metadata(
+    ctx: Context<UpdateMetadataCTX>,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Result<()> {
+    ctx.accounts.token_data.require_unlocked(LOCK_METADATA)?;
+    // With a delay set, changes must go through the queue
+    require!(
+        ctx.accounts.token_data.metadata_delay == 0,
+        ErrorCode::MetadataDelayActive
+    );
+    require!(name.len() <= 32, ErrorCode::NameTooLong);
+    require!(symbol.len() <= 10, ErrorCode::SymbolTooLong);
+    require!(uri.len() <= 200, ErrorCode::UriTooLong);
+
+    let bump_seed = [ctx.bumps.mint_authority_pda];
+    let signer_seeds = &[&[
+        b"mint_authority",
+        ctx.accounts.token_data.creator.as_ref(),
+        &bump_seed,
+    ][..]];
+
+    MetadataCpiAccounts {
+        mint_authority_pda: ctx.accounts.mint_authority_pda.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        metadata: ctx.accounts.metadata.to_account_info(),
+        payer: ctx.accounts.authority.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        sysvar_instructions: ctx.accounts.sysvar_instructions.to_account_info(),
+        token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+    }
+    .update(name.clone(), symbol.clone(), uri.clone(), signer_seeds)?;
+
+    let token_data = &mut ctx.accounts.token_data;
+    token_data.name = name;
+    token_data.symbol = symbol;
+    token_data.uri = uri;
+
+    emit_cpi!(MetadataUpdateApplied {
+        mint: token_data.mint,
+        name: token_data.name.clone(),
+        symbol: token_data.symbol.clone(),
+        uri: token_data.uri.clone(),
+    });
+    msg!("Metadata updated");
+    Ok(())
+}
+
+pub fn handle_cancel_metadata_update(ctx: Context<CancelMetadataUpdateCTX>) -> Result<()> {
+    msg!("Metadata update cancelled");
+    emit_cpi!(MetadataUpdateCancelled {
+        mint: ctx.accounts.token_data.mint,
+    });
+    Ok(())
+}
+
+pub fn handle_set_token_profile(
+    ctx: Context<SetTokenProfileCTX>,
+    description_hash: Option<[u8; 32]>,
+    website: String,
+    socials_hash: Option<[u8; 32]>,
+) -> Result<()> {
+    ctx.accounts.token_data.require_unlocked(LOCK_METADATA)?;
+    require!(website.len() <= MAX_WEBSITE_LEN, ErrorCode::WebsiteTooLong);
+
+    ctx.accounts.profile.set_inner(TokenProfile {
+        description_hash,
+        website: website.clone(),
+        socials_hash,
+    });
+    msg!(
+        "Token profile updated for mint: {}",
+        ctx.accounts.token_data.mint
+    );
+    emit_cpi!(TokenProfileUpdated {
+        mint: ctx.accounts.token_data.mint,
+        description_hash,
+        website,
+        socials_hash,
+    });
+    Ok(())
+}
+
+pub fn handle_set_localized_metadata(
+    ctx: Context<SetLocalizedMetadataCTX>,
+    locale: String,
+    name: String,
+    uri: String,
+) -> Result<()> {
+    ctx.accounts.token_data.require_unlocked(LOCK_METADATA)?;
+    require!(
+        !locale.is_empty() && locale.len() <= MAX_LOCALE_LEN,
+        ErrorCode::InvalidLocale
+    );
+    require!(name.len() <= 32, ErrorCode::NameTooLong);
+    require!(uri.len() <= 200, ErrorCode::UriTooLong);
+
+    ctx.accounts.localized.set_inner(LocalizedMetadata {
+        locale: locale.clone(),
+        name: name.clone(),
+        uri: uri.clone(),
+    });
+    msg!("Localized metadata set for {}", locale);
+    emit_cpi!(LocalizedMetadataUpdated {
+        mint: ctx.accounts.token_data.mint,
+        locale,
+        name,
+        uri,
+    });
+    Ok(())
+}
+
+pub fn handle_get_localized_metadata(
+    ctx: Context<GetLocalizedMetadataCTX>,
+    _locale: String,
+) -> Result<LocalizedMetadata> {
+    let localized = &ctx.accounts.localized;
+    msg!(
+        "{}: {} ({})",
+        localized.locale,
+        localized.name,
+        localized.uri
+    );
+    Ok(localized.clone().into_inner())
+}
+
+// Accounts for the Metaplex `UpdateV1` CPI, shared by the immediate and
+// queued update paths
+struct MetadataCpiAccounts<'info> {
+    mint_authority_pda: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    metadata: AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    sysvar_instructions: AccountInfo<'info>,
+    token_metadata_program: AccountInfo<'info>,
+}
+
+impl<'info> MetadataCpiAccounts<'info> {
+    fn update(
+        self,
+        name: String,
+        symbol: String,
+        uri: String,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        let ix = UpdateV1 {
+            authority: self.mint_authority_pda.key(),
+            delegate_record: None,
+            token: None,
+            mint: self.mint.key(),
+            metadata: self.metadata.key(),
+            edition: None,
+            payer: self.payer.key(),
+            system_program: self.system_program.key(),
+            sysvar_instructions: sysvar::instructions::ID,
+            authorization_rules_program: None,
+            authorization_rules: None,
+        }
+        .instruction(UpdateV1InstructionArgs {
+            new_update_authority: None,
+            data: Some(Data {
+                name,
+                symbol,
+                uri,
+                seller_fee_basis_points: 0,
+                creators: None,
+            }),
+            primary_sale_happened: None,
+            is_mutable: None,
+            collection: CollectionToggle::None,
+            collection_details: CollectionDetailsToggle::None,
+            uses: UsesToggle::None,
+            rule_set: RuleSetToggle::None,
+            authorization_data: None,
+        });
+
+        invoke_signed(
+            &ix,
+            &[
+                self.mint_authority_pda,
+                self.mint,
+                self.metadata,
+                self.payer,
+                self.system_program,
+                self.sysvar_instructions,
+                self.token_metadata_program,
+            ],
+            signer_seeds,
+        )?;
+        Ok(())
+    }
+}
+
+// One update can be pending per token; cancel it to queue a different one
+#[event_cpi]
+#[derive(Accounts)]
+pub struct QueueMetadataUpdateCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = PendingMetadataUpdate::SPACE,
+        seeds = [b"metadata_update", token_data.key().as_ref()],
+        bump
+    )]
+    pub pending_update: Account<'info, PendingMetadataUpdate>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ApplyMetadataUpdateCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"metadata_update", token_data.key().as_ref()],
+        bump
+    )]
+    pub pending_update: Account<'info, PendingMetadataUpdate>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Validated by token metadata program
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            MPL_TOKEN_METADATA_ID.as_ref(),
+            mint.key().as_ref()
+        ],
+        bump,
+        seeds::program = MPL_TOKEN_METADATA_ID
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as metadata update authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Instructions sysvar
+    #[account(address = sysvar::instructions::ID)]
+    pub sysvar_instructions: UncheckedAccount<'info>,
+
+    /// CHECK: Token Metadata Program
+    #[account(address = MPL_TOKEN_METADATA_ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateMetadataCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Validated by token metadata program
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            MPL_TOKEN_METADATA_ID.as_ref(),
+            mint.key().as_ref()
+        ],
+        bump,
+        seeds::program = MPL_TOKEN_METADATA_ID
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as metadata update authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Instructions sysvar
+    #[account(address = sysvar::instructions::ID)]
+    pub sysvar_instructions: UncheckedAccount<'info>,
+
+    /// CHECK: Token Metadata Program
+    #[account(address = MPL_TOKEN_METADATA_ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelMetadataUpdateCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"metadata_update", token_data.key().as_ref()],
+        bump
+    )]
+    pub pending_update: Account<'info, PendingMetadataUpdate>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetTokenProfileCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = TokenProfile::SPACE,
+        seeds = [b"token_profile", token_data.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, TokenProfile>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(locale: String)]
+pub struct SetLocalizedMetadataCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = LocalizedMetadata::SPACE,
+        seeds = [b"localized_metadata", token_data.key().as_ref(), locale.as_bytes()],
+        bump
+    )]
+    pub localized: Account<'info, LocalizedMetadata>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(locale: String)]
+pub struct GetLocalizedMetadataCTX<'info> {
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        seeds = [b"localized_metadata", token_data.key().as_ref(), locale.as_bytes()],
+        bump
+    )]
+    pub localized: Account<'info, LocalizedMetadata>,
+}
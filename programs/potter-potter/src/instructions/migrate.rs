@@ -0,0 +1,256 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+
+// TokenData as first released, before the whitelist moved to pages
+#[derive(AnchorDeserialize)]
+struct LegacyTokenData {
+    mint: Pubkey,
+    authority: Pubkey,
+    total_supply: u64,
+    decimals: u8,
+    is_paused: bool,
+    is_minting_paused: bool,
+    name: String,
+    symbol: String,
+    uri: String,
+    _whitelist: Pubkey,
+}
+
+impl LegacyTokenData {
+    const SPACE: usize = 8 + 32 + 32 + 8 + 1 + 1 + 1 + (4 + 32) + (4 + 10) + (4 + 200) + 32;
+}
+
+// A legacy account is rewritten field by field, with every setting added
+// since then off; its old whitelist account isn't carried over, so those
+// addresses have to be re-added to pages. Anything newer only needs to
+// grow into the appended fields, with `creator` filled in from the
+// seeds. Either way the account is rewritten over zeroed data so no stale
+// bytes sit behind the strings.
+pub fn handle_migrate_token_data(
+    ctx: Context<MigrateTokenDataCTX>,
+    creator: Pubkey,
+    token_index: u64,
+) -> Result<()> {
+    let info = ctx.accounts.token_data.to_account_info();
+    require!(
+        info.data_len() >= 8 && info.try_borrow_data()?[..8] == *TokenData::DISCRIMINATOR,
+        ErrorCode::InvalidAccountLayout
+    );
+    let old_space = info.data_len();
+    let legacy = if old_space == LegacyTokenData::SPACE {
+        Some(LegacyTokenData::deserialize(
+            &mut &info.try_borrow_data()?[8..],
+        )?)
+    } else {
+        None
+    };
+
+    grow_program_account(
+        &info,
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        TokenData::SPACE,
+    )?;
+
+    let token_data = match legacy {
+        Some(legacy) => TokenData {
+            token_index,
+            mint: legacy.mint,
+            authority: legacy.authority,
+            total_supply: legacy.total_supply,
+            decimals: legacy.decimals,
+            is_paused: legacy.is_paused,
+            is_minting_paused: legacy.is_minting_paused,
+            config_lock: 0,
+            token_account_whitelisting: false,
+            token_account_entries: 0,
+            metadata_delay: 0,
+            whitelist_mint: legacy.mint,
+            factory: Pubkey::find_program_address(&[b"factory", creator.as_ref()], &crate::ID).0,
+            pending_authority: None,
+            max_supply: None,
+            debug_telemetry: false,
+            whitelist_root: None,
+            min_transfer_amount: 0,
+            whitelist_policy: WhitelistPolicy::DestinationOnly,
+            supply_locked: false,
+            burn_allowed_while_paused: false,
+            max_wallet_amount: 0,
+            wallet_cap_treasury: None,
+            daily_transfer_limit: 0,
+            trading_starts_at: 0,
+            name: legacy.name,
+            symbol: legacy.symbol,
+            uri: legacy.uri,
+            circulating_supply: legacy.total_supply,
+            whitelist_source: WhitelistSource::TokenOnly,
+            nft_gate_collection: None,
+            whitelist_mirrors: 0,
+            creator,
+        },
+        None => {
+            let mut token_data = TokenData::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+            if token_data.creator == Pubkey::default() {
+                token_data.creator = creator;
+            }
+            token_data
+        }
+    };
+    require_keys_eq!(
+        token_data.authority,
+        ctx.accounts.authority.key(),
+        ErrorCode::Unauthorized
+    );
+
+    let mut data = info.try_borrow_mut_data()?;
+    data.fill(0);
+    token_data.try_serialize(&mut &mut data[..])?;
+    drop(data);
+
+    msg!(
+        "Token data migrated for mint {}: {} -> {} bytes",
+        token_data.mint,
+        old_space,
+        TokenData::SPACE
+    );
+    emit_cpi!(AccountMigrated {
+        account: info.key(),
+        old_space: old_space as u64,
+        new_space: TokenData::SPACE as u64,
+    });
+    Ok(())
+}
+
+// Factories created before collections read `collection_mint` as `None`
+// once grown
+pub fn handle_migrate_factory(ctx: Context<MigrateFactoryCTX>) -> Result<()> {
+    let factory = &ctx.accounts.factory;
+    require!(
+        factory.data_len() >= 8 && factory.try_borrow_data()?[..8] == *TokenFactory::DISCRIMINATOR,
+        ErrorCode::InvalidAccountLayout
+    );
+    let old_space = factory.data_len();
+    grow_program_account(
+        factory,
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        TokenFactory::SPACE,
+    )?;
+
+    msg!("Factory migrated: {}", factory.key());
+    emit_cpi!(AccountMigrated {
+        account: factory.key(),
+        old_space: old_space as u64,
+        new_space: factory.data_len() as u64,
+    });
+    Ok(())
+}
+
+// Entries created before expiries or suspensions were added are shorter;
+// the zeroed bytes they grow by read back as `expires_at: None` and not
+// suspended
+pub fn handle_migrate_whitelist_entry(
+    ctx: Context<MigrateWhitelistEntryCTX>,
+    owner: Pubkey,
+) -> Result<()> {
+    let entry = &ctx.accounts.entry;
+    require!(
+        entry.data_len() >= 8 && entry.try_borrow_data()?[..8] == *WhitelistEntry::DISCRIMINATOR,
+        ErrorCode::InvalidAccountLayout
+    );
+    let old_space = entry.data_len();
+    grow_program_account(
+        entry,
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        WhitelistEntry::SPACE,
+    )?;
+
+    msg!("Whitelist entry migrated: {}", owner);
+    emit_cpi!(AccountMigrated {
+        account: entry.key(),
+        old_space: old_space as u64,
+        new_space: entry.data_len() as u64,
+    });
+    Ok(())
+}
+
+// Token data is keyed by its creator, who may since have handed the token
+// over; the handler checks the stored authority
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(creator: Pubkey, token_index: u64)]
+pub struct MigrateTokenDataCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    /// CHECK: TokenData in any past layout, checked by the handler
+    #[account(
+        mut,
+        seeds = [b"token", creator.as_ref(), &token_index.to_le_bytes()],
+        bump
+    )]
+    pub token_data: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Keyed by the signer, so only the factory's own authority can grow it
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateFactoryCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    /// CHECK: TokenFactory in any past layout, checked by the handler
+    #[account(
+        mut,
+        seeds = [b"factory", authority.key().as_ref()],
+        bump
+    )]
+    pub factory: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct MigrateWhitelistEntryCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    /// CHECK: WhitelistEntry in any past layout, checked by the handler
+    #[account(
+        mut,
+        seeds = [b"wl", token_data.mint.as_ref(), owner.as_ref()],
+        bump
+    )]
+    pub entry: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
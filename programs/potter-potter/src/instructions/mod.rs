@@ -0,0 +1,75 @@
+mod admin;
+mod blacklist;
+mod budget;
+mod checkpoint;
+mod circuit_breaker;
+mod clawback;
+mod close;
+mod collection;
+mod config;
+mod create_token;
+mod credential;
+mod custodian;
+mod distribution;
+mod escheatment;
+mod factory;
+mod freeze;
+mod idempotency;
+mod insurance;
+mod interest;
+mod manifest;
+mod metadata;
+mod migrate;
+mod nft_pass;
+mod permit;
+mod role;
+mod rules_digest;
+mod stats;
+mod supply;
+mod swap;
+mod timelock;
+mod transfer_fee;
+mod transfer_window;
+mod treasury;
+mod vault;
+mod vesting;
+mod whitelist;
+mod whitelist_import;
+
+pub use admin::*;
+pub use blacklist::*;
+pub use budget::*;
+pub use checkpoint::*;
+pub use circuit_breaker::*;
+pub use clawback::*;
+pub use close::*;
+pub use collection::*;
+pub use config::*;
+pub use create_token::*;
+pub use credential::*;
+pub use custodian::*;
+pub use distribution::*;
+pub use escheatment::*;
+pub use factory::*;
+pub use freeze::*;
+pub use idempotency::*;
+pub use insurance::*;
+pub use interest::*;
+pub use manifest::*;
+pub use metadata::*;
+pub use migrate::*;
+pub use nft_pass::*;
+pub use permit::*;
+pub use role::*;
+pub use rules_digest::*;
+pub use stats::*;
+pub use supply::*;
+pub use swap::*;
+pub use timelock::*;
+pub use transfer_fee::*;
+pub use transfer_window::*;
+pub use treasury::*;
+pub use vault::*;
+pub use vesting::*;
+pub use whitelist::*;
+pub use whitelist_import::*;
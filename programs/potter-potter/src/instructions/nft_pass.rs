@@ -0,0 +1,208 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+use mpl_token_metadata::accounts::Metadata;
+use mpl_token_metadata::ID as MPL_TOKEN_METADATA_ID;
+
+pub fn handle_register_nft_pass(ctx: Context<RegisterNftPassCTX>) -> Result<()> {
+    let collection = ctx
+        .accounts
+        .token_data
+        .nft_gate_collection
+        .ok_or(ErrorCode::NftGateNotConfigured)?;
+
+    // Only a verified collection counts; anyone can claim an unverified one
+    let metadata = Metadata::safe_deserialize(&ctx.accounts.nft_metadata.try_borrow_data()?)
+        .map_err(|_| error!(ErrorCode::InvalidNftPass))?;
+    require!(
+        metadata
+            .collection
+            .is_some_and(|c| c.verified && c.key == collection),
+        ErrorCode::InvalidNftPass
+    );
+    let nft_mint = &ctx.accounts.nft_mint;
+    require!(
+        nft_mint.supply == 1 && nft_mint.decimals == 0,
+        ErrorCode::InvalidNftPass
+    );
+
+    // The NFT moves into the holder's own account at the pass address, so
+    // the hook can find it from the owner alone and see it still there
+    if ctx.accounts.pass_nft_account.amount == 0 {
+        transfer_checked(
+            CpiContext::new(
+                ctx.accounts.nft_token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.nft_token_account.to_account_info(),
+                    mint: nft_mint.to_account_info(),
+                    to: ctx.accounts.pass_nft_account.to_account_info(),
+                    authority: ctx.accounts.holder.to_account_info(),
+                },
+            ),
+            1,
+            0,
+        )?;
+    }
+
+    ctx.accounts.pass.set_inner(NftPass {
+        collection,
+        nft_mint: nft_mint.key(),
+        nft_token_account: ctx.accounts.pass_nft_account.key(),
+    });
+
+    let owner = ctx.accounts.holder.key();
+    msg!("NFT pass registered for {} with {}", owner, nft_mint.key());
+    emit_cpi!(WhitelistUpdated {
+        mint: ctx.accounts.token_data.mint,
+        change: WhitelistChange::NftPassRegistered {
+            owner,
+            nft_mint: nft_mint.key(),
+        },
+    });
+    Ok(())
+}
+
+// Permissionless, like `refreeze_account`: the pass goes once its NFT has
+// left the owner's account or the token gates on another collection
+pub fn handle_revoke_nft_pass(ctx: Context<RevokeNftPassCTX>, owner: Pubkey) -> Result<()> {
+    require!(
+        !pass_still_valid(ctx.accounts)?,
+        ErrorCode::NftPassStillValid
+    );
+    msg!("NFT pass revoked for {}", owner);
+    emit_cpi!(WhitelistUpdated {
+        mint: ctx.accounts.token_data.mint,
+        change: WhitelistChange::NftPassRevoked { owner },
+    });
+    Ok(())
+}
+
+fn pass_still_valid(accounts: &RevokeNftPassCTX) -> Result<bool> {
+    if accounts.token_data.nft_gate_collection != Some(accounts.pass.collection) {
+        return Ok(false);
+    }
+    // A closed account no longer holds anything
+    let nft_token_account = &accounts.nft_token_account;
+    if nft_token_account.data_is_empty() {
+        return Ok(false);
+    }
+    let account = TokenAccount::try_deserialize(&mut &nft_token_account.try_borrow_data()?[..])?;
+    Ok(account.owner == accounts.holder.key()
+        && account.mint == accounts.pass.nft_mint
+        && account.amount == 1)
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RegisterNftPassCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        init,
+        payer = holder,
+        space = NftPass::SPACE,
+        seeds = [b"nft_pass", token_data.mint.as_ref(), holder.key().as_ref()],
+        bump
+    )]
+    pub pass: Account<'info, NftPass>,
+
+    #[account(mint::token_program = nft_token_program)]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+
+    /// Where the holder keeps the NFT today
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = holder,
+        token::token_program = nft_token_program
+    )]
+    pub nft_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Still the holder's; kept from an earlier pass if it was never closed
+    #[account(
+        init_if_needed,
+        payer = holder,
+        seeds = [b"nft_pass_account", token_data.mint.as_ref(), holder.key().as_ref()],
+        bump,
+        token::mint = nft_mint,
+        token::authority = holder,
+        token::token_program = nft_token_program
+    )]
+    pub pass_nft_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Fails the registration while another pass holds this NFT
+    #[account(
+        init,
+        payer = holder,
+        space = NftPassClaim::SPACE,
+        seeds = [b"nft_pass_nft", token_data.mint.as_ref(), nft_mint.key().as_ref()],
+        bump
+    )]
+    pub nft_claim: Account<'info, NftPassClaim>,
+
+    /// CHECK: Token Metadata account of the NFT, parsed by the handler
+    #[account(
+        seeds = [
+            b"metadata",
+            MPL_TOKEN_METADATA_ID.as_ref(),
+            nft_mint.key().as_ref()
+        ],
+        bump,
+        seeds::program = MPL_TOKEN_METADATA_ID
+    )]
+    pub nft_metadata: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub holder: Signer<'info>,
+    pub nft_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct RevokeNftPassCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        close = holder,
+        seeds = [b"nft_pass", token_data.mint.as_ref(), owner.as_ref()],
+        bump
+    )]
+    pub pass: Account<'info, NftPass>,
+
+    #[account(
+        mut,
+        close = holder,
+        seeds = [b"nft_pass_nft", token_data.mint.as_ref(), pass.nft_mint.as_ref()],
+        bump
+    )]
+    pub nft_claim: Account<'info, NftPassClaim>,
+
+    /// CHECK: The token account recorded in the pass, may have been closed
+    #[account(address = pass.nft_token_account)]
+    pub nft_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Pass owner, receives the rent
+    #[account(mut, address = owner)]
+    pub holder: UncheckedAccount<'info>,
+}
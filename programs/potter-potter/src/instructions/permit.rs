@@ -0,0 +1,106 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+
+pub fn handle_issue_transfer_permit(
+    ctx: Context<IssueTransferPermitCTX>,
+    owner: Pubkey,
+    amount_cap: u64,
+    expires_at: i64,
+) -> Result<()> {
+    ctx.accounts.token_data.require_unlocked(LOCK_WHITELIST)?;
+    require!(
+        amount_cap > 0 && expires_at > unix_timestamp(ctx.remaining_accounts)?,
+        ErrorCode::InvalidTransferPermit
+    );
+
+    ctx.accounts.permit.set_inner(TransferPermit {
+        remaining: amount_cap,
+        expires_at,
+    });
+
+    msg!(
+        "Transfer permit issued: {} for {} until {}",
+        owner,
+        amount_cap,
+        expires_at
+    );
+    emit_cpi!(WhitelistUpdated {
+        mint: ctx.accounts.token_data.mint,
+        change: WhitelistChange::PermitIssued {
+            owner,
+            amount_cap,
+            expires_at,
+        },
+    });
+    Ok(())
+}
+
+pub fn handle_revoke_transfer_permit(
+    ctx: Context<RevokeTransferPermitCTX>,
+    owner: Pubkey,
+) -> Result<()> {
+    ctx.accounts.token_data.require_unlocked(LOCK_WHITELIST)?;
+    msg!("Transfer permit revoked: {}", owner);
+    emit_cpi!(WhitelistUpdated {
+        mint: ctx.accounts.token_data.mint,
+        change: WhitelistChange::PermitRevoked { owner },
+    });
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct IssueTransferPermitCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = TransferPermit::SPACE,
+        seeds = [b"permit", token_data.mint.as_ref(), owner.as_ref()],
+        bump
+    )]
+    pub permit: Account<'info, TransferPermit>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct RevokeTransferPermitCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"permit", token_data.mint.as_ref(), owner.as_ref()],
+        bump
+    )]
+    pub permit: Account<'info, TransferPermit>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
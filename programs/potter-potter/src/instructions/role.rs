@@ -0,0 +1,165 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+pub fn handle_grant_role(ctx: Context<GrantRoleCTX>, member: Pubkey, role: Role) -> Result<()> {
+    ctx.accounts.token_data.require_unlocked(LOCK_AUTHORITY)?;
+    msg!("Role {:?} granted to {}", role, member);
+    emit_cpi!(RoleGranted {
+        mint: ctx.accounts.token_data.mint,
+        member,
+        role,
+    });
+    Ok(())
+}
+
+pub fn handle_revoke_role(ctx: Context<RevokeRoleCTX>, member: Pubkey, role: Role) -> Result<()> {
+    ctx.accounts.token_data.require_unlocked(LOCK_AUTHORITY)?;
+    msg!("Role {:?} revoked from {}", role, member);
+    emit_cpi!(RoleRevoked {
+        mint: ctx.accounts.token_data.mint,
+        member,
+        role,
+    });
+    Ok(())
+}
+
+// Sets rather than adds, so a refill is just a new allowance
+pub fn handle_set_minter_allowance(
+    ctx: Context<SetMinterAllowanceCTX>,
+    minter: Pubkey,
+    allowance: u64,
+) -> Result<()> {
+    ctx.accounts.token_data.require_unlocked(LOCK_AUTHORITY)?;
+    ctx.accounts.allowance.remaining = allowance;
+    msg!("Minter allowance for {}: {} tokens", minter, allowance);
+    emit_cpi!(MinterAllowanceUpdated {
+        mint: ctx.accounts.token_data.mint,
+        minter,
+        allowance,
+    });
+    Ok(())
+}
+
+pub fn handle_revoke_minter_allowance(
+    ctx: Context<RevokeMinterAllowanceCTX>,
+    minter: Pubkey,
+) -> Result<()> {
+    ctx.accounts.token_data.require_unlocked(LOCK_AUTHORITY)?;
+    msg!("Minter allowance revoked: {}", minter);
+    emit_cpi!(MinterAllowanceUpdated {
+        mint: ctx.accounts.token_data.mint,
+        minter,
+        allowance: 0,
+    });
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(member: Pubkey, role: Role)]
+pub struct GrantRoleCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = RoleGrant::SPACE,
+        seeds = [b"role", token_data.key().as_ref(), member.as_ref(), &[role as u8]],
+        bump
+    )]
+    pub grant: Account<'info, RoleGrant>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(member: Pubkey, role: Role)]
+pub struct RevokeRoleCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"role", token_data.key().as_ref(), member.as_ref(), &[role as u8]],
+        bump
+    )]
+    pub grant: Account<'info, RoleGrant>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(minter: Pubkey)]
+pub struct SetMinterAllowanceCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = MinterAllowance::SPACE,
+        seeds = [b"minter_allowance", token_data.key().as_ref(), minter.as_ref()],
+        bump
+    )]
+    pub allowance: Account<'info, MinterAllowance>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(minter: Pubkey)]
+pub struct RevokeMinterAllowanceCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"minter_allowance", token_data.key().as_ref(), minter.as_ref()],
+        bump
+    )]
+    pub allowance: Account<'info, MinterAllowance>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
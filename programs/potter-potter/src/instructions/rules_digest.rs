@@ -0,0 +1,38 @@
+use crate::events::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+// Read-only: the digest goes out as return data and in an event, so a
+// bridge can take it from a simulation or from the transaction itself
+pub fn handle_export_rules_digest(
+    ctx: Context<ExportRulesDigestCTX>,
+    token_count: u64,
+) -> Result<RulesDigest> {
+    let digest = RulesDigest::new(ComplianceRules::from(&*ctx.accounts.token_data))?;
+
+    emit_cpi!(RulesDigestExported {
+        mint: digest.rules.mint,
+        version: digest.version,
+        hash: digest.hash,
+    });
+    msg!(
+        "Rules digest v{} for token {}: {:?}",
+        digest.version,
+        token_count,
+        digest.hash
+    );
+    Ok(digest)
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(token_count: u64)]
+pub struct ExportRulesDigestCTX<'info> {
+    pub factory: Account<'info, TokenFactory>,
+
+    #[account(
+        seeds = [b"token", factory.authority.as_ref(), &token_count.to_le_bytes()],
+        bump
+    )]
+    pub token_data: Account<'info, TokenData>,
+}
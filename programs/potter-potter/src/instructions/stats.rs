@@ -0,0 +1,29 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+pub fn handle_initialize_protocol_stats(ctx: Context<InitializeProtocolStatsCTX>) -> Result<()> {
+    ctx.accounts.protocol_stats.set_inner(ProtocolStats {
+        factories: 0,
+        tokens: 0,
+        total_minted: 0,
+    });
+    msg!("Protocol stats initialized");
+    Ok(())
+}
+
+// Zeroed counters carry no authority, so anyone may pay to create them
+#[derive(Accounts)]
+pub struct InitializeProtocolStatsCTX<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = ProtocolStats::SPACE,
+        seeds = [b"protocol_stats"],
+        bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,864 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey::MAX_SEED_LEN;
+use anchor_spl::token_interface::spl_token_2022::instruction::AuthorityType;
+use anchor_spl::token_interface::{
+    burn, mint_to, set_authority, Burn, Mint, MintTo, SetAuthority, TokenAccount, TokenInterface,
+};
+
+pub fn handle_get_circulating_supply(ctx: Context<GetCirculatingSupplyCTX>) -> Result<u64> {
+    let token_data = &ctx.accounts.token_data;
+    msg!(
+        "Circulating supply: {} of {} total",
+        token_data.circulating_supply,
+        token_data.total_supply
+    );
+    Ok(token_data.circulating_supply)
+}
+
+pub fn handle_mint_tokens(
+    ctx: Context<MintTokensCTX>,
+    amount: u64,
+    idempotency_key: Option<[u8; 16]>,
+) -> Result<()> {
+    if is_duplicate_request(&mut ctx.accounts.idempotency_log, idempotency_key)? {
+        return Ok(());
+    }
+    authorize_mint(
+        &ctx.accounts.program_config,
+        &ctx.accounts.token_data,
+        &ctx.accounts.authority,
+        &ctx.accounts.role,
+        &mut ctx.accounts.allowance,
+        amount,
+    )?;
+
+    let raw_amount = amount
+        .checked_mul(10u64.pow(ctx.accounts.token_data.decimals as u32))
+        .ok_or(ErrorCode::InvalidAmount)?;
+
+    // Minters sign in place of the authority, so seed from the token data
+    let creator = ctx.accounts.token_data.creator;
+    let seeds = &[
+        b"mint_authority",
+        creator.as_ref(),
+        &[ctx.bumps.mint_authority_pda],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.to.to_account_info(),
+                authority: ctx.accounts.mint_authority_pda.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        raw_amount,
+    )?;
+
+    // Update supply counters (store human-readable amount)
+    ctx.accounts.token_data.record_mint(amount)?;
+    ctx.accounts.protocol_stats.record_mint(amount);
+
+    emit_cpi!(TokensMinted {
+        mint: ctx.accounts.mint.key(),
+        to: ctx.accounts.to.key(),
+        amount,
+        raw_amount,
+        ui_amount_string: ui_amount_string(raw_amount, ctx.accounts.token_data.decimals),
+        total_supply: ctx.accounts.token_data.total_supply,
+    });
+    msg!("Minted {} tokens", amount);
+    Ok(())
+}
+
+// Remaining accounts come in pairs, one per amount and in the same order:
+// the destination token account, then its owner's `opt_out` address.
+// Owners who opted out of distributions are skipped.
+pub fn handle_mint_tokens_to_many<'info>(
+    ctx: Context<'_, '_, 'info, 'info, MintTokensToManyCTX<'info>>,
+    amounts: Vec<u64>,
+    idempotency_key: Option<[u8; 16]>,
+) -> Result<()> {
+    if is_duplicate_request(&mut ctx.accounts.idempotency_log, idempotency_key)? {
+        return Ok(());
+    }
+    require!(
+        !amounts.is_empty() && amounts.len() * 2 == ctx.remaining_accounts.len(),
+        ErrorCode::InvalidAmount
+    );
+    ctx.accounts
+        .program_config
+        .limits
+        .require_batch_size(amounts.len())?;
+
+    // Opted-out owners are dropped first so the allowance is only drawn
+    // for what actually gets minted, and in one go
+    let mut recipients = vec![];
+    let mut total: u64 = 0;
+    for (pair, &amount) in ctx.remaining_accounts.chunks(2).zip(&amounts) {
+        let (account_info, opt_out) = (&pair[0], &pair[1]);
+        let to = InterfaceAccount::<TokenAccount>::try_from(account_info)?;
+        require_keys_eq!(to.mint, ctx.accounts.mint.key(), ErrorCode::Unauthorized);
+        if has_opted_out(opt_out, &to.mint, &to.owner)? {
+            continue;
+        }
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        total = total.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+        recipients.push((account_info, amount));
+    }
+    authorize_mint(
+        &ctx.accounts.program_config,
+        &ctx.accounts.token_data,
+        &ctx.accounts.authority,
+        &ctx.accounts.role,
+        &mut ctx.accounts.allowance,
+        total,
+    )?;
+
+    let decimals = ctx.accounts.token_data.decimals;
+    // Minters sign in place of the authority, so seed from the token data
+    let creator = ctx.accounts.token_data.creator;
+    let seeds = &[
+        b"mint_authority",
+        creator.as_ref(),
+        &[ctx.bumps.mint_authority_pda],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    for &(account_info, amount) in &recipients {
+        let raw_amount = amount
+            .checked_mul(10u64.pow(decimals as u32))
+            .ok_or(ErrorCode::InvalidAmount)?;
+        mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: account_info.clone(),
+                    authority: ctx.accounts.mint_authority_pda.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            raw_amount,
+        )?;
+
+        ctx.accounts.token_data.record_mint(amount)?;
+        ctx.accounts.protocol_stats.record_mint(amount);
+        emit_cpi!(TokensMinted {
+            mint: ctx.accounts.mint.key(),
+            to: account_info.key(),
+            amount,
+            raw_amount,
+            ui_amount_string: ui_amount_string(raw_amount, decimals),
+            total_supply: ctx.accounts.token_data.total_supply,
+        });
+    }
+
+    msg!(
+        "Minted to {} recipients, {} opted out",
+        recipients.len(),
+        amounts.len() - recipients.len()
+    );
+    Ok(())
+}
+
+pub fn handle_mint_with_reference(
+    ctx: Context<MintWithReferenceCTX>,
+    amount: u64,
+    reference: [u8; 32],
+    idempotency_key: Option<[u8; 16]>,
+) -> Result<()> {
+    if is_duplicate_request(&mut ctx.accounts.idempotency_log, idempotency_key)? {
+        return Ok(());
+    }
+    authorize_mint(
+        &ctx.accounts.program_config,
+        &ctx.accounts.token_data,
+        &ctx.accounts.authority,
+        &ctx.accounts.role,
+        &mut ctx.accounts.allowance,
+        amount,
+    )?;
+
+    let raw_amount = amount
+        .checked_mul(10u64.pow(ctx.accounts.token_data.decimals as u32))
+        .ok_or(ErrorCode::InvalidAmount)?;
+
+    let creator = ctx.accounts.token_data.creator;
+    let seeds = &[
+        b"mint_authority",
+        creator.as_ref(),
+        &[ctx.bumps.mint_authority_pda],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.to.to_account_info(),
+                authority: ctx.accounts.mint_authority_pda.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        raw_amount,
+    )?;
+
+    ctx.accounts.token_data.record_mint(amount)?;
+    ctx.accounts.protocol_stats.record_mint(amount);
+
+    if let Some(receipt) = &mut ctx.accounts.receipt {
+        receipt.set_inner(MintReceipt {
+            reference,
+            to: ctx.accounts.to.key(),
+            amount,
+            slot: Clock::get()?.slot,
+        });
+    }
+
+    emit_cpi!(TokensMinted {
+        mint: ctx.accounts.mint.key(),
+        to: ctx.accounts.to.key(),
+        amount,
+        raw_amount,
+        ui_amount_string: ui_amount_string(raw_amount, ctx.accounts.token_data.decimals),
+        total_supply: ctx.accounts.token_data.total_supply,
+    });
+    emit_cpi!(MintReferenceRecorded {
+        mint: ctx.accounts.mint.key(),
+        to: ctx.accounts.to.key(),
+        amount,
+        reference,
+    });
+    msg!("Minted {} tokens with reference", amount);
+    Ok(())
+}
+
+pub fn handle_lock_supply(ctx: Context<LockSupplyCTX>) -> Result<()> {
+    require!(
+        !ctx.accounts.token_data.supply_locked,
+        ErrorCode::SupplyLocked
+    );
+    // Vaults pay out by minting, so they have to be drained first. Vesting
+    // deposits show in the counter; harvested fees only in the balances.
+    require!(
+        ctx.accounts.token_data.circulating_supply == ctx.accounts.token_data.total_supply
+            && vault_is_empty(&ctx.accounts.treasury)?
+            && vault_is_empty(&ctx.accounts.insurance_vault)?,
+        ErrorCode::VaultsNotEmpty
+    );
+
+    let creator = ctx.accounts.token_data.creator;
+    let seeds = &[
+        b"mint_authority",
+        creator.as_ref(),
+        &[ctx.bumps.mint_authority_pda],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    // Irreversible: with no mint authority the mint can never grow again
+    set_authority(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            SetAuthority {
+                current_authority: ctx.accounts.mint_authority_pda.to_account_info(),
+                account_or_mint: ctx.accounts.mint.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        AuthorityType::MintTokens,
+        None,
+    )?;
+
+    let token_data = &mut ctx.accounts.token_data;
+    token_data.supply_locked = true;
+
+    emit_cpi!(SupplyLocked {
+        mint: token_data.mint,
+        total_supply: token_data.total_supply,
+    });
+    msg!("Supply locked at {} tokens", token_data.total_supply);
+    Ok(())
+}
+
+// Every mint entry point goes through here. The authority and `Minter`s
+// mint freely; a capped minter draws `amount` from its allowance.
+fn authorize_mint(
+    program_config: &ProgramConfig,
+    token_data: &TokenData,
+    authority: &Signer,
+    role: &Option<Account<RoleGrant>>,
+    allowance: &mut Option<Account<MinterAllowance>>,
+    amount: u64,
+) -> Result<()> {
+    program_config.require_feature_enabled(FEATURE_MINTING)?;
+    require!(
+        token_data.authority == authority.key() || role.is_some() || allowance.is_some(),
+        ErrorCode::Unauthorized
+    );
+    require!(!token_data.is_minting_paused, ErrorCode::MintingPaused);
+    require!(!token_data.supply_locked, ErrorCode::SupplyLocked);
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    if let Some(allowance) = allowance {
+        allowance.consume(amount)?;
+    }
+    Ok(())
+}
+
+// Tokens created before treasuries or without an insurance fund have no
+// vault at the address
+fn vault_is_empty(vault: &UncheckedAccount) -> Result<bool> {
+    if vault.data_is_empty() {
+        return Ok(true);
+    }
+    let vault = TokenAccount::try_deserialize(&mut &vault.try_borrow_data()?[..])?;
+    Ok(vault.amount == 0)
+}
+
+pub fn handle_burn_tokens(ctx: Context<BurnTokensCTX>, amount: u64) -> Result<()> {
+    require!(
+        !ctx.accounts.token_data.is_paused || ctx.accounts.token_data.burn_allowed_while_paused,
+        ErrorCode::BurningPaused
+    );
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let raw_amount = amount
+        .checked_mul(10u64.pow(ctx.accounts.token_data.decimals as u32))
+        .ok_or(ErrorCode::InvalidAmount)?;
+
+    burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.mint.to_account_info(),
+                from: ctx.accounts.from.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            },
+        ),
+        raw_amount,
+    )?;
+
+    // Update supply counters (store human-readable amount)
+    ctx.accounts.token_data.record_burn(amount)?;
+
+    emit_cpi!(TokensBurned {
+        mint: ctx.accounts.mint.key(),
+        from: ctx.accounts.from.key(),
+        amount,
+        raw_amount,
+        ui_amount_string: ui_amount_string(raw_amount, ctx.accounts.token_data.decimals),
+        total_supply: ctx.accounts.token_data.total_supply,
+    });
+    msg!("Burned {} tokens", amount);
+    Ok(())
+}
+
+// Burns from a named vault's token account. `vault_deposit` already took
+// those tokens out of circulation, so the burn comes out of the deposited
+// remainder first, as a treasury buyback does.
+pub fn handle_burn_from_vault(
+    ctx: Context<BurnFromVaultCTX>,
+    vault_name: String,
+    amount: u64,
+) -> Result<()> {
+    require!(
+        !ctx.accounts.token_data.is_paused || ctx.accounts.token_data.burn_allowed_while_paused,
+        ErrorCode::BurningPaused
+    );
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let raw_amount = amount
+        .checked_mul(10u64.pow(ctx.accounts.token_data.decimals as u32))
+        .ok_or(ErrorCode::InvalidAmount)?;
+
+    let token_data_key = ctx.accounts.token_data.key();
+    let seeds = &[
+        b"vault",
+        token_data_key.as_ref(),
+        vault_name.as_bytes(),
+        &[ctx.bumps.vault],
+    ];
+    burn(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.mint.to_account_info(),
+                from: ctx.accounts.vault_tokens.to_account_info(),
+                authority: ctx.accounts.vault.to_account_info(),
+            },
+            &[&seeds[..]],
+        ),
+        raw_amount,
+    )?;
+
+    ctx.accounts.token_data.record_vault_burn(amount)?;
+
+    emit_cpi!(TokensBurned {
+        mint: ctx.accounts.mint.key(),
+        from: ctx.accounts.vault_tokens.key(),
+        amount,
+        raw_amount,
+        ui_amount_string: ui_amount_string(raw_amount, ctx.accounts.token_data.decimals),
+        total_supply: ctx.accounts.token_data.total_supply,
+    });
+    msg!("Burned {} tokens from vault {}", amount, vault_name);
+    Ok(())
+}
+
+pub fn handle_burn_with_reason(
+    ctx: Context<BurnTokensCTX>,
+    amount: u64,
+    reason_code: u16,
+    reference_hash: [u8; 32],
+) -> Result<()> {
+    // Both events land in the same transaction, so a failed burn drops this too
+    emit_cpi!(BurnReasonRecorded {
+        mint: ctx.accounts.mint.key(),
+        from: ctx.accounts.from.key(),
+        amount,
+        reason_code,
+        reference_hash,
+    });
+    msg!("Burn reason code: {}", reason_code);
+    handle_burn_tokens(ctx, amount)
+}
+
+#[derive(Accounts)]
+pub struct GetCirculatingSupplyCTX<'info> {
+    pub token_data: Account<'info, TokenData>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MintTokensCTX<'info> {
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        has_one = mint
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = to.mint == token_data.mint
+    )]
+    pub to: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as mint authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"idempotency", token_data.key().as_ref()],
+        bump
+    )]
+    pub idempotency_log: Option<Account<'info, IdempotencyLog>>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_stats"],
+        bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Lets a `Minter` act in place of the token authority
+    #[account(
+        seeds = [b"role", token_data.key().as_ref(), authority.key().as_ref(), &[Role::Minter as u8]],
+        bump
+    )]
+    pub role: Option<Account<'info, RoleGrant>>,
+
+    /// Lets a capped minter act in place of the token authority
+    #[account(
+        mut,
+        seeds = [b"minter_allowance", token_data.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub allowance: Option<Account<'info, MinterAllowance>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MintTokensToManyCTX<'info> {
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        has_one = mint
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as mint authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"idempotency", token_data.key().as_ref()],
+        bump
+    )]
+    pub idempotency_log: Option<Account<'info, IdempotencyLog>>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_stats"],
+        bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Lets a `Minter` act in place of the token authority
+    #[account(
+        seeds = [b"role", token_data.key().as_ref(), authority.key().as_ref(), &[Role::Minter as u8]],
+        bump
+    )]
+    pub role: Option<Account<'info, RoleGrant>>,
+
+    /// Lets a capped minter act in place of the token authority
+    #[account(
+        mut,
+        seeds = [b"minter_allowance", token_data.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub allowance: Option<Account<'info, MinterAllowance>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct LockSupplyCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as mint authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    /// CHECK: The mint's treasury, may not exist
+    #[account(
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// CHECK: The mint's insurance vault, may not exist
+    #[account(
+        seeds = [b"insurance_vault", mint.key().as_ref()],
+        bump
+    )]
+    pub insurance_vault: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// The optional receipt is keyed by reference, so each reference can only
+// be receipted once
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(amount: u64, reference: [u8; 32])]
+pub struct MintWithReferenceCTX<'info> {
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        has_one = mint
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = to.mint == token_data.mint
+    )]
+    pub to: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as mint authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"idempotency", token_data.key().as_ref()],
+        bump
+    )]
+    pub idempotency_log: Option<Account<'info, IdempotencyLog>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 32 + 8 + 8,
+        seeds = [b"mint_receipt", token_data.key().as_ref(), reference.as_ref()],
+        bump
+    )]
+    pub receipt: Option<Account<'info, MintReceipt>>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_stats"],
+        bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+
+    /// Lets a `Minter` act in place of the token authority
+    #[account(
+        seeds = [b"role", token_data.key().as_ref(), authority.key().as_ref(), &[Role::Minter as u8]],
+        bump
+    )]
+    pub role: Option<Account<'info, RoleGrant>>,
+
+    /// Lets a capped minter act in place of the token authority
+    #[account(
+        mut,
+        seeds = [b"minter_allowance", token_data.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub allowance: Option<Account<'info, MinterAllowance>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct BurnTokensCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_MINTING) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = from.mint == token_data.mint
+    )]
+    pub from: InterfaceAccount<'info, TokenAccount>,
+
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_name: String)]
+pub struct BurnFromVaultCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_MINTING) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    // Checked ahead of the vault seeds, which can't be derived past 32 bytes
+    #[account(
+        mut,
+        has_one = authority,
+        has_one = mint,
+        constraint = vault_name.len() <= MAX_SEED_LEN @ ErrorCode::VaultNameTooLong
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"vault", token_data.key().as_ref(), vault_name.as_bytes()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        mut,
+        seeds = [b"vault_tokens", vault.key().as_ref()],
+        bump
+    )]
+    pub vault_tokens: InterfaceAccount<'info, TokenAccount>,
+
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    struct Minter {
+        config: ProgramConfig,
+        token_data: TokenData,
+        signer: Pubkey,
+        role: bool,
+        allowance: Option<u64>,
+    }
+
+    impl Minter {
+        /// The token's own authority, with minting switched on.
+        fn authority() -> Self {
+            let signer = Pubkey::new_unique();
+            Self {
+                config: program_config(0),
+                token_data: token_data(Pubkey::new_unique(), signer),
+                signer,
+                role: false,
+                allowance: None,
+            }
+        }
+
+        fn stranger() -> Self {
+            Self {
+                signer: Pubkey::new_unique(),
+                ..Self::authority()
+            }
+        }
+
+        /// Mints `amount`, returning what's left of the allowance.
+        fn mint(&self, amount: u64) -> Result<Option<u64>> {
+            let authority = Signer::try_from(signer_account(self.signer))?;
+            let role = match self.role {
+                true => Some(Account::try_from(program_account(
+                    Pubkey::new_unique(),
+                    &RoleGrant {},
+                ))?),
+                false => None,
+            };
+            let mut allowance = match self.allowance {
+                Some(remaining) => Some(Account::try_from(program_account(
+                    Pubkey::new_unique(),
+                    &MinterAllowance { remaining },
+                ))?),
+                None => None,
+            };
+            authorize_mint(
+                &self.config,
+                &self.token_data,
+                &authority,
+                &role,
+                &mut allowance,
+                amount,
+            )?;
+            Ok(allowance.map(|allowance| allowance.remaining))
+        }
+    }
+
+    #[test]
+    fn authority_mints_without_an_allowance() {
+        assert_eq!(Minter::authority().mint(10).unwrap(), None);
+    }
+
+    #[test]
+    fn stranger_is_rejected() {
+        assert_eq!(
+            Minter::stranger().mint(10).unwrap_err(),
+            ErrorCode::Unauthorized.into()
+        );
+    }
+
+    #[test]
+    fn minter_role_mints_in_place_of_the_authority() {
+        let minter = Minter {
+            role: true,
+            ..Minter::stranger()
+        };
+        assert_eq!(minter.mint(10).unwrap(), None);
+    }
+
+    #[test]
+    fn allowance_is_drawn_down() {
+        let minter = Minter {
+            allowance: Some(25),
+            ..Minter::stranger()
+        };
+        assert_eq!(minter.mint(10).unwrap(), Some(15));
+        assert_eq!(
+            minter.mint(26).unwrap_err(),
+            ErrorCode::MinterAllowanceExceeded.into()
+        );
+    }
+
+    #[test]
+    fn paused_or_locked_minting_is_rejected() {
+        let mut minter = Minter::authority();
+        minter.token_data.is_minting_paused = true;
+        assert_eq!(
+            minter.mint(10).unwrap_err(),
+            ErrorCode::MintingPaused.into()
+        );
+
+        let mut minter = Minter::authority();
+        minter.token_data.supply_locked = true;
+        assert_eq!(minter.mint(10).unwrap_err(), ErrorCode::SupplyLocked.into());
+
+        let minter = Minter {
+            config: program_config(FEATURE_MINTING),
+            ..Minter::authority()
+        };
+        assert_eq!(
+            minter.mint(10).unwrap_err(),
+            ErrorCode::FeatureDisabled.into()
+        );
+    }
+}
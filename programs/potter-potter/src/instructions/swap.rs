@@ -0,0 +1,245 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::hook::*;
+use crate::state::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    burn, mint_to, Burn, Mint, MintTo, TokenAccount, TokenInterface,
+};
+
+pub fn handle_set_swap_rate(
+    ctx: Context<SetSwapRateCTX>,
+    rate_numerator: u64,
+    rate_denominator: u64,
+) -> Result<()> {
+    require_keys_neq!(
+        ctx.accounts.from_token_data.key(),
+        ctx.accounts.to_token_data.key(),
+        ErrorCode::InvalidSwapPair
+    );
+    require!(rate_denominator > 0, ErrorCode::InvalidAmount);
+    ctx.accounts
+        .from_token_data
+        .require_unlocked(LOCK_SWAP_RATES)?;
+    ctx.accounts
+        .to_token_data
+        .require_unlocked(LOCK_SWAP_RATES)?;
+
+    // A zero numerator disables the swap without closing the account
+    ctx.accounts.swap_rate.set_inner(SwapRate {
+        from_mint: ctx.accounts.from_token_data.mint,
+        to_mint: ctx.accounts.to_token_data.mint,
+        rate_numerator,
+        rate_denominator,
+    });
+
+    msg!(
+        "Swap rate set: {} -> {} at {}/{}",
+        ctx.accounts.from_token_data.mint,
+        ctx.accounts.to_token_data.mint,
+        rate_numerator,
+        rate_denominator
+    );
+    emit_cpi!(SwapRateUpdated {
+        from_mint: ctx.accounts.from_token_data.mint,
+        to_mint: ctx.accounts.to_token_data.mint,
+        rate_numerator,
+        rate_denominator,
+    });
+    Ok(())
+}
+
+pub fn handle_swap_tokens(ctx: Context<SwapTokensCTX>, amount: u64) -> Result<()> {
+    // The incoming leg is a mint, so a minting halt stops swaps too
+    ctx.accounts
+        .program_config
+        .require_feature_enabled(FEATURE_SWAPS | FEATURE_MINTING)?;
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    require!(
+        ctx.accounts.swap_rate.rate_numerator > 0,
+        ErrorCode::SwapDisabled
+    );
+    require!(
+        !ctx.accounts.from_token_data.is_paused,
+        ErrorCode::TokenPaused
+    );
+    require!(
+        !ctx.accounts.to_token_data.is_minting_paused,
+        ErrorCode::MintingPaused
+    );
+    require!(
+        !ctx.accounts.to_token_data.supply_locked,
+        ErrorCode::SupplyLocked
+    );
+
+    let amount_out = (amount as u128)
+        .checked_mul(ctx.accounts.swap_rate.rate_numerator as u128)
+        .ok_or(ErrorCode::InvalidAmount)?
+        / ctx.accounts.swap_rate.rate_denominator as u128;
+    let amount_out = u64::try_from(amount_out).map_err(|_| error!(ErrorCode::InvalidAmount))?;
+    require!(amount_out > 0, ErrorCode::InvalidAmount);
+
+    let raw_in = amount
+        .checked_mul(10u64.pow(ctx.accounts.from_token_data.decimals as u32))
+        .ok_or(ErrorCode::InvalidAmount)?;
+    let raw_out = amount_out
+        .checked_mul(10u64.pow(ctx.accounts.to_token_data.decimals as u32))
+        .ok_or(ErrorCode::InvalidAmount)?;
+
+    // Minting skips the target's hook, so its receiver rules run here
+    let now = unix_timestamp(ctx.remaining_accounts)?;
+    ctx.accounts.receiver.require_can_receive(
+        &ctx.accounts.to_token_data,
+        &ctx.accounts.to,
+        raw_out,
+        now,
+    )?;
+
+    // Burn the outgoing token from the user
+    burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.from_mint.to_account_info(),
+                from: ctx.accounts.from.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        raw_in,
+    )?;
+
+    // Mint the incoming token using PDA authority
+    let to_creator = ctx.accounts.to_token_data.creator;
+    let seeds = &[
+        b"mint_authority",
+        to_creator.as_ref(),
+        &[ctx.bumps.mint_authority_pda],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.to_mint.to_account_info(),
+                to: ctx.accounts.to.to_account_info(),
+                authority: ctx.accounts.mint_authority_pda.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        raw_out,
+    )?;
+
+    ctx.accounts.from_token_data.record_burn(amount)?;
+    ctx.accounts.to_token_data.record_mint(amount_out)?;
+    ctx.accounts.protocol_stats.record_mint(amount_out);
+
+    emit_cpi!(TokensSwapped {
+        user: ctx.accounts.user.key(),
+        from_mint: ctx.accounts.from_mint.key(),
+        to_mint: ctx.accounts.to_mint.key(),
+        amount_in: amount,
+        raw_amount_in: raw_in,
+        ui_amount_in_string: ui_amount_string(raw_in, ctx.accounts.from_token_data.decimals),
+        amount_out,
+        raw_amount_out: raw_out,
+        ui_amount_out_string: ui_amount_string(raw_out, ctx.accounts.to_token_data.decimals),
+    });
+    msg!("Swapped {} tokens for {} tokens", amount, amount_out);
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetSwapRateCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub from_token_data: Account<'info, TokenData>,
+
+    #[account(has_one = authority)]
+    pub to_token_data: Account<'info, TokenData>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 32 + 8 + 8,
+        seeds = [b"swap_rate", from_token_data.mint.as_ref(), to_token_data.mint.as_ref()],
+        bump
+    )]
+    pub swap_rate: Account<'info, SwapRate>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SwapTokensCTX<'info> {
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        seeds = [b"swap_rate", from_mint.key().as_ref(), to_mint.key().as_ref()],
+        bump
+    )]
+    pub swap_rate: Account<'info, SwapRate>,
+
+    #[account(
+        mut,
+        constraint = from_token_data.mint == from_mint.key()
+    )]
+    pub from_token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        constraint = to_token_data.mint == to_mint.key()
+    )]
+    pub to_token_data: Account<'info, TokenData>,
+
+    #[account(mut)]
+    pub from_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub to_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = from.mint == from_mint.key()
+    )]
+    pub from: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = to.mint == to_mint.key(),
+        constraint = to.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub to: InterfaceAccount<'info, TokenAccount>,
+
+    /// Target token's lookups for `to`
+    pub receiver: ReceiverChecks<'info>,
+
+    #[account(
+        seeds = [b"mint_authority", to_token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as mint authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_stats"],
+        bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
+    pub user: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
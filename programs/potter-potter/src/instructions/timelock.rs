@@ -0,0 +1,201 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::instructions::{apply_admin_action, AdminAction};
+use crate::state::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+
+// Creates the timelock on first use. Like the metadata delay, it can only
+// grow, and there is no way to remove it again.
+pub fn handle_set_timelock(ctx: Context<SetTimelockCTX>, min_delay: i64) -> Result<()> {
+    let timelock = &mut ctx.accounts.timelock;
+    require!(
+        min_delay > 0 && min_delay >= timelock.min_delay,
+        ErrorCode::TimelockDelayDecrease
+    );
+    timelock.min_delay = min_delay;
+
+    emit_cpi!(TimelockUpdated {
+        mint: ctx.accounts.token_data.mint,
+        min_delay,
+    });
+    msg!("Admin timelock set to {}s", min_delay);
+    Ok(())
+}
+
+pub fn handle_queue_action(ctx: Context<QueueActionCTX>, action: AdminAction) -> Result<()> {
+    let timelock = &mut ctx.accounts.timelock;
+    let action_id = timelock.next_action_id;
+    let ready_at = unix_timestamp(ctx.remaining_accounts)?
+        .checked_add(timelock.min_delay)
+        .ok_or(ErrorCode::InvalidAmount)?;
+    ctx.accounts.queued_action.set_inner(QueuedAction {
+        action_id,
+        action,
+        ready_at,
+    });
+    timelock.next_action_id = action_id.checked_add(1).ok_or(ErrorCode::Overflow)?;
+
+    emit_cpi!(ActionQueued {
+        mint: ctx.accounts.token_data.mint,
+        action_id,
+        action,
+        ready_at,
+    });
+    msg!("Admin action {} queued, ready at {}", action_id, ready_at);
+    Ok(())
+}
+
+// Config locks are checked again here, as one may have been set while the
+// action was waiting
+pub fn handle_execute_action(ctx: Context<ExecuteActionCTX>, action_id: u64) -> Result<()> {
+    let queued = &ctx.accounts.queued_action;
+    require!(
+        unix_timestamp(ctx.remaining_accounts)? >= queued.ready_at,
+        ErrorCode::TimelockNotElapsed
+    );
+
+    let authority = ctx.accounts.authority.key();
+    let event = apply_admin_action(
+        &ctx.accounts.program_config,
+        &mut ctx.accounts.token_data,
+        &mut ctx.accounts.hook_manifest,
+        authority,
+        queued.action,
+    )?;
+    emit_cpi!(event);
+    msg!("Admin action {} executed", action_id);
+    Ok(())
+}
+
+pub fn handle_cancel_action(ctx: Context<CancelActionCTX>, action_id: u64) -> Result<()> {
+    emit_cpi!(ActionCancelled {
+        mint: ctx.accounts.token_data.mint,
+        action_id,
+    });
+    msg!("Admin action {} cancelled", action_id);
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetTimelockCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = TimelockConfig::SPACE,
+        seeds = [b"timelock", token_data.mint.as_ref()],
+        bump
+    )]
+    pub timelock: Account<'info, TimelockConfig>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct QueueActionCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"timelock", token_data.mint.as_ref()],
+        bump
+    )]
+    pub timelock: Account<'info, TimelockConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = QueuedAction::SPACE,
+        seeds = [
+            b"queued_action",
+            token_data.mint.as_ref(),
+            &timelock.next_action_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub queued_action: Account<'info, QueuedAction>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(action_id: u64)]
+pub struct ExecuteActionCTX<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"queued_action", token_data.mint.as_ref(), &action_id.to_le_bytes()],
+        bump
+    )]
+    pub queued_action: Account<'info, QueuedAction>,
+
+    #[account(
+        mut,
+        seeds = [b"hook_manifest", token_data.mint.as_ref()],
+        bump
+    )]
+    pub hook_manifest: Option<Account<'info, HookManifest>>,
+
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(action_id: u64)]
+pub struct CancelActionCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"queued_action", token_data.mint.as_ref(), &action_id.to_le_bytes()],
+        bump
+    )]
+    pub queued_action: Account<'info, QueuedAction>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
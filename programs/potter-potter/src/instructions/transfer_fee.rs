@@ -0,0 +1,296 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use anchor_spl::token_interface::{
+    burn, get_mint_extension_data, harvest_withheld_tokens_to_mint, mint_to, transfer_fee_set,
+    withdraw_withheld_tokens_from_mint, Burn, HarvestWithheldTokensToMint, Mint, MintTo,
+    TokenAccount, TokenInterface, TransferFeeSetTransferFee, WithdrawWithheldTokensFromMint,
+};
+
+pub fn handle_update_transfer_fee(
+    ctx: Context<UpdateTransferFeeCTX>,
+    basis_points: u16,
+    maximum_fee: u64,
+) -> Result<()> {
+    ctx.accounts
+        .token_data
+        .require_unlocked(LOCK_TRANSFER_RULES)?;
+    require!(
+        basis_points <= ctx.accounts.program_config.limits.max_fee_basis_points,
+        ErrorCode::InvalidTransferFee
+    );
+
+    let creator = ctx.accounts.token_data.creator;
+    let seeds = &[
+        b"mint_authority",
+        creator.as_ref(),
+        &[ctx.bumps.mint_authority_pda],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    transfer_fee_set(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferFeeSetTransferFee {
+                token_program_id: ctx.accounts.token_program.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                authority: ctx.accounts.mint_authority_pda.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        basis_points,
+        maximum_fee,
+    )?;
+
+    emit_cpi!(TransferFeeUpdated {
+        mint: ctx.accounts.mint.key(),
+        basis_points,
+        maximum_fee,
+    });
+    msg!(
+        "Transfer fee set to {} bps, max {} base units",
+        basis_points,
+        maximum_fee
+    );
+    Ok(())
+}
+
+pub fn handle_quote_transfer(ctx: Context<QuoteTransferCTX>, amount: u64) -> Result<u64> {
+    // Token-2022 has no fee routing of its own and this program adds none,
+    // so the withheld fee is the only deduction
+    let mint_info = ctx.accounts.mint.to_account_info();
+    let fee = match get_mint_extension_data::<TransferFeeConfig>(&mint_info) {
+        Ok(config) => config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(ErrorCode::InvalidAmount)?,
+        Err(_) => 0,
+    };
+    let net_amount = amount.checked_sub(fee).ok_or(ErrorCode::InvalidAmount)?;
+
+    msg!(
+        "Transfer of {} nets {} after a {} fee",
+        amount,
+        net_amount,
+        fee
+    );
+    Ok(net_amount)
+}
+
+pub fn handle_harvest_withheld_fees<'info>(
+    ctx: Context<'_, '_, 'info, 'info, HarvestWithheldFeesCTX<'info>>,
+) -> Result<()> {
+    require!(!ctx.remaining_accounts.is_empty(), ErrorCode::InvalidAmount);
+
+    harvest_withheld_tokens_to_mint(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            HarvestWithheldTokensToMint {
+                token_program_id: ctx.accounts.token_program.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+            },
+        ),
+        ctx.remaining_accounts.to_vec(),
+    )?;
+
+    msg!(
+        "Harvested withheld fees from {} accounts",
+        ctx.remaining_accounts.len()
+    );
+    Ok(())
+}
+
+pub fn handle_withdraw_withheld_fees_to_treasury(
+    ctx: Context<WithdrawWithheldFeesCTX>,
+) -> Result<()> {
+    ctx.accounts
+        .program_config
+        .require_feature_enabled(FEATURE_MINTING)?;
+    let raw_amount = u64::from(
+        get_mint_extension_data::<TransferFeeConfig>(&ctx.accounts.mint.to_account_info())?
+            .withheld_amount,
+    );
+
+    let creator = ctx.accounts.token_data.creator;
+    let seeds = &[
+        b"mint_authority",
+        creator.as_ref(),
+        &[ctx.bumps.mint_authority_pda],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    // With an insurance fund, everything lands in its vault first and the
+    // treasury's part is moved on, as the vault is the only account here
+    // the program can sign for
+    let insurance = match (&ctx.accounts.insurance_fund, &ctx.accounts.insurance_vault) {
+        (Some(fund), Some(vault)) => Some((fund, vault)),
+        (None, None) => None,
+        _ => return err!(ErrorCode::InvalidInsuranceFund),
+    };
+    let destination = match insurance {
+        Some((_, vault)) => vault.to_account_info(),
+        None => ctx.accounts.treasury.to_account_info(),
+    };
+
+    // Withdrawing withheld fees isn't a transfer, so the hook doesn't run
+    withdraw_withheld_tokens_from_mint(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        WithdrawWithheldTokensFromMint {
+            token_program_id: ctx.accounts.token_program.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            destination,
+            authority: ctx.accounts.mint_authority_pda.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    let mut insurance_share = 0;
+    if let Some((fund, vault)) = insurance {
+        insurance_share = (raw_amount as u128 * fund.fee_share_bps as u128 / 10_000) as u64;
+        let treasury_share = raw_amount - insurance_share;
+        if treasury_share > 0 {
+            let mint_key = ctx.accounts.mint.key();
+            let fund_bump = ctx
+                .bumps
+                .insurance_fund
+                .ok_or(ErrorCode::InvalidInsuranceFund)?;
+            let fund_seeds = &[b"insurance", mint_key.as_ref(), &[fund_bump]];
+            burn(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Burn {
+                        mint: ctx.accounts.mint.to_account_info(),
+                        from: vault.to_account_info(),
+                        authority: fund.to_account_info(),
+                    },
+                    &[&fund_seeds[..]],
+                ),
+                treasury_share,
+            )?;
+            mint_to(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    MintTo {
+                        mint: ctx.accounts.mint.to_account_info(),
+                        to: ctx.accounts.treasury.to_account_info(),
+                        authority: ctx.accounts.mint_authority_pda.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                treasury_share,
+            )?;
+        }
+    }
+
+    emit_cpi!(WithheldFeesWithdrawn {
+        mint: ctx.accounts.mint.key(),
+        treasury: ctx.accounts.treasury.key(),
+        raw_amount,
+        insurance_share,
+    });
+    msg!("Withdrew {} base units of withheld fees", raw_amount);
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateTransferFeeCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        has_one = authority,
+        has_one = mint
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as transfer fee config authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct QuoteTransferCTX<'info> {
+    #[account(has_one = mint)]
+    pub token_data: Account<'info, TokenData>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+}
+
+// Token accounts holding withheld fees are passed as remaining accounts
+#[derive(Accounts)]
+pub struct HarvestWithheldFeesCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = mint)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawWithheldFeesCTX<'info> {
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        has_one = authority,
+        has_one = mint
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = treasury.mint == mint.key()
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as withdraw withheld authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"insurance", mint.key().as_ref()],
+        bump
+    )]
+    pub insurance_fund: Option<Account<'info, InsuranceFund>>,
+
+    #[account(
+        mut,
+        seeds = [b"insurance_vault", mint.key().as_ref()],
+        bump
+    )]
+    pub insurance_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
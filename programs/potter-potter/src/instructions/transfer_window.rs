@@ -0,0 +1,87 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+
+// The hook can't create accounts, so a window has to exist before its
+// owner sends anything while a daily limit is set. Anyone may pay for it.
+pub fn handle_open_transfer_window(
+    ctx: Context<OpenTransferWindowCTX>,
+    owner: Pubkey,
+) -> Result<()> {
+    ctx.accounts.window.set_inner(TransferWindow {
+        payer: ctx.accounts.payer.key(),
+        window_start: unix_timestamp(ctx.remaining_accounts)?,
+        volume: 0,
+    });
+    msg!("Transfer window opened for {}", owner);
+    Ok(())
+}
+
+// Crank for reclaiming rent: an expired window carries no state worth
+// keeping, and the owner just opens a new one before their next transfer
+pub fn handle_close_transfer_window(
+    ctx: Context<CloseTransferWindowCTX>,
+    owner: Pubkey,
+) -> Result<()> {
+    require!(
+        ctx.accounts
+            .window
+            .is_expired(unix_timestamp(ctx.remaining_accounts)?),
+        ErrorCode::TransferWindowActive
+    );
+    msg!("Transfer window closed for {}", owner);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct OpenTransferWindowCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_TRANSFERS) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = TransferWindow::SPACE,
+        seeds = [b"transfer_window", token_data.mint.as_ref(), owner.as_ref()],
+        bump
+    )]
+    pub window: Account<'info, TransferWindow>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct CloseTransferWindowCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_TRANSFERS) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        close = payer,
+        has_one = payer,
+        seeds = [b"transfer_window", token_data.mint.as_ref(), owner.as_ref()],
+        bump
+    )]
+    pub window: Account<'info, TransferWindow>,
+
+    /// CHECK: Receives the rent; checked against the window's payer
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+}
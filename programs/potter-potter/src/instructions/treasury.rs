@@ -0,0 +1,282 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::hook::*;
+use crate::state::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{burn, Burn, Mint, TokenAccount, TokenInterface};
+
+// The treasury is a program-owned vault, so value moves in and out through
+// `VaultMint` as a burn and a mint of the same amount. Total supply is
+// unchanged either way; only circulating supply moves.
+pub fn handle_deposit_to_treasury(ctx: Context<DepositToTreasuryCTX>, amount: u64) -> Result<()> {
+    ctx.accounts
+        .program_config
+        .require_feature_enabled(FEATURE_MINTING)?;
+    require!(!ctx.accounts.token_data.is_paused, ErrorCode::TokenPaused);
+    require!(
+        !ctx.accounts.token_data.supply_locked,
+        ErrorCode::SupplyLocked
+    );
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let raw_amount = amount
+        .checked_mul(10u64.pow(ctx.accounts.token_data.decimals as u32))
+        .ok_or(ErrorCode::InvalidAmount)?;
+
+    VaultMint {
+        token_program: &ctx.accounts.token_program,
+        mint: &ctx.accounts.mint,
+        mint_authority_pda: &ctx.accounts.mint_authority_pda,
+        creator: ctx.accounts.token_data.creator,
+        mint_authority_bump: ctx.bumps.mint_authority_pda,
+    }
+    .deposit(
+        &ctx.accounts.from,
+        &ctx.accounts.depositor,
+        &ctx.accounts.treasury,
+        raw_amount,
+    )?;
+    ctx.accounts.token_data.record_vault_deposit(amount)?;
+
+    emit_cpi!(TreasuryDeposited {
+        mint: ctx.accounts.mint.key(),
+        from: ctx.accounts.from.key(),
+        amount,
+        raw_amount,
+    });
+    msg!("Deposited {} tokens to treasury", amount);
+    Ok(())
+}
+
+pub fn handle_withdraw_from_treasury(
+    ctx: Context<WithdrawFromTreasuryCTX>,
+    amount: u64,
+) -> Result<()> {
+    ctx.accounts
+        .program_config
+        .require_feature_enabled(FEATURE_MINTING)?;
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let raw_amount = amount
+        .checked_mul(10u64.pow(ctx.accounts.token_data.decimals as u32))
+        .ok_or(ErrorCode::InvalidAmount)?;
+    // The payout is minted, skipping the hook, so its rules run here
+    ctx.accounts.receiver.require_can_receive(
+        &ctx.accounts.token_data,
+        &ctx.accounts.destination,
+        raw_amount,
+        unix_timestamp(ctx.remaining_accounts)?,
+    )?;
+
+    let mint_key = ctx.accounts.mint.key();
+    let treasury_seeds = &[
+        b"treasury_authority",
+        mint_key.as_ref(),
+        &[ctx.bumps.treasury_authority],
+    ];
+    VaultMint {
+        token_program: &ctx.accounts.token_program,
+        mint: &ctx.accounts.mint,
+        mint_authority_pda: &ctx.accounts.mint_authority_pda,
+        creator: ctx.accounts.token_data.creator,
+        mint_authority_bump: ctx.bumps.mint_authority_pda,
+    }
+    .release(
+        &ctx.accounts.treasury,
+        &ctx.accounts.treasury_authority,
+        treasury_seeds,
+        &ctx.accounts.destination,
+        raw_amount,
+    )?;
+    ctx.accounts.token_data.record_vault_release(amount)?;
+
+    emit_cpi!(TreasuryWithdrawn {
+        mint: mint_key,
+        to: ctx.accounts.destination.key(),
+        amount,
+        raw_amount,
+    });
+    msg!("Withdrew {} tokens from treasury", amount);
+    Ok(())
+}
+
+// Unlike deposits and withdrawals this is a real burn, so supply drops
+pub fn handle_buyback_and_burn(ctx: Context<BuybackAndBurnCTX>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let raw_amount = amount
+        .checked_mul(10u64.pow(ctx.accounts.token_data.decimals as u32))
+        .ok_or(ErrorCode::InvalidAmount)?;
+
+    let mint_key = ctx.accounts.mint.key();
+    let seeds = &[
+        b"treasury_authority",
+        mint_key.as_ref(),
+        &[ctx.bumps.treasury_authority],
+    ];
+    burn(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.mint.to_account_info(),
+                from: ctx.accounts.treasury.to_account_info(),
+                authority: ctx.accounts.treasury_authority.to_account_info(),
+            },
+            &[&seeds[..]],
+        ),
+        raw_amount,
+    )?;
+
+    let token_data = &mut ctx.accounts.token_data;
+    let supply_before = token_data.total_supply;
+    token_data.record_vault_burn(amount)?;
+
+    emit_cpi!(BuybackBurn {
+        mint: mint_key,
+        amount,
+        raw_amount,
+        supply_before,
+        supply_after: token_data.total_supply,
+    });
+    msg!(
+        "Buyback burned {} tokens, supply {} -> {}",
+        amount,
+        supply_before,
+        token_data.total_supply
+    );
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DepositToTreasuryCTX<'info> {
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(mut, has_one = mint)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = from.mint == mint.key()
+    )]
+    pub from: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"treasury_authority", mint.key().as_ref()],
+        bump
+    )]
+    /// CHECK: PDA that owns the treasury
+    pub treasury_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = treasury_authority
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as mint authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    pub depositor: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawFromTreasuryCTX<'info> {
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(mut, has_one = authority, has_one = mint)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"treasury_authority", mint.key().as_ref()],
+        bump
+    )]
+    /// CHECK: PDA that owns the treasury
+    pub treasury_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = treasury_authority
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = destination.mint == mint.key()
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    /// Lookups for `destination`
+    pub receiver: ReceiverChecks<'info>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as mint authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct BuybackAndBurnCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_MINTING) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"treasury_authority", mint.key().as_ref()],
+        bump
+    )]
+    /// CHECK: PDA that owns the treasury
+    pub treasury_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = treasury_authority
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,288 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::hook::*;
+use crate::state::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey::MAX_SEED_LEN;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+pub fn handle_create_vault(ctx: Context<CreateVaultCTX>, name: String) -> Result<()> {
+    VaultMint {
+        token_program: &ctx.accounts.token_program,
+        mint: &ctx.accounts.mint,
+        mint_authority_pda: &ctx.accounts.mint_authority_pda,
+        creator: ctx.accounts.token_data.creator,
+        mint_authority_bump: ctx.bumps.mint_authority_pda,
+    }
+    .thaw_if_frozen(&ctx.accounts.vault_tokens)?;
+
+    ctx.accounts.vault.set_inner(Vault {
+        mint: ctx.accounts.mint.key(),
+        name: name.clone(),
+    });
+
+    emit_cpi!(VaultCreated {
+        mint: ctx.accounts.mint.key(),
+        vault: ctx.accounts.vault.key(),
+        name: name.clone(),
+    });
+    msg!("Vault {} created", name);
+    Ok(())
+}
+
+// Same rules as a treasury deposit: anyone can fund a vault, and the
+// tokens leave circulation until they're withdrawn
+pub fn handle_vault_deposit(
+    ctx: Context<VaultDepositCTX>,
+    name: String,
+    amount: u64,
+) -> Result<()> {
+    ctx.accounts
+        .program_config
+        .require_feature_enabled(FEATURE_MINTING)?;
+    require!(!ctx.accounts.token_data.is_paused, ErrorCode::TokenPaused);
+    require!(
+        !ctx.accounts.token_data.supply_locked,
+        ErrorCode::SupplyLocked
+    );
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let raw_amount = amount
+        .checked_mul(10u64.pow(ctx.accounts.token_data.decimals as u32))
+        .ok_or(ErrorCode::InvalidAmount)?;
+
+    VaultMint {
+        token_program: &ctx.accounts.token_program,
+        mint: &ctx.accounts.mint,
+        mint_authority_pda: &ctx.accounts.mint_authority_pda,
+        creator: ctx.accounts.token_data.creator,
+        mint_authority_bump: ctx.bumps.mint_authority_pda,
+    }
+    .deposit(
+        &ctx.accounts.from,
+        &ctx.accounts.depositor,
+        &ctx.accounts.vault_tokens,
+        raw_amount,
+    )?;
+    ctx.accounts.token_data.record_vault_deposit(amount)?;
+
+    emit_cpi!(VaultDeposited {
+        mint: ctx.accounts.mint.key(),
+        vault: ctx.accounts.vault.key(),
+        from: ctx.accounts.from.key(),
+        amount,
+        raw_amount,
+    });
+    msg!("Deposited {} tokens to vault {}", amount, name);
+    Ok(())
+}
+
+pub fn handle_vault_withdraw(
+    ctx: Context<VaultWithdrawCTX>,
+    name: String,
+    amount: u64,
+) -> Result<()> {
+    ctx.accounts
+        .program_config
+        .require_feature_enabled(FEATURE_MINTING)?;
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let raw_amount = amount
+        .checked_mul(10u64.pow(ctx.accounts.token_data.decimals as u32))
+        .ok_or(ErrorCode::InvalidAmount)?;
+    // The payout is minted, skipping the hook, so its rules run here
+    ctx.accounts.receiver.require_can_receive(
+        &ctx.accounts.token_data,
+        &ctx.accounts.destination,
+        raw_amount,
+        unix_timestamp(ctx.remaining_accounts)?,
+    )?;
+
+    let token_data_key = ctx.accounts.token_data.key();
+    let vault_seeds = &[
+        b"vault",
+        token_data_key.as_ref(),
+        name.as_bytes(),
+        &[ctx.bumps.vault],
+    ];
+    VaultMint {
+        token_program: &ctx.accounts.token_program,
+        mint: &ctx.accounts.mint,
+        mint_authority_pda: &ctx.accounts.mint_authority_pda,
+        creator: ctx.accounts.token_data.creator,
+        mint_authority_bump: ctx.bumps.mint_authority_pda,
+    }
+    .release(
+        &ctx.accounts.vault_tokens,
+        &ctx.accounts.vault.to_account_info(),
+        vault_seeds,
+        &ctx.accounts.destination,
+        raw_amount,
+    )?;
+    ctx.accounts.token_data.record_vault_release(amount)?;
+
+    emit_cpi!(VaultWithdrawn {
+        mint: ctx.accounts.mint.key(),
+        vault: ctx.accounts.vault.key(),
+        to: ctx.accounts.destination.key(),
+        amount,
+        raw_amount,
+    });
+    msg!("Withdrew {} tokens from vault {}", amount, name);
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct CreateVaultCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    // Checked ahead of the vault seeds, which can't be derived past 32 bytes
+    #[account(
+        has_one = authority,
+        has_one = mint,
+        constraint = name.len() <= MAX_SEED_LEN @ ErrorCode::VaultNameTooLong
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Vault::SPACE,
+        seeds = [b"vault", token_data.key().as_ref(), name.as_bytes()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"vault_tokens", vault.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vault,
+        token::token_program = token_program
+    )]
+    pub vault_tokens: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as freeze authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct VaultDepositCTX<'info> {
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(mut, has_one = mint)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = from.mint == mint.key()
+    )]
+    pub from: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"vault", token_data.key().as_ref(), name.as_bytes()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        mut,
+        seeds = [b"vault_tokens", vault.key().as_ref()],
+        bump
+    )]
+    pub vault_tokens: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as mint authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    pub depositor: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct VaultWithdrawCTX<'info> {
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        has_one = mint,
+        constraint = token_data.authority == authority.key() || role.is_some() @ ErrorCode::Unauthorized
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"vault", token_data.key().as_ref(), name.as_bytes()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        mut,
+        seeds = [b"vault_tokens", vault.key().as_ref()],
+        bump
+    )]
+    pub vault_tokens: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = destination.mint == mint.key()
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    /// Lookups for `destination`
+    pub receiver: ReceiverChecks<'info>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as mint authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Lets a `VaultManager` act in place of the token authority
+    #[account(
+        seeds = [b"role", token_data.key().as_ref(), authority.key().as_ref(), &[Role::VaultManager as u8]],
+        bump
+    )]
+    pub role: Option<Account<'info, RoleGrant>>,
+}
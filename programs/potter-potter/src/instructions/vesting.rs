@@ -0,0 +1,339 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::hook::*;
+use crate::state::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+// Tokens move in and out of the vault through `VaultMint`, as with the
+// treasury, because a transfer CPI would re-enter this program via the hook.
+// Paying out needs the mint authority, so a supply-locked token can't vest.
+pub fn handle_create_vesting(
+    ctx: Context<CreateVestingCTX>,
+    beneficiary: Pubkey,
+    amount: u64,
+    start_at: i64,
+    cliff: i64,
+    duration: i64,
+) -> Result<()> {
+    ctx.accounts
+        .program_config
+        .require_feature_enabled(FEATURE_MINTING)?;
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    require!(
+        !ctx.accounts.token_data.supply_locked,
+        ErrorCode::SupplyLocked
+    );
+    require!(
+        cliff >= 0 && duration > 0 && cliff <= duration,
+        ErrorCode::InvalidVestingSchedule
+    );
+    require!(
+        duration <= ctx.accounts.program_config.limits.max_vesting_duration,
+        ErrorCode::LimitExceeded
+    );
+
+    let raw_amount = amount
+        .checked_mul(10u64.pow(ctx.accounts.token_data.decimals as u32))
+        .ok_or(ErrorCode::InvalidAmount)?;
+
+    let vault_mint = VaultMint {
+        token_program: &ctx.accounts.token_program,
+        mint: &ctx.accounts.mint,
+        mint_authority_pda: &ctx.accounts.mint_authority_pda,
+        creator: ctx.accounts.token_data.creator,
+        mint_authority_bump: ctx.bumps.mint_authority_pda,
+    };
+    vault_mint.thaw_if_frozen(&ctx.accounts.vault)?;
+    vault_mint.deposit(
+        &ctx.accounts.source,
+        &ctx.accounts.authority,
+        &ctx.accounts.vault,
+        raw_amount,
+    )?;
+    ctx.accounts.token_data.record_vault_deposit(amount)?;
+
+    ctx.accounts.vesting_schedule.set_inner(VestingSchedule {
+        mint: ctx.accounts.mint.key(),
+        beneficiary,
+        total: amount,
+        released: 0,
+        start_at,
+        cliff,
+        duration,
+    });
+
+    emit_cpi!(VestingCreated {
+        mint: ctx.accounts.mint.key(),
+        beneficiary,
+        amount,
+        start_at,
+        cliff,
+        duration,
+    });
+    msg!("Vesting {} tokens to {}", amount, beneficiary);
+    Ok(())
+}
+
+pub fn handle_claim_vested(ctx: Context<ClaimVestedCTX>) -> Result<()> {
+    ctx.accounts
+        .program_config
+        .require_feature_enabled(FEATURE_MINTING)?;
+    let now = unix_timestamp(ctx.remaining_accounts)?;
+    let amount = ctx.accounts.vesting_schedule.releasable(now);
+    require!(amount > 0, ErrorCode::NothingToClaim);
+
+    let raw_amount = amount
+        .checked_mul(10u64.pow(ctx.accounts.token_data.decimals as u32))
+        .ok_or(ErrorCode::InvalidAmount)?;
+    // The mint skips the hook, so the beneficiary is held to its rules here
+    ctx.accounts.receiver.require_can_receive(
+        &ctx.accounts.token_data,
+        &ctx.accounts.destination,
+        raw_amount,
+        now,
+    )?;
+
+    release_from_vault(
+        &VaultMint {
+            token_program: &ctx.accounts.token_program,
+            mint: &ctx.accounts.mint,
+            mint_authority_pda: &ctx.accounts.mint_authority_pda,
+            creator: ctx.accounts.token_data.creator,
+            mint_authority_bump: ctx.bumps.mint_authority_pda,
+        },
+        &ctx.accounts.vault,
+        &ctx.accounts.vesting_schedule,
+        ctx.bumps.vesting_schedule,
+        &ctx.accounts.destination,
+        raw_amount,
+    )?;
+    ctx.accounts.token_data.record_vault_release(amount)?;
+
+    let schedule = &mut ctx.accounts.vesting_schedule;
+    schedule.released += amount;
+
+    emit_cpi!(VestingClaimed {
+        mint: schedule.mint,
+        beneficiary: schedule.beneficiary,
+        amount,
+        released: schedule.released,
+    });
+    msg!("Claimed {} vested tokens", amount);
+    Ok(())
+}
+
+// Whatever has vested stays claimable; the rest goes back to the authority
+// and the schedule is cut down to what vested
+pub fn handle_revoke_vesting(ctx: Context<RevokeVestingCTX>) -> Result<()> {
+    ctx.accounts
+        .program_config
+        .require_feature_enabled(FEATURE_MINTING)?;
+    let now = unix_timestamp(ctx.remaining_accounts)?;
+    let vested = ctx.accounts.vesting_schedule.vested_amount(now);
+    let returned = ctx.accounts.vesting_schedule.total - vested;
+
+    if returned > 0 {
+        let raw_returned = returned
+            .checked_mul(10u64.pow(ctx.accounts.token_data.decimals as u32))
+            .ok_or(ErrorCode::InvalidAmount)?;
+        release_from_vault(
+            &VaultMint {
+                token_program: &ctx.accounts.token_program,
+                mint: &ctx.accounts.mint,
+                mint_authority_pda: &ctx.accounts.mint_authority_pda,
+                creator: ctx.accounts.token_data.creator,
+                mint_authority_bump: ctx.bumps.mint_authority_pda,
+            },
+            &ctx.accounts.vault,
+            &ctx.accounts.vesting_schedule,
+            ctx.bumps.vesting_schedule,
+            &ctx.accounts.destination,
+            raw_returned,
+        )?;
+        ctx.accounts.token_data.record_vault_release(returned)?;
+    }
+
+    let schedule = &mut ctx.accounts.vesting_schedule;
+    schedule.total = vested;
+    schedule.cliff = 0;
+    schedule.duration = 0;
+
+    emit_cpi!(VestingRevoked {
+        mint: schedule.mint,
+        beneficiary: schedule.beneficiary,
+        vested,
+        returned,
+    });
+    msg!("Vesting revoked: {} vested, {} returned", vested, returned);
+    Ok(())
+}
+
+// Pays base units out of a schedule's vault, signed by the schedule
+fn release_from_vault<'info>(
+    vault_mint: &VaultMint<'_, 'info>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    schedule: &Account<'info, VestingSchedule>,
+    bump: u8,
+    destination: &InterfaceAccount<'info, TokenAccount>,
+    raw_amount: u64,
+) -> Result<()> {
+    let seeds = &[
+        b"vesting",
+        schedule.mint.as_ref(),
+        schedule.beneficiary.as_ref(),
+        &[bump],
+    ];
+    vault_mint.release(
+        vault,
+        &schedule.to_account_info(),
+        seeds,
+        destination,
+        raw_amount,
+    )
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(beneficiary: Pubkey)]
+pub struct CreateVestingCTX<'info> {
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(mut, has_one = authority, has_one = mint)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = VestingSchedule::SPACE,
+        seeds = [b"vesting", mint.key().as_ref(), beneficiary.as_ref()],
+        bump
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"vesting_vault", vesting_schedule.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vesting_schedule,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = source.mint == mint.key()
+    )]
+    pub source: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as mint authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimVestedCTX<'info> {
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(mut, has_one = mint)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        has_one = beneficiary,
+        seeds = [b"vesting", mint.key().as_ref(), beneficiary.key().as_ref()],
+        bump
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+
+    #[account(
+        mut,
+        seeds = [b"vesting_vault", vesting_schedule.key().as_ref()],
+        bump
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = destination.mint == mint.key(),
+        constraint = destination.owner == beneficiary.key() @ ErrorCode::Unauthorized
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    /// Lookups for `destination`
+    pub receiver: ReceiverChecks<'info>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as mint authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    pub beneficiary: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RevokeVestingCTX<'info> {
+    #[account(seeds = [b"program_config"], bump)]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(mut, has_one = authority, has_one = mint)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"vesting", mint.key().as_ref(), vesting_schedule.beneficiary.as_ref()],
+        bump
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+
+    #[account(
+        mut,
+        seeds = [b"vesting_vault", vesting_schedule.key().as_ref()],
+        bump
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = destination.mint == mint.key(),
+        constraint = destination.owner == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"mint_authority", token_data.creator.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA used as mint authority
+    pub mint_authority_pda: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
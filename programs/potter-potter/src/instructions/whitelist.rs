@@ -0,0 +1,768 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+pub fn handle_create_whitelist_page(ctx: Context<CreateWhitelistPageCTX>, page: u8) -> Result<()> {
+    ctx.accounts.whitelist.set_inner(Whitelist {
+        addresses: vec![],
+        suspended: vec![],
+    });
+    msg!("Whitelist page {} created", page);
+    Ok(())
+}
+
+pub fn handle_add_to_whitelist(
+    ctx: Context<AddToWhitelistCTX>,
+    page: u8,
+    addresses: Vec<Pubkey>,
+) -> Result<()> {
+    ctx.accounts.token_data.require_unlocked(LOCK_WHITELIST)?;
+    require!(!addresses.is_empty(), ErrorCode::InvalidAmount);
+    require!(
+        addresses.iter().all(|addr| whitelist_page_of(addr) == page),
+        ErrorCode::WrongWhitelistPage
+    );
+
+    let mut added = vec![];
+    for addr in &addresses {
+        if !ctx.accounts.whitelist.addresses.contains(addr) {
+            ctx.accounts.whitelist.addresses.push(*addr);
+            added.push(*addr);
+        }
+    }
+    ctx.accounts
+        .program_config
+        .limits
+        .require_page_size(ctx.accounts.whitelist.addresses.len())?;
+
+    msg!("Added {} addresses to whitelist", addresses.len());
+    emit_cpi!(WhitelistUpdated {
+        mint: ctx.accounts.token_data.mint,
+        change: WhitelistChange::AddressesAdded {
+            page,
+            addresses: added,
+        },
+    });
+    Ok(())
+}
+
+pub fn handle_remove_from_whitelist(
+    ctx: Context<RemoveFromWhitelistCTX>,
+    page: u8,
+    addresses: Vec<Pubkey>,
+) -> Result<()> {
+    ctx.accounts.token_data.require_unlocked(LOCK_WHITELIST)?;
+    let whitelist = &mut ctx.accounts.whitelist;
+    let mut removed = vec![];
+    for addr in addresses {
+        if whitelist.addresses.contains(&addr) {
+            removed.push(addr);
+        }
+        whitelist.addresses.retain(|&x| x != addr);
+        whitelist.suspended.retain(|&x| x != addr);
+    }
+    msg!("Removed addresses from whitelist");
+    emit_cpi!(WhitelistUpdated {
+        mint: ctx.accounts.token_data.mint,
+        change: WhitelistChange::AddressesRemoved {
+            page,
+            addresses: removed,
+        },
+    });
+    Ok(())
+}
+
+// Holds the owner's page listing, its `WhitelistEntry`, or both, whichever
+// are passed
+pub fn handle_suspend_address(ctx: Context<SuspendAddressCTX>, address: Pubkey) -> Result<()> {
+    ctx.accounts.token_data.require_unlocked(LOCK_WHITELIST)?;
+    require!(
+        ctx.accounts.whitelist.is_some() || ctx.accounts.entry.is_some(),
+        ErrorCode::AddressNotWhitelisted
+    );
+    if let Some(whitelist) = &mut ctx.accounts.whitelist {
+        require!(
+            whitelist.addresses.contains(&address),
+            ErrorCode::AddressNotWhitelisted
+        );
+        if !whitelist.suspended.contains(&address) {
+            whitelist.suspended.push(address);
+        }
+    }
+    if let Some(entry) = &mut ctx.accounts.entry {
+        entry.suspended = true;
+    }
+    msg!("Whitelist entry suspended: {}", address);
+    emit_cpi!(WhitelistUpdated {
+        mint: ctx.accounts.token_data.mint,
+        change: WhitelistChange::AddressSuspended { address },
+    });
+    Ok(())
+}
+
+pub fn handle_restore_address(ctx: Context<RestoreAddressCTX>, address: Pubkey) -> Result<()> {
+    ctx.accounts.token_data.require_unlocked(LOCK_WHITELIST)?;
+    let mut restored = false;
+    if let Some(whitelist) = &mut ctx.accounts.whitelist {
+        restored |= whitelist.suspended.contains(&address);
+        whitelist.suspended.retain(|&x| x != address);
+    }
+    if let Some(entry) = &mut ctx.accounts.entry {
+        restored |= entry.suspended;
+        entry.suspended = false;
+    }
+    require!(restored, ErrorCode::AddressNotSuspended);
+    msg!("Whitelist entry restored: {}", address);
+    emit_cpi!(WhitelistUpdated {
+        mint: ctx.accounts.token_data.mint,
+        change: WhitelistChange::AddressRestored { address },
+    });
+    Ok(())
+}
+
+pub fn handle_add_whitelist_entry(
+    ctx: Context<AddWhitelistEntryCTX>,
+    owner: Pubkey,
+    expires_at: Option<i64>,
+) -> Result<()> {
+    ctx.accounts.token_data.require_unlocked(LOCK_WHITELIST)?;
+    if let Some(expires_at) = expires_at {
+        require!(
+            expires_at > unix_timestamp(ctx.remaining_accounts)?,
+            ErrorCode::InvalidWhitelistExpiry
+        );
+    }
+    ctx.accounts.entry.expires_at = expires_at;
+    msg!(
+        "Whitelist entry added: {} (expires: {:?})",
+        owner,
+        expires_at
+    );
+    emit_cpi!(WhitelistUpdated {
+        mint: ctx.accounts.token_data.mint,
+        change: WhitelistChange::EntryAdded { owner },
+    });
+    Ok(())
+}
+
+pub fn handle_remove_whitelist_entry(
+    ctx: Context<RemoveWhitelistEntryCTX>,
+    owner: Pubkey,
+) -> Result<()> {
+    ctx.accounts.token_data.require_unlocked(LOCK_WHITELIST)?;
+    msg!("Whitelist entry removed: {}", owner);
+    emit_cpi!(WhitelistUpdated {
+        mint: ctx.accounts.token_data.mint,
+        change: WhitelistChange::EntryRemoved { owner },
+    });
+    Ok(())
+}
+
+// Permissionless: an expired entry already counts for nothing, so removing
+// it only returns the rent to the token authority
+pub fn handle_prune_expired(ctx: Context<PruneExpiredCTX>, owner: Pubkey) -> Result<()> {
+    require!(
+        ctx.accounts
+            .entry
+            .is_expired(unix_timestamp(ctx.remaining_accounts)?),
+        ErrorCode::WhitelistEntryNotExpired
+    );
+    msg!("Expired whitelist entry pruned: {}", owner);
+    emit_cpi!(WhitelistUpdated {
+        mint: ctx.accounts.token_data.mint,
+        change: WhitelistChange::EntryExpired { owner },
+    });
+    Ok(())
+}
+
+pub fn handle_claim_whitelist_entry(
+    ctx: Context<ClaimWhitelistEntryCTX>,
+    owner: Pubkey,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let root = ctx
+        .accounts
+        .token_data
+        .whitelist_root
+        .ok_or(ErrorCode::WhitelistRootNotSet)?;
+    require!(
+        verify_whitelist_proof(&root, &owner, &proof),
+        ErrorCode::InvalidWhitelistProof
+    );
+    // Merkle claims carry no expiry, and rotating the root leaves claimed
+    // entries in place; they stay valid until `remove_whitelist_entry`
+    ctx.accounts.entry.expires_at = None;
+    msg!("Whitelist entry claimed: {}", owner);
+    emit_cpi!(WhitelistUpdated {
+        mint: ctx.accounts.token_data.mint,
+        change: WhitelistChange::EntryAdded { owner },
+    });
+    Ok(())
+}
+
+pub fn handle_add_token_account_to_whitelist(
+    ctx: Context<AddTokenAccountToWhitelistCTX>,
+) -> Result<()> {
+    let token_data = &mut ctx.accounts.token_data;
+    token_data.require_unlocked(LOCK_WHITELIST)?;
+    require!(
+        token_data.token_account_whitelisting,
+        ErrorCode::TokenAccountWhitelistingDisabled
+    );
+
+    token_data.token_account_entries = token_data
+        .token_account_entries
+        .checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
+    msg!(
+        "Token account whitelisted: {}",
+        ctx.accounts.token_account.key()
+    );
+    emit_cpi!(WhitelistUpdated {
+        mint: token_data.mint,
+        change: WhitelistChange::TokenAccountAdded {
+            token_account: ctx.accounts.token_account.key(),
+        },
+    });
+    Ok(())
+}
+
+pub fn handle_remove_token_account_from_whitelist(
+    ctx: Context<RemoveTokenAccountFromWhitelistCTX>,
+) -> Result<()> {
+    let token_data = &mut ctx.accounts.token_data;
+    token_data.require_unlocked(LOCK_WHITELIST)?;
+
+    token_data.token_account_entries = token_data.token_account_entries.saturating_sub(1);
+    msg!(
+        "Token account removed from whitelist: {}",
+        ctx.accounts.token_account.key()
+    );
+    emit_cpi!(WhitelistUpdated {
+        mint: token_data.mint,
+        change: WhitelistChange::TokenAccountRemoved {
+            token_account: ctx.accounts.token_account.key(),
+        },
+    });
+    Ok(())
+}
+
+pub fn handle_mirror_whitelist(ctx: Context<MirrorWhitelistCTX>) -> Result<()> {
+    let token_data = &mut ctx.accounts.token_data;
+    token_data.require_unlocked(LOCK_WHITELIST)?;
+
+    // Without a source the token goes back to its own pages. The source has
+    // to own the pages it's read from, so the hook stays at a single page
+    // lookup and the pages' owner knows how many tokens read them.
+    let whitelist_mint = match &ctx.accounts.source_token_data {
+        Some(source) => {
+            require_keys_eq!(
+                source.authority,
+                token_data.authority,
+                ErrorCode::InvalidWhitelistSource
+            );
+            require!(
+                source.whitelist_mint == source.mint && source.mint != token_data.mint,
+                ErrorCode::InvalidWhitelistSource
+            );
+            source.mint
+        }
+        None => token_data.mint,
+    };
+
+    if whitelist_mint != token_data.whitelist_mint {
+        if token_data.whitelist_mint != token_data.mint {
+            let previous = ctx
+                .accounts
+                .previous_source_token_data
+                .as_mut()
+                .ok_or(ErrorCode::InvalidWhitelistSource)?;
+            require_keys_eq!(
+                previous.mint,
+                token_data.whitelist_mint,
+                ErrorCode::InvalidWhitelistSource
+            );
+            previous.whitelist_mirrors = previous.whitelist_mirrors.saturating_sub(1);
+        }
+        if let Some(source) = &mut ctx.accounts.source_token_data {
+            source.whitelist_mirrors = source
+                .whitelist_mirrors
+                .checked_add(1)
+                .ok_or(ErrorCode::InvalidAmount)?;
+        }
+        token_data.whitelist_mint = whitelist_mint;
+    }
+    if let Some(manifest) = &mut ctx.accounts.hook_manifest {
+        manifest.refresh(token_data)?;
+    }
+    msg!("Whitelist now read from mint {}", token_data.whitelist_mint);
+    emit_cpi!(WhitelistUpdated {
+        mint: token_data.mint,
+        change: WhitelistChange::Mirrored {
+            whitelist_mint: token_data.whitelist_mint,
+        },
+    });
+    Ok(())
+}
+
+pub fn handle_get_whitelist(ctx: Context<GetWhitelistCTX>, _page: u8) -> Result<()> {
+    msg!(
+        "Total whitelisted addresses on page: {} ({} suspended)",
+        ctx.accounts.whitelist.addresses.len(),
+        ctx.accounts.whitelist.suspended.len()
+    );
+    for (i, addr) in ctx.accounts.whitelist.addresses.iter().enumerate() {
+        msg!("Address {}: {}", i, addr);
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(page: u8)]
+pub struct CreateWhitelistPageCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        constraint = token_data.authority == authority.key() || role.is_some() @ ErrorCode::Unauthorized
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Whitelist::space(10, 0),
+        seeds = [b"whitelist", token_data.mint.as_ref(), &[page]],
+        bump
+    )]
+    pub whitelist: Account<'info, Whitelist>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+
+    /// Lets a `WhitelistManager` act in place of the token authority
+    #[account(
+        seeds = [b"role", token_data.key().as_ref(), authority.key().as_ref(), &[Role::WhitelistManager as u8]],
+        bump
+    )]
+    pub role: Option<Account<'info, RoleGrant>>,
+}
+
+// Whitelist writes only touch token_data read-only, so updates to
+// different pages can land in parallel.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(page: u8, addresses: Vec<Pubkey>)]
+pub struct AddToWhitelistCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        constraint = token_data.authority == authority.key() || role.is_some() @ ErrorCode::Unauthorized
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"whitelist", token_data.mint.as_ref(), &[page]],
+        bump,
+        realloc = Whitelist::space(
+            whitelist.addresses.len() + addresses.len(),
+            whitelist.suspended.len()
+        ),
+        realloc::payer = authority,
+        realloc::zero = false,
+    )]
+    pub whitelist: Account<'info, Whitelist>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+
+    /// Lets a `WhitelistManager` act in place of the token authority
+    #[account(
+        seeds = [b"role", token_data.key().as_ref(), authority.key().as_ref(), &[Role::WhitelistManager as u8]],
+        bump
+    )]
+    pub role: Option<Account<'info, RoleGrant>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(page: u8)]
+pub struct RemoveFromWhitelistCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        constraint = token_data.authority == authority.key() || role.is_some() @ ErrorCode::Unauthorized
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"whitelist", token_data.mint.as_ref(), &[page]],
+        bump
+    )]
+    pub whitelist: Account<'info, Whitelist>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Lets a `WhitelistManager` act in place of the token authority
+    #[account(
+        seeds = [b"role", token_data.key().as_ref(), authority.key().as_ref(), &[Role::WhitelistManager as u8]],
+        bump
+    )]
+    pub role: Option<Account<'info, RoleGrant>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(address: Pubkey)]
+pub struct SuspendAddressCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        constraint = token_data.authority == authority.key() || role.is_some() @ ErrorCode::Unauthorized
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"whitelist", token_data.mint.as_ref(), &[whitelist_page_of(&address)]],
+        bump,
+        realloc = Whitelist::space(
+            whitelist.addresses.len(),
+            whitelist.suspended.len() + usize::from(!whitelist.suspended.contains(&address))
+        ),
+        realloc::payer = authority,
+        realloc::zero = false,
+    )]
+    pub whitelist: Option<Account<'info, Whitelist>>,
+
+    #[account(
+        mut,
+        seeds = [b"wl", token_data.mint.as_ref(), address.as_ref()],
+        bump
+    )]
+    pub entry: Option<Account<'info, WhitelistEntry>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+
+    /// Lets a `WhitelistManager` act in place of the token authority
+    #[account(
+        seeds = [b"role", token_data.key().as_ref(), authority.key().as_ref(), &[Role::WhitelistManager as u8]],
+        bump
+    )]
+    pub role: Option<Account<'info, RoleGrant>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(address: Pubkey)]
+pub struct RestoreAddressCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        constraint = token_data.authority == authority.key() || role.is_some() @ ErrorCode::Unauthorized
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"whitelist", token_data.mint.as_ref(), &[whitelist_page_of(&address)]],
+        bump
+    )]
+    pub whitelist: Option<Account<'info, Whitelist>>,
+
+    #[account(
+        mut,
+        seeds = [b"wl", token_data.mint.as_ref(), address.as_ref()],
+        bump
+    )]
+    pub entry: Option<Account<'info, WhitelistEntry>>,
+
+    pub authority: Signer<'info>,
+
+    /// Lets a `WhitelistManager` act in place of the token authority
+    #[account(
+        seeds = [b"role", token_data.key().as_ref(), authority.key().as_ref(), &[Role::WhitelistManager as u8]],
+        bump
+    )]
+    pub role: Option<Account<'info, RoleGrant>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct AddWhitelistEntryCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        constraint = token_data.authority == authority.key() || role.is_some() @ ErrorCode::Unauthorized
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = WhitelistEntry::SPACE,
+        seeds = [b"wl", token_data.mint.as_ref(), owner.as_ref()],
+        bump
+    )]
+    pub entry: Account<'info, WhitelistEntry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+
+    /// Lets a `WhitelistManager` act in place of the token authority
+    #[account(
+        seeds = [b"role", token_data.key().as_ref(), authority.key().as_ref(), &[Role::WhitelistManager as u8]],
+        bump
+    )]
+    pub role: Option<Account<'info, RoleGrant>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct RemoveWhitelistEntryCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        constraint = token_data.authority == authority.key() || role.is_some() @ ErrorCode::Unauthorized
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"wl", token_data.mint.as_ref(), owner.as_ref()],
+        bump
+    )]
+    pub entry: Account<'info, WhitelistEntry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Lets a `WhitelistManager` act in place of the token authority
+    #[account(
+        seeds = [b"role", token_data.key().as_ref(), authority.key().as_ref(), &[Role::WhitelistManager as u8]],
+        bump
+    )]
+    pub role: Option<Account<'info, RoleGrant>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct PruneExpiredCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"wl", token_data.mint.as_ref(), owner.as_ref()],
+        bump
+    )]
+    pub entry: Account<'info, WhitelistEntry>,
+
+    /// CHECK: receives the rent, only checked against the token data
+    #[account(mut, address = token_data.authority)]
+    pub authority: UncheckedAccount<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct ClaimWhitelistEntryCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = WhitelistEntry::SPACE,
+        seeds = [b"wl", token_data.mint.as_ref(), owner.as_ref()],
+        bump
+    )]
+    pub entry: Account<'info, WhitelistEntry>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AddTokenAccountToWhitelistCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        constraint = token_data.authority == authority.key() || role.is_some() @ ErrorCode::Unauthorized
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(constraint = token_account.mint == token_data.mint)]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8,
+        seeds = [b"whitelisted_account", token_data.mint.as_ref(), token_account.key().as_ref()],
+        bump
+    )]
+    pub entry: Account<'info, WhitelistedTokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+
+    /// Lets a `WhitelistManager` act in place of the token authority
+    #[account(
+        seeds = [b"role", token_data.key().as_ref(), authority.key().as_ref(), &[Role::WhitelistManager as u8]],
+        bump
+    )]
+    pub role: Option<Account<'info, RoleGrant>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RemoveTokenAccountFromWhitelistCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        constraint = token_data.authority == authority.key() || role.is_some() @ ErrorCode::Unauthorized
+    )]
+    pub token_data: Account<'info, TokenData>,
+
+    /// CHECK: Only used as a seed; the account may already be closed
+    pub token_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"whitelisted_account", token_data.mint.as_ref(), token_account.key().as_ref()],
+        bump
+    )]
+    pub entry: Account<'info, WhitelistedTokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Lets a `WhitelistManager` act in place of the token authority
+    #[account(
+        seeds = [b"role", token_data.key().as_ref(), authority.key().as_ref(), &[Role::WhitelistManager as u8]],
+        bump
+    )]
+    pub role: Option<Account<'info, RoleGrant>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MirrorWhitelistCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(mut, has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    /// Token whose whitelist is mirrored; omit to use this token's own
+    #[account(mut)]
+    pub source_token_data: Option<Account<'info, TokenData>>,
+
+    /// Token currently mirrored, required when switching away from one
+    #[account(mut)]
+    pub previous_source_token_data: Option<Account<'info, TokenData>>,
+
+    #[account(
+        mut,
+        seeds = [b"hook_manifest", token_data.mint.as_ref()],
+        bump
+    )]
+    pub hook_manifest: Option<Account<'info, HookManifest>>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(page: u8)]
+pub struct GetWhitelistCTX<'info> {
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        seeds = [b"whitelist", token_data.mint.as_ref(), &[page]],
+        bump
+    )]
+    pub whitelist: Account<'info, Whitelist>,
+}
@@ -0,0 +1,222 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+
+pub fn handle_begin_whitelist_import(
+    ctx: Context<BeginWhitelistImportCTX>,
+    page: u8,
+) -> Result<()> {
+    ctx.accounts.token_data.require_unlocked(LOCK_WHITELIST)?;
+    ctx.accounts.import.set_inner(WhitelistImport {
+        page,
+        next_chunk: 0,
+        committed: 0,
+        addresses: vec![],
+    });
+    msg!("Whitelist import started for page {}", page);
+    Ok(())
+}
+
+pub fn handle_append_whitelist_import(
+    ctx: Context<AppendWhitelistImportCTX>,
+    page: u8,
+    chunk_index: u32,
+    addresses: Vec<Pubkey>,
+) -> Result<()> {
+    let import = &mut ctx.accounts.import;
+
+    // Retried chunks are no-ops so clients can resend blindly
+    if chunk_index < import.next_chunk {
+        msg!("Chunk {} already appended", chunk_index);
+        return Ok(());
+    }
+    require!(chunk_index == import.next_chunk, ErrorCode::ChunkOutOfOrder);
+    require!(import.committed == 0, ErrorCode::ImportAlreadyCommitting);
+    require!(
+        addresses.iter().all(|addr| whitelist_page_of(addr) == page),
+        ErrorCode::WrongWhitelistPage
+    );
+
+    import.addresses.extend_from_slice(&addresses);
+    import.next_chunk = chunk_index.checked_add(1).ok_or(ErrorCode::Overflow)?;
+
+    msg!(
+        "Appended chunk {} ({} addresses staged)",
+        chunk_index,
+        import.addresses.len()
+    );
+    Ok(())
+}
+
+pub fn handle_commit_whitelist_import(
+    ctx: Context<CommitWhitelistImportCTX>,
+    page: u8,
+) -> Result<()> {
+    ctx.accounts.token_data.require_unlocked(LOCK_WHITELIST)?;
+    let import = &mut ctx.accounts.import;
+    let start = import.committed as usize;
+    let end = start + import.next_batch_len();
+
+    let mut added = vec![];
+    for addr in &import.addresses[start..end] {
+        if !ctx.accounts.whitelist.addresses.contains(addr) {
+            ctx.accounts.whitelist.addresses.push(*addr);
+            added.push(*addr);
+        }
+    }
+    ctx.accounts
+        .program_config
+        .limits
+        .require_page_size(ctx.accounts.whitelist.addresses.len())?;
+    import.committed = end as u32;
+    emit_cpi!(WhitelistUpdated {
+        mint: ctx.accounts.token_data.mint,
+        change: WhitelistChange::AddressesAdded {
+            page,
+            addresses: added,
+        },
+    });
+
+    msg!(
+        "Committed {} of {} staged addresses",
+        import.committed,
+        import.addresses.len()
+    );
+
+    if end == import.addresses.len() {
+        ctx.accounts
+            .import
+            .close(ctx.accounts.authority.to_account_info())?;
+        msg!("Whitelist import complete");
+    }
+    Ok(())
+}
+
+pub fn handle_cancel_whitelist_import(
+    _ctx: Context<CancelWhitelistImportCTX>,
+    _page: u8,
+) -> Result<()> {
+    msg!("Whitelist import cancelled");
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(page: u8)]
+pub struct BeginWhitelistImportCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = WhitelistImport::space(0),
+        seeds = [b"whitelist_import", token_data.mint.as_ref(), &[page]],
+        bump
+    )]
+    pub import: Account<'info, WhitelistImport>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(page: u8, chunk_index: u32, addresses: Vec<Pubkey>)]
+pub struct AppendWhitelistImportCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"whitelist_import", token_data.mint.as_ref(), &[page]],
+        bump,
+        realloc = import.space_after_append(chunk_index, addresses.len()),
+        realloc::payer = authority,
+        realloc::zero = false,
+    )]
+    pub import: Account<'info, WhitelistImport>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(page: u8)]
+pub struct CommitWhitelistImportCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        seeds = [b"whitelist_import", token_data.mint.as_ref(), &[page]],
+        bump
+    )]
+    pub import: Account<'info, WhitelistImport>,
+
+    #[account(
+        mut,
+        seeds = [b"whitelist", token_data.mint.as_ref(), &[page]],
+        bump,
+        realloc = Whitelist::space(
+            whitelist.addresses.len() + import.next_batch_len(),
+            whitelist.suspended.len()
+        ),
+        realloc::payer = authority,
+        realloc::zero = false,
+    )]
+    pub whitelist: Account<'info, Whitelist>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(page: u8)]
+pub struct CancelWhitelistImportCTX<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.is_enabled(FEATURE_ADMIN) @ ErrorCode::FeatureDisabled
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(has_one = authority)]
+    pub token_data: Account<'info, TokenData>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"whitelist_import", token_data.mint.as_ref(), &[page]],
+        bump
+    )]
+    pub import: Account<'info, WhitelistImport>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
@@ -1,30 +1,28 @@
 use anchor_lang::prelude::*;
-use anchor_spl::associated_token;
-use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token_2022::spl_token_2022::{
-    extension::{
-        transfer_hook::TransferHookAccount, BaseStateWithExtensionsMut, PodStateWithExtensionsMut,
-    },
-    pod::PodAccount,
-};
-use anchor_spl::token_interface::{
-    burn, mint_to, Burn, Mint, MintTo, TokenAccount, TokenInterface,
-};
-use anchor_lang::solana_program::sysvar;
-use mpl_token_metadata::instructions::{CreateV1InstructionArgs, CreateV1};
-use mpl_token_metadata::types::{ PrintSupply, TokenStandard};
-use anchor_lang::solana_program::program::invoke_signed;
-use mpl_token_metadata::ID as MPL_TOKEN_METADATA_ID;
 use spl_discriminator::discriminator::SplDiscriminate;
-use spl_tlv_account_resolution::{
-    account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList,
-};
 use spl_transfer_hook_interface::instruction::{
     ExecuteInstruction, InitializeExtraAccountMetaListInstruction,
+    UpdateExtraAccountMetaListInstruction,
 };
 
 mod errors;
-use errors::ErrorCode;
+pub mod events;
+pub mod hook;
+pub mod instructions;
+pub mod state;
+pub mod utils;
+
+#[cfg(test)]
+mod test_utils;
+
+// Mock clocks and other test-only instructions must never ship to mainnet
+#[cfg(all(feature = "mainnet", feature = "test-harness"))]
+compile_error!("the `mainnet` and `test-harness` features can't be enabled together");
+
+pub use hook::*;
+pub use instructions::*;
+pub use state::*;
+pub use utils::*;
 
 declare_id!("A3jca3XyW52j1aMdpE75affvCtgyN4UwNc1Sn2ahLzo6");
 
@@ -32,18 +30,67 @@ declare_id!("A3jca3XyW52j1aMdpE75affvCtgyN4UwNc1Sn2ahLzo6");
 pub mod potter_potter {
     use super::*;
 
+    pub fn initialize_protocol_stats(ctx: Context<InitializeProtocolStatsCTX>) -> Result<()> {
+        instructions::handle_initialize_protocol_stats(ctx)
+    }
+
     pub fn create_factory(ctx: Context<CreateFactoryCTX>) -> Result<()> {
-        ctx.accounts.factory.set_inner(TokenFactory {
-            authority: ctx.accounts.authority.key(),
-            token_count: 0,
-        });
-        msg!(
-            "Factory created with authority: {}",
-            ctx.accounts.authority.key()
-        );
-        Ok(())
+        instructions::handle_create_factory(ctx)
+    }
+
+    pub fn initialize_program_config(
+        ctx: Context<InitializeProgramConfigCTX>,
+        is_devnet: bool,
+    ) -> Result<()> {
+        instructions::handle_initialize_program_config(ctx, is_devnet)
+    }
+
+    pub fn set_v1_disabled(ctx: Context<UpdateProgramConfigCTX>, disabled: bool) -> Result<()> {
+        instructions::handle_set_v1_disabled(ctx, disabled)
+    }
+
+    /// Commits the config and the `token_count` token data accounts passed
+    /// as remaining accounts to the next checkpoint in the chain.
+    pub fn checkpoint_state<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CheckpointStateCTX<'info>>,
+        token_count: u32,
+    ) -> Result<()> {
+        instructions::handle_checkpoint_state(ctx, token_count)
+    }
+
+    /// Replaces the deployment's parameter caps; each must stay within the
+    /// program's hard ceilings.
+    pub fn set_limits(ctx: Context<UpdateProgramConfigCTX>, limits: Limits) -> Result<()> {
+        instructions::handle_set_limits(ctx, limits)
+    }
+
+    /// Test-harness builds only: pins the time seen by timelocks and
+    /// expiries, for integration tests that need to skip ahead.
+    #[cfg(feature = "test-harness")]
+    pub fn set_mock_clock(ctx: Context<SetMockClockCTX>, unix_timestamp: i64) -> Result<()> {
+        instructions::handle_set_mock_clock(ctx, unix_timestamp)
+    }
+
+    /// Kill switches: halts the `FEATURE_*` subsystems in `mask` for every
+    /// token, leaving the rest running. Pass 0 to re-enable everything.
+    /// Refused while a `program_pause` is in effect.
+    pub fn set_disabled_features(ctx: Context<UpdateProgramConfigCTX>, mask: u16) -> Result<()> {
+        instructions::handle_set_disabled_features(ctx, mask)
+    }
+
+    /// Upgrade-authority emergency stop: disables every `FEATURE_*`
+    /// subsystem, transfers and admin actions included.
+    pub fn program_pause(ctx: Context<ProgramPauseCTX>) -> Result<()> {
+        instructions::handle_program_pause(ctx)
     }
 
+    /// Re-enables one `ResumePhase` after a `program_pause`, in the order
+    /// admin ops, transfers, minting, sales.
+    pub fn program_resume(ctx: Context<ProgramPauseCTX>, phase: ResumePhase) -> Result<()> {
+        instructions::handle_program_resume(ctx, phase)
+    }
+
+    /// Deprecated v1 entrypoint, delegates to `create_token_v2`.
     pub fn create_token(
         ctx: Context<CreateTokenCTX>,
         total_supply: u64,
@@ -52,701 +99,879 @@ pub mod potter_potter {
         uri: String,
         default_address: Pubkey,
     ) -> Result<()> {
-        // Validation
-        require!(name.len() <= 32, ErrorCode::NameTooLong);
-        require!(symbol.len() <= 10, ErrorCode::SymbolTooLong);
-        require!(uri.len() <= 200, ErrorCode::UriTooLong);
-        require!(total_supply > 0, ErrorCode::InvalidAmount);
-
-        let factory = &mut ctx.accounts.factory;
-        let token_count = factory.token_count;
-
-        // Initialize token data
-        ctx.accounts.token_data.set_inner(TokenData {
-            mint: ctx.accounts.mint.key(),
-            authority: factory.authority,
-            total_supply,
-            decimals: 9,
-            is_paused: false,
-            is_minting_paused: false,
-            name: name.clone(),
-            symbol: symbol.clone(),
-            uri: uri.clone(),
-            whitelist: ctx.accounts.whitelist.key(),
-        });
-
-        // Initialize whitelist with default address
-        ctx.accounts.whitelist.set_inner(Whitelist {
-            addresses: vec![default_address],
-        });
-
-        factory.token_count = token_count.checked_add(1).unwrap();
-
-        // Create associated token account for the authority
-        let cpi_accounts = associated_token::Create {
-            payer: ctx.accounts.authority.to_account_info(),
-            associated_token: ctx.accounts.ata.to_account_info(),
-            authority: ctx.accounts.authority.to_account_info(),
-            mint: ctx.accounts.mint.to_account_info(),
-            system_program: ctx.accounts.system_program.to_account_info(),
-            token_program: ctx.accounts.token_program.to_account_info(),
-        };
-        associated_token::create(CpiContext::new(
-            ctx.accounts.associated_token_program.to_account_info(),
-            cpi_accounts,
-        ))?;
-
-        // Create metadata
-        let bump_seed = [ctx.bumps.mint_authority_pda];
-        let signer_seeds = &[&[
-            b"mint_authority",
-            ctx.accounts.authority.key.as_ref(),
-            &bump_seed,
-        ][..]];
-
-        let ix = CreateV1 {
-            metadata: ctx.accounts.metadata.key(),
-            master_edition: None,
-            mint: (ctx.accounts.mint.key(), false),
-            authority: ctx.accounts.mint_authority_pda.key(),
-            payer: ctx.accounts.authority.key(),
-            update_authority: (ctx.accounts.mint_authority_pda.key(), true),
-            system_program: ctx.accounts.system_program.key(),
-            sysvar_instructions: sysvar::instructions::ID,
-            spl_token_program: Some(ctx.accounts.token_program.key()),
-        }
-        .instruction(CreateV1InstructionArgs {
-            name,
-            symbol,
-            uri,
-            seller_fee_basis_points: 0,
-            creators: None,
-            primary_sale_happened: false,
-            is_mutable: true,
-            token_standard: TokenStandard::Fungible,
-            collection: None,
-            uses: None,
-            collection_details: None,
-            rule_set: None,
-            decimals: Some(9),
-            print_supply: Some(PrintSupply::Zero),
-        });
-
-        invoke_signed(
-            &ix,
-            &[
-                ctx.accounts.metadata.to_account_info(),
-                ctx.accounts.mint.to_account_info(),
-                ctx.accounts.mint_authority_pda.to_account_info(),
-                ctx.accounts.authority.to_account_info(),
-                ctx.accounts.system_program.to_account_info(),
-                ctx.accounts.token_program.to_account_info(),
-                ctx.accounts.token_metadata_program.to_account_info(),
-            ],
-            signer_seeds,
-        )?;
-
-        // Mint initial supply using PDA authority
-        msg!("Minting initial supply: {} tokens", total_supply);
-
-        let raw_supply = total_supply
-            .checked_mul(10u64.pow(ctx.accounts.token_data.decimals as u32))
-            .ok_or(ErrorCode::InvalidAmount)?;
-
-        mint_to(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                MintTo {
-                    mint: ctx.accounts.mint.to_account_info(),
-                    to: ctx.accounts.ata.to_account_info(),
-                    authority: ctx.accounts.mint_authority_pda.to_account_info(),
-                },
-                signer_seeds,
-            ),
-            raw_supply,
-        )?;
-
-        msg!("Token created successfully");
-        Ok(())
+        instructions::handle_create_token(ctx, total_supply, name, symbol, uri, default_address)
+    }
+
+    pub fn create_token_v2(ctx: Context<CreateTokenCTX>, params: CreateTokenParams) -> Result<()> {
+        instructions::handle_create_token_v2(ctx, params)
+    }
+
+    /// Dry run of `create_token_v2`'s parameter checks, returning every
+    /// issue found; an empty list means the params are valid.
+    pub fn validate_token_params(
+        ctx: Context<ValidateTokenParamsCTX>,
+        params: CreateTokenParams,
+    ) -> Result<Vec<TokenParamIssue>> {
+        instructions::handle_validate_token_params(ctx, params)
+    }
+
+    /// Stages `create_token_v2` parameters for `mint` so they can be
+    /// reviewed before anything is created.
+    pub fn prepare_token_config(
+        ctx: Context<PrepareTokenConfigCTX>,
+        mint: Pubkey,
+        params: CreateTokenParams,
+    ) -> Result<()> {
+        instructions::handle_prepare_token_config(ctx, mint, params)
+    }
+
+    /// `create_token_v2` that requires, checks and closes the prepared
+    /// config for the mint.
+    pub fn execute_token_create(
+        ctx: Context<CreateTokenCTX>,
+        params: CreateTokenParams,
+    ) -> Result<()> {
+        instructions::handle_execute_token_create(ctx, params)
+    }
+
+    pub fn cancel_token_config(ctx: Context<CancelTokenConfigCTX>, mint: Pubkey) -> Result<()> {
+        instructions::handle_cancel_token_config(ctx, mint)
+    }
+
+    pub fn initialize_factory_collection(
+        ctx: Context<InitializeFactoryCollectionCTX>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        instructions::handle_initialize_factory_collection(ctx, name, symbol, uri)
+    }
+
+    /// Whitelists `owner` for every token of the factory whose
+    /// `WhitelistSource` reads the factory list.
+    pub fn add_factory_whitelist_entry(
+        ctx: Context<AddFactoryWhitelistEntryCTX>,
+        owner: Pubkey,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        instructions::handle_add_factory_whitelist_entry(ctx, owner, expires_at)
+    }
+
+    pub fn remove_factory_whitelist_entry(
+        ctx: Context<RemoveFactoryWhitelistEntryCTX>,
+        owner: Pubkey,
+    ) -> Result<()> {
+        instructions::handle_remove_factory_whitelist_entry(ctx, owner)
+    }
+
+    /// Permissionless: closes an expired factory whitelist entry, refunding
+    /// its rent to the factory authority.
+    pub fn prune_expired_factory_entry(
+        ctx: Context<PruneExpiredFactoryEntryCTX>,
+        owner: Pubkey,
+    ) -> Result<()> {
+        instructions::handle_prune_expired_factory_entry(ctx, owner)
+    }
+
+    /// Records that the signer holds an NFT from the token's verified gate
+    /// collection, which the hook then accepts as whitelisting for as long
+    /// as the NFT stays in the signer's pass account it's moved into. Each
+    /// NFT backs at most one live pass per token.
+    pub fn register_nft_pass(ctx: Context<RegisterNftPassCTX>) -> Result<()> {
+        instructions::handle_register_nft_pass(ctx)
+    }
+
+    /// Permissionless: closes a pass whose owner no longer holds its NFT,
+    /// or that was verified against a previous gate collection.
+    pub fn revoke_nft_pass(ctx: Context<RevokeNftPassCTX>, owner: Pubkey) -> Result<()> {
+        instructions::handle_revoke_nft_pass(ctx, owner)
+    }
+
+    pub fn create_whitelist_page(ctx: Context<CreateWhitelistPageCTX>, page: u8) -> Result<()> {
+        instructions::handle_create_whitelist_page(ctx, page)
     }
 
     pub fn add_to_whitelist(
         ctx: Context<AddToWhitelistCTX>,
-        _token_count: u64,
+        page: u8,
         addresses: Vec<Pubkey>,
     ) -> Result<()> {
-        require!(!addresses.is_empty(), ErrorCode::InvalidAmount);
-
-        for addr in &addresses {
-            if !ctx.accounts.whitelist.addresses.contains(addr) {
-                ctx.accounts.whitelist.addresses.push(*addr);
-            }
-        }
-
-        msg!("Added {} addresses to whitelist", addresses.len());
-        Ok(())
+        instructions::handle_add_to_whitelist(ctx, page, addresses)
     }
 
     pub fn remove_from_whitelist(
         ctx: Context<RemoveFromWhitelistCTX>,
-        _token_count: u64,
+        page: u8,
+        addresses: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::handle_remove_from_whitelist(ctx, page, addresses)
+    }
+
+    /// Puts an owner's page listing, its `WhitelistEntry`, or both on hold;
+    /// the hook denies them until `restore_address`.
+    pub fn suspend_address(ctx: Context<SuspendAddressCTX>, address: Pubkey) -> Result<()> {
+        instructions::handle_suspend_address(ctx, address)
+    }
+
+    pub fn restore_address(ctx: Context<RestoreAddressCTX>, address: Pubkey) -> Result<()> {
+        instructions::handle_restore_address(ctx, address)
+    }
+
+    // ============ CHUNKED WHITELIST IMPORT ============
+
+    pub fn begin_whitelist_import(ctx: Context<BeginWhitelistImportCTX>, page: u8) -> Result<()> {
+        instructions::handle_begin_whitelist_import(ctx, page)
+    }
+
+    pub fn append_whitelist_import(
+        ctx: Context<AppendWhitelistImportCTX>,
+        page: u8,
+        chunk_index: u32,
         addresses: Vec<Pubkey>,
     ) -> Result<()> {
-        for addr in addresses {
-            ctx.accounts.whitelist.addresses.retain(|&x| x != addr);
-        }
-        msg!("Removed addresses from whitelist");
-        Ok(())
-    }
-
-    pub fn get_whitelist(ctx: Context<GetWhitelistCTX>, _token_count: u64) -> Result<()> {
-        msg!(
-            "Total whitelisted addresses: {}",
-            ctx.accounts.whitelist.addresses.len()
-        );
-        for (i, addr) in ctx.accounts.whitelist.addresses.iter().enumerate() {
-            msg!("Address {}: {}", i, addr);
-        }
-        Ok(())
-    }
-
-    pub fn mint_tokens(ctx: Context<MintTokensCTX>, _token_count: u64, amount: u64) -> Result<()> {
-        require!(
-            !ctx.accounts.token_data.is_minting_paused,
-            ErrorCode::MintingPaused
-        );
-        require!(amount > 0, ErrorCode::InvalidAmount);
-
-        let raw_amount = amount
-            .checked_mul(10u64.pow(ctx.accounts.token_data.decimals as u32))
-            .ok_or(ErrorCode::InvalidAmount)?;
-
-        let authority_key = ctx.accounts.authority.key();
-        let seeds = &[
-            b"mint_authority",
-            authority_key.as_ref(),
-            &[ctx.bumps.mint_authority_pda],
-        ];
-        let signer_seeds = &[&seeds[..]];
-
-        mint_to(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                MintTo {
-                    mint: ctx.accounts.mint.to_account_info(),
-                    to: ctx.accounts.to.to_account_info(),
-                    authority: ctx.accounts.mint_authority_pda.to_account_info(),
-                },
-                signer_seeds,
-            ),
-            raw_amount,
-        )?;
-
-        // Update total supply (store human-readable amount)
-        ctx.accounts.token_data.total_supply = ctx
-            .accounts
-            .token_data
-            .total_supply
-            .checked_add(amount)
-            .ok_or(ErrorCode::InvalidAmount)?;
-
-        msg!("Minted {} tokens", amount);
-        Ok(())
-    }
-
-    pub fn burn_tokens(ctx: Context<BurnTokensCTX>, _token_count: u64, amount: u64) -> Result<()> {
-        require!(amount > 0, ErrorCode::InvalidAmount);
-
-        let raw_amount = amount
-            .checked_mul(10u64.pow(ctx.accounts.token_data.decimals as u32))
-            .ok_or(ErrorCode::InvalidAmount)?;
-
-        burn(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                Burn {
-                    mint: ctx.accounts.mint.to_account_info(),
-                    from: ctx.accounts.from.to_account_info(),
-                    authority: ctx.accounts.authority.to_account_info(),
-                },
-            ),
-            raw_amount,
-        )?;
-
-        // Update total supply (store human-readable amount)
-        ctx.accounts.token_data.total_supply = ctx
-            .accounts
-            .token_data
-            .total_supply
-            .checked_sub(amount)
-            .ok_or(ErrorCode::InvalidAmount)?;
-
-        msg!("Burned {} tokens", amount);
-        Ok(())
-    }
-
-    pub fn pause_minting(ctx: Context<PauseMintingCTX>, _token_count: u64) -> Result<()> {
-        ctx.accounts.token_data.is_minting_paused = !ctx.accounts.token_data.is_minting_paused;
-        msg!(
-            "Minting paused: {}",
-            ctx.accounts.token_data.is_minting_paused
-        );
-        Ok(())
-    }
-
-    pub fn pause_token(ctx: Context<PauseTokenCTX>, _token_count: u64) -> Result<()> {
-        ctx.accounts.token_data.is_paused = !ctx.accounts.token_data.is_paused;
-        msg!("Token paused: {}", ctx.accounts.token_data.is_paused);
-        Ok(())
-    }
-
-    pub fn transfer_authority(
-        ctx: Context<TransferAuthorityCTX>,
-        _token_count: u64,
+        instructions::handle_append_whitelist_import(ctx, page, chunk_index, addresses)
+    }
+
+    pub fn commit_whitelist_import(ctx: Context<CommitWhitelistImportCTX>, page: u8) -> Result<()> {
+        instructions::handle_commit_whitelist_import(ctx, page)
+    }
+
+    pub fn cancel_whitelist_import(ctx: Context<CancelWhitelistImportCTX>, page: u8) -> Result<()> {
+        instructions::handle_cancel_whitelist_import(ctx, page)
+    }
+
+    /// Bars `address` from sending or receiving the token, whatever the
+    /// whitelist policy.
+    pub fn blacklist_address(ctx: Context<BlacklistAddressCTX>, address: Pubkey) -> Result<()> {
+        instructions::handle_blacklist_address(ctx, address)
+    }
+
+    pub fn unblacklist_address(ctx: Context<UnblacklistAddressCTX>, address: Pubkey) -> Result<()> {
+        instructions::handle_unblacklist_address(ctx, address)
+    }
+
+    /// Whitelists `owner` through its own entry, optionally only until
+    /// `expires_at`.
+    pub fn add_whitelist_entry(
+        ctx: Context<AddWhitelistEntryCTX>,
+        owner: Pubkey,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        instructions::handle_add_whitelist_entry(ctx, owner, expires_at)
+    }
+
+    pub fn remove_whitelist_entry(
+        ctx: Context<RemoveWhitelistEntryCTX>,
+        owner: Pubkey,
+    ) -> Result<()> {
+        instructions::handle_remove_whitelist_entry(ctx, owner)
+    }
+
+    /// Permissionless: closes an expired whitelist entry, refunding its rent
+    /// to the token authority.
+    pub fn prune_expired(ctx: Context<PruneExpiredCTX>, owner: Pubkey) -> Result<()> {
+        instructions::handle_prune_expired(ctx, owner)
+    }
+
+    /// Grows a `WhitelistEntry` created before expiries were added to the
+    /// current layout, as one that never expires.
+    pub fn migrate_whitelist_entry(
+        ctx: Context<MigrateWhitelistEntryCTX>,
+        owner: Pubkey,
+    ) -> Result<()> {
+        instructions::handle_migrate_whitelist_entry(ctx, owner)
+    }
+
+    /// Lets `owner` receive up to `amount_cap` base units until `expires_at`
+    /// without being whitelisted. Reissuing replaces the previous permit.
+    pub fn issue_transfer_permit(
+        ctx: Context<IssueTransferPermitCTX>,
+        owner: Pubkey,
+        amount_cap: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::handle_issue_transfer_permit(ctx, owner, amount_cap, expires_at)
+    }
+
+    /// Closes a permit, spent or not, refunding its rent to the authority.
+    pub fn revoke_transfer_permit(
+        ctx: Context<RevokeTransferPermitCTX>,
+        owner: Pubkey,
+    ) -> Result<()> {
+        instructions::handle_revoke_transfer_permit(ctx, owner)
+    }
+
+    /// Creates the non-transferable mint for this token's membership
+    /// credentials, once per token.
+    pub fn initialize_credential_mint(ctx: Context<InitializeCredentialMintCTX>) -> Result<()> {
+        instructions::handle_initialize_credential_mint(ctx)
+    }
+
+    /// Mints a credential the hook accepts in place of list membership.
+    pub fn issue_credential(ctx: Context<IssueCredentialCTX>, owner: Pubkey) -> Result<()> {
+        instructions::handle_issue_credential(ctx, owner)
+    }
+
+    /// Freezes the credential so the hook stops accepting it.
+    pub fn revoke_credential(ctx: Context<RevokeCredentialCTX>, owner: Pubkey) -> Result<()> {
+        instructions::handle_revoke_credential(ctx, owner)
+    }
+
+    pub fn set_whitelist_root(
+        ctx: Context<SetWhitelistRootCTX>,
+        root: Option<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::handle_set_whitelist_root(ctx, root)
+    }
+
+    /// Creates the `WhitelistEntry` for an owner that proves membership
+    /// under the token's Merkle root; anyone may pay for it.
+    pub fn claim_whitelist_entry(
+        ctx: Context<ClaimWhitelistEntryCTX>,
+        owner: Pubkey,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::handle_claim_whitelist_entry(ctx, owner, proof)
+    }
+
+    pub fn add_token_account_to_whitelist(
+        ctx: Context<AddTokenAccountToWhitelistCTX>,
+    ) -> Result<()> {
+        instructions::handle_add_token_account_to_whitelist(ctx)
+    }
+
+    pub fn remove_token_account_from_whitelist(
+        ctx: Context<RemoveTokenAccountFromWhitelistCTX>,
+    ) -> Result<()> {
+        instructions::handle_remove_token_account_from_whitelist(ctx)
+    }
+
+    /// Points the hook at another token's whitelist pages, or back at this
+    /// token's own. A token that's mirrored can't be closed.
+    pub fn mirror_whitelist(ctx: Context<MirrorWhitelistCTX>) -> Result<()> {
+        instructions::handle_mirror_whitelist(ctx)
+    }
+
+    pub fn get_whitelist(ctx: Context<GetWhitelistCTX>, page: u8) -> Result<()> {
+        instructions::handle_get_whitelist(ctx, page)
+    }
+
+    pub fn get_circulating_supply(ctx: Context<GetCirculatingSupplyCTX>) -> Result<u64> {
+        instructions::handle_get_circulating_supply(ctx)
+    }
+
+    pub fn initialize_idempotency_log(ctx: Context<InitializeIdempotencyLogCTX>) -> Result<()> {
+        instructions::handle_initialize_idempotency_log(ctx)
+    }
+
+    pub fn mint_tokens(
+        ctx: Context<MintTokensCTX>,
+        amount: u64,
+        idempotency_key: Option<[u8; 16]>,
+    ) -> Result<()> {
+        instructions::handle_mint_tokens(ctx, amount, idempotency_key)
+    }
+
+    /// Mints `amounts[i]` whole tokens to the i-th pair of remaining
+    /// accounts (token account, owner's opt-out address), skipping owners
+    /// who opted out of distributions.
+    pub fn mint_tokens_to_many<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MintTokensToManyCTX<'info>>,
+        amounts: Vec<u64>,
+        idempotency_key: Option<[u8; 16]>,
+    ) -> Result<()> {
+        instructions::handle_mint_tokens_to_many(ctx, amounts, idempotency_key)
+    }
+
+    /// Stops distribution cranks such as `mint_tokens_to_many` from
+    /// sending this token to the signer.
+    pub fn opt_out_of_distributions(ctx: Context<OptOutOfDistributionsCTX>) -> Result<()> {
+        instructions::handle_opt_out_of_distributions(ctx)
+    }
+
+    pub fn opt_into_distributions(ctx: Context<OptIntoDistributionsCTX>) -> Result<()> {
+        instructions::handle_opt_into_distributions(ctx)
+    }
+
+    pub fn mint_with_reference(
+        ctx: Context<MintWithReferenceCTX>,
+        amount: u64,
+        reference: [u8; 32],
+        idempotency_key: Option<[u8; 16]>,
+    ) -> Result<()> {
+        instructions::handle_mint_with_reference(ctx, amount, reference, idempotency_key)
+    }
+
+    /// Permanently revokes the mint authority, fixing the supply. Vaults
+    /// have to be emptied first, since they pay out by minting.
+    pub fn lock_supply(ctx: Context<LockSupplyCTX>) -> Result<()> {
+        instructions::handle_lock_supply(ctx)
+    }
+
+    /// Reclaims rent from a token whose supply has been fully burned. The
+    /// whitelist pages to close are passed as remaining accounts, in the
+    /// order of `pages`.
+    pub fn close_token<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CloseTokenCTX<'info>>,
+        pages: Vec<u8>,
+    ) -> Result<()> {
+        instructions::handle_close_token(ctx, pages)
+    }
+
+    /// Burns tokens from any holder via the permanent delegate. With a
+    /// `destination`, the same amount is minted there instead of leaving
+    /// supply, which stands in for a forced transfer: a delegate transfer
+    /// would re-enter this program through the hook.
+    pub fn clawback(ctx: Context<ClawbackCTX>, amount: u64) -> Result<()> {
+        instructions::handle_clawback(ctx, amount)
+    }
+
+    /// Opts the token into dormant balance recovery. Recovery also needs
+    /// the permanent delegate, so the token must have been created with
+    /// `clawback`.
+    pub fn set_escheatment_policy(
+        ctx: Context<SetEscheatmentPolicyCTX>,
+        dormancy_period: i64,
+        waiting_period: i64,
+    ) -> Result<()> {
+        instructions::handle_set_escheatment_policy(ctx, dormancy_period, waiting_period)
+    }
+
+    /// Starts tracking a token account's outbound activity, which a
+    /// dormancy notice is later judged against. Anyone can pay for it.
+    pub fn open_activity_record(ctx: Context<OpenActivityRecordCTX>) -> Result<()> {
+        instructions::handle_open_activity_record(ctx)
+    }
+
+    pub fn file_dormancy_notice(ctx: Context<FileDormancyNoticeCTX>) -> Result<()> {
+        instructions::handle_file_dormancy_notice(ctx)
+    }
+
+    /// Lets the holder answer a pending notice without moving tokens.
+    pub fn contest_dormancy_notice(ctx: Context<ContestDormancyNoticeCTX>) -> Result<()> {
+        instructions::handle_contest_dormancy_notice(ctx)
+    }
+
+    pub fn cancel_dormancy_notice(ctx: Context<CancelDormancyNoticeCTX>) -> Result<()> {
+        instructions::handle_cancel_dormancy_notice(ctx)
+    }
+
+    /// Moves an uncontested noticed balance into custody once the waiting
+    /// period is over.
+    pub fn recover_dormant_balance(ctx: Context<RecoverDormantBalanceCTX>) -> Result<()> {
+        instructions::handle_recover_dormant_balance(ctx)
+    }
+
+    /// Only for tokens created with `interest_rate_bps`.
+    pub fn update_interest_rate(ctx: Context<UpdateInterestRateCTX>, rate_bps: i16) -> Result<()> {
+        instructions::handle_update_interest_rate(ctx, rate_bps)
+    }
+
+    /// New fees take effect two epochs later, as Token-2022 requires.
+    pub fn update_transfer_fee(
+        ctx: Context<UpdateTransferFeeCTX>,
+        basis_points: u16,
+        maximum_fee: u64,
+    ) -> Result<()> {
+        instructions::handle_update_transfer_fee(ctx, basis_points, maximum_fee)
+    }
+
+    /// Returns what the recipient of a transfer of `amount` base units
+    /// receives at the current epoch's fee.
+    pub fn quote_transfer(ctx: Context<QuoteTransferCTX>, amount: u64) -> Result<u64> {
+        instructions::handle_quote_transfer(ctx, amount)
+    }
+
+    /// Sweeps fees withheld in token accounts, passed as remaining
+    /// accounts, into the mint. Anyone may call it.
+    pub fn harvest_withheld_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, HarvestWithheldFeesCTX<'info>>,
+    ) -> Result<()> {
+        instructions::handle_harvest_withheld_fees(ctx)
+    }
+
+    pub fn withdraw_withheld_fees_to_treasury(ctx: Context<WithdrawWithheldFeesCTX>) -> Result<()> {
+        instructions::handle_withdraw_withheld_fees_to_treasury(ctx)
+    }
+
+    /// Moves whole tokens from the depositor into the mint's treasury.
+    pub fn deposit_to_treasury(ctx: Context<DepositToTreasuryCTX>, amount: u64) -> Result<()> {
+        instructions::handle_deposit_to_treasury(ctx, amount)
+    }
+
+    pub fn withdraw_from_treasury(
+        ctx: Context<WithdrawFromTreasuryCTX>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::handle_withdraw_from_treasury(ctx, amount)
+    }
+
+    /// Burns whole tokens bought back into the treasury, as one auditable
+    /// action with the supply before and after.
+    pub fn buyback_and_burn(ctx: Context<BuybackAndBurnCTX>, amount: u64) -> Result<()> {
+        instructions::handle_buyback_and_burn(ctx, amount)
+    }
+
+    /// Opens the token's `name` vault, a program-owned account that sales,
+    /// buybacks or rewards can share instead of each defining its own PDA.
+    pub fn create_vault(ctx: Context<CreateVaultCTX>, name: String) -> Result<()> {
+        instructions::handle_create_vault(ctx, name)
+    }
+
+    /// Moves whole tokens from the depositor into the `name` vault.
+    pub fn vault_deposit(ctx: Context<VaultDepositCTX>, name: String, amount: u64) -> Result<()> {
+        instructions::handle_vault_deposit(ctx, name, amount)
+    }
+
+    /// Pays whole tokens out of the `name` vault; the authority or a
+    /// `VaultManager` signs.
+    pub fn vault_withdraw(ctx: Context<VaultWithdrawCTX>, name: String, amount: u64) -> Result<()> {
+        instructions::handle_vault_withdraw(ctx, name, amount)
+    }
+
+    /// Locks `amount` whole tokens from the authority's `source` account in
+    /// a vault that releases them to `beneficiary` linearly over `duration`
+    /// seconds from `start_at`, with nothing before `cliff` seconds.
+    pub fn create_vesting(
+        ctx: Context<CreateVestingCTX>,
+        beneficiary: Pubkey,
+        amount: u64,
+        start_at: i64,
+        cliff: i64,
+        duration: i64,
+    ) -> Result<()> {
+        instructions::handle_create_vesting(ctx, beneficiary, amount, start_at, cliff, duration)
+    }
+
+    /// Pays what has vested so far to the beneficiary's own account.
+    pub fn claim_vested(ctx: Context<ClaimVestedCTX>) -> Result<()> {
+        instructions::handle_claim_vested(ctx)
+    }
+
+    /// Returns the unvested remainder to the authority; what has vested
+    /// stays claimable.
+    pub fn revoke_vesting(ctx: Context<RevokeVestingCTX>) -> Result<()> {
+        instructions::handle_revoke_vesting(ctx)
+    }
+
+    /// Sets up the mint's insurance vault, which keeps `fee_share_bps` of
+    /// each withheld-fee withdrawal.
+    pub fn create_insurance_fund(
+        ctx: Context<CreateInsuranceFundCTX>,
+        guardian: Pubkey,
+        fee_share_bps: u16,
+        veto_period: i64,
+    ) -> Result<()> {
+        instructions::handle_create_insurance_fund(ctx, guardian, fee_share_bps, veto_period)
+    }
+
+    /// Queues a payout of `raw_amount` base units from the insurance vault
+    /// to the `recipient` token account, executable once the guardian's
+    /// veto period has passed.
+    pub fn payout_claim(
+        ctx: Context<PayoutClaimCTX>,
+        recipient: Pubkey,
+        raw_amount: u64,
+        evidence_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::handle_payout_claim(ctx, recipient, raw_amount, evidence_hash)
+    }
+
+    pub fn veto_payout_claim(ctx: Context<VetoPayoutClaimCTX>, claim_id: u64) -> Result<()> {
+        instructions::handle_veto_payout_claim(ctx, claim_id)
+    }
+
+    pub fn execute_payout_claim(ctx: Context<ExecutePayoutClaimCTX>, claim_id: u64) -> Result<()> {
+        instructions::handle_execute_payout_claim(ctx, claim_id)
+    }
+
+    /// Burns whole tokens held in the `vault_name` vault opened with
+    /// `create_vault`, e.g. unsold supply or buybacks sent to it.
+    pub fn burn_from_vault(
+        ctx: Context<BurnFromVaultCTX>,
+        vault_name: String,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::handle_burn_from_vault(ctx, vault_name, amount)
+    }
+
+    pub fn burn_tokens(ctx: Context<BurnTokensCTX>, amount: u64) -> Result<()> {
+        instructions::handle_burn_tokens(ctx, amount)
+    }
+
+    pub fn burn_with_reason(
+        ctx: Context<BurnTokensCTX>,
+        amount: u64,
+        reason_code: u16,
+        reference_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::handle_burn_with_reason(ctx, amount, reason_code, reference_hash)
+    }
+
+    pub fn pause_minting(
+        ctx: Context<PauseMintingCTX>,
+        idempotency_key: Option<[u8; 16]>,
+    ) -> Result<()> {
+        instructions::handle_pause_minting(ctx, idempotency_key)
+    }
+
+    pub fn pause_token(
+        ctx: Context<PauseTokenCTX>,
+        idempotency_key: Option<[u8; 16]>,
+    ) -> Result<()> {
+        instructions::handle_pause_token(ctx, idempotency_key)
+    }
+
+    /// Nominates a new authority; nothing changes until it accepts.
+    pub fn propose_authority(
+        ctx: Context<ProposeAuthorityCTX>,
         new_authority: Pubkey,
     ) -> Result<()> {
-        let old_authority = ctx.accounts.token_data.authority;
-        ctx.accounts.token_data.authority = new_authority;
-        msg!(
-            "Authority transferred from {} to {}",
-            old_authority,
-            new_authority
-        );
-        Ok(())
+        instructions::handle_propose_authority(ctx, new_authority)
     }
 
-    // ============ TRANSFER HOOK IMPLEMENTATION ============
+    pub fn accept_authority(ctx: Context<AcceptAuthorityCTX>) -> Result<()> {
+        instructions::handle_accept_authority(ctx)
+    }
 
-    #[instruction(discriminator = ExecuteInstruction::SPL_DISCRIMINATOR_SLICE)]
-    pub fn transfer_hook(ctx: Context<TransferHook>, _amount: u64) -> Result<()> {
-        check_is_transferring(&ctx)?;
+    pub fn cancel_authority_transfer(ctx: Context<CancelAuthorityTransferCTX>) -> Result<()> {
+        instructions::handle_cancel_authority_transfer(ctx)
+    }
 
-        let destination_owner = ctx.accounts.destination_token.owner;
+    pub fn lock_config(ctx: Context<LockConfigCTX>, mask: u16) -> Result<()> {
+        instructions::handle_lock_config(ctx, mask)
+    }
 
-        // Check if destination is whitelisted
-        require!(
-            ctx.accounts
-                .whitelist
-                .addresses
-                .contains(&destination_owner),
-            ErrorCode::AddressNotWhitelisted
-        );
+    /// Lets `member` mint, pause or manage the whitelist, per `role`,
+    /// without holding the authority key.
+    pub fn grant_role(ctx: Context<GrantRoleCTX>, member: Pubkey, role: Role) -> Result<()> {
+        instructions::handle_grant_role(ctx, member, role)
+    }
 
-        msg!(
-            "Transfer hook passed: destination {} is whitelisted",
-            destination_owner
-        );
-        Ok(())
+    pub fn revoke_role(ctx: Context<RevokeRoleCTX>, member: Pubkey, role: Role) -> Result<()> {
+        instructions::handle_revoke_role(ctx, member, role)
     }
 
-    #[instruction(discriminator = InitializeExtraAccountMetaListInstruction::SPL_DISCRIMINATOR_SLICE)]
-    pub fn initialize_extra_account_meta_list(
-        ctx: Context<InitializeExtraAccountMetaList>,
-        _token_count: u64,
-    ) -> Result<()> {
-        let extra_account_metas = InitializeExtraAccountMetaList::extra_account_metas(
-            &ctx.accounts.authority.key(),
-            _token_count,
-        )?;
-
-        // Initialize ExtraAccountMetaList account with extra accounts
-        // Convert ProgramError to anchor_lang::error::Error
-        ExtraAccountMetaList::init::<ExecuteInstruction>(
-            &mut ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?,
-            &extra_account_metas,
-        )
-        .map_err(|e| {
-            msg!("Error initializing extra account meta list: {:?}", e);
-            error!(ErrorCode::InvalidAmount)
-        })?;
-
-        msg!(
-            "Transfer hook initialized for mint: {}",
-            ctx.accounts.mint.key()
-        );
-        Ok(())
+    /// Lets `minter` mint up to `allowance` whole tokens; calling again
+    /// replaces the remaining allowance.
+    pub fn set_minter_allowance(
+        ctx: Context<SetMinterAllowanceCTX>,
+        minter: Pubkey,
+        allowance: u64,
+    ) -> Result<()> {
+        instructions::handle_set_minter_allowance(ctx, minter, allowance)
     }
-}
 
-// ============ ACCOUNTS STRUCTS ============
-
-#[derive(Accounts)]
-pub struct CreateFactoryCTX<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + 32 + 8,
-        seeds = [b"factory", authority.key().as_ref()],
-        bump
-    )]
-    pub factory: Account<'info, TokenFactory>,
-
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
-}
+    pub fn revoke_minter_allowance(
+        ctx: Context<RevokeMinterAllowanceCTX>,
+        minter: Pubkey,
+    ) -> Result<()> {
+        instructions::handle_revoke_minter_allowance(ctx, minter)
+    }
 
-#[derive(Accounts)]
-pub struct CreateTokenCTX<'info> {
-    #[account(
-        mut,
-        has_one = authority,
-        seeds = [b"factory", authority.key().as_ref()],
-        bump
-    )]
-    pub factory: Account<'info, TokenFactory>,
-
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + 32 + 32 + 8 + 1 + 1 + 1 + (4 + 32) + (4 + 10) + (4 + 200) + 32,
-        seeds = [b"token", authority.key().as_ref(), &factory.token_count.to_le_bytes()],
-        bump
-    )]
-    pub token_data: Account<'info, TokenData>,
-
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + 4 + (32 * 10),
-        seeds = [b"whitelist", authority.key().as_ref(), &factory.token_count.to_le_bytes()],
-        bump
-    )]
-    pub whitelist: Account<'info, Whitelist>,
-
-    #[account(
-        init,
-        payer = authority,
-        mint::decimals = 9,
-        mint::authority = mint_authority_pda,
-        mint::freeze_authority = mint_authority_pda,
-        mint::token_program = token_program,
-        extensions::transfer_hook::authority = mint_authority_pda,
-        extensions::transfer_hook::program_id = crate::ID,
-    )]
-    pub mint: InterfaceAccount<'info, Mint>,
-
-    #[account(
-        seeds = [b"mint_authority", authority.key().as_ref()],
-        bump
-    )]
-    /// CHECK: PDA used as mint authority
-    pub mint_authority_pda: UncheckedAccount<'info>,
-
-    /// CHECK: Created via CPI to associated token program
-    #[account(mut)]
-    pub ata: UncheckedAccount<'info>,
-
-    /// CHECK: Validated by token metadata program
-    #[account(
-        mut,
-        seeds = [
-            b"metadata",
-            MPL_TOKEN_METADATA_ID.as_ref(),
-            mint.key().as_ref()
-        ],
-        bump,
-        seeds::program =MPL_TOKEN_METADATA_ID 
-    )]
-    pub metadata: UncheckedAccount<'info>,
-
-    #[account(mut)]
-    pub authority: Signer<'info>,
-
-    pub system_program: Program<'info, System>,
-    pub token_program: Interface<'info, TokenInterface>,
-    #[account(address = anchor_spl::associated_token::ID)]
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub rent: Sysvar<'info, Rent>,
-
-    /// CHECK: Token Metadata Program
-    #[account(address = MPL_TOKEN_METADATA_ID)]
-    pub token_metadata_program: UncheckedAccount<'info>,
-}
+    pub fn set_token_account_whitelisting(
+        ctx: Context<SetTokenAccountWhitelistingCTX>,
+        enabled: bool,
+    ) -> Result<()> {
+        instructions::handle_set_token_account_whitelisting(ctx, enabled)
+    }
 
-#[derive(Accounts)]
-#[instruction(token_count: u64)]
-pub struct AddToWhitelistCTX<'info> {
-    #[account(
-        mut,
-        seeds = [b"token", authority.key().as_ref(), &token_count.to_le_bytes()],
-        bump,
-        has_one = authority
-    )]
-    pub token_data: Account<'info, TokenData>,
-
-    #[account(
-        mut,
-        address = token_data.whitelist,
-        realloc = 8 + 4 + ((whitelist.addresses.len() + 10) * 32),
-        realloc::payer = authority,
-        realloc::zero = false,
-    )]
-    pub whitelist: Account<'info, Whitelist>,
-
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
-}
+    /// Freezes one page of token accounts, passed as remaining accounts.
+    pub fn freeze_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FreezeBatchCTX<'info>>,
+    ) -> Result<()> {
+        instructions::handle_freeze_batch(ctx)
+    }
 
-#[derive(Accounts)]
-#[instruction(token_count: u64)]
-pub struct RemoveFromWhitelistCTX<'info> {
-    #[account(
-        mut,
-        seeds = [b"token", authority.key().as_ref(), &token_count.to_le_bytes()],
-        bump,
-        has_one = authority
-    )]
-    pub token_data: Account<'info, TokenData>,
-
-    #[account(
-        mut,
-        address = token_data.whitelist,
-    )]
-    pub whitelist: Account<'info, Whitelist>,
-
-    #[account(mut)]
-    pub authority: Signer<'info>,
-}
+    /// Thaws one page of token accounts, passed as remaining accounts.
+    pub fn thaw_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FreezeBatchCTX<'info>>,
+    ) -> Result<()> {
+        instructions::handle_thaw_batch(ctx)
+    }
 
-#[derive(Accounts)]
-#[instruction(token_count: u64)]
-pub struct GetWhitelistCTX<'info> {
-    /// CHECK: Authority check done via seeds
-    pub authority: UncheckedAccount<'info>,
+    /// Thaws a token account whose owner is whitelisted. Anyone may call it.
+    pub fn thaw_account(ctx: Context<WhitelistGateCTX>) -> Result<()> {
+        instructions::handle_thaw_account(ctx)
+    }
 
-    #[account(
-        seeds = [b"token", authority.key().as_ref(), &token_count.to_le_bytes()],
-        bump
-    )]
-    pub token_data: Account<'info, TokenData>,
+    /// Refreezes a token account whose owner has left the whitelist. Anyone
+    /// may call it, though not for accounts of the token authority or of a
+    /// program address such as the treasury's.
+    pub fn refreeze_account(ctx: Context<WhitelistGateCTX>) -> Result<()> {
+        instructions::handle_refreeze_account(ctx)
+    }
 
-    #[account(address = token_data.whitelist)]
-    pub whitelist: Account<'info, Whitelist>,
-}
+    pub fn set_metadata_delay(ctx: Context<SetMetadataDelayCTX>, delay_seconds: i64) -> Result<()> {
+        instructions::handle_set_metadata_delay(ctx, delay_seconds)
+    }
 
-#[derive(Accounts)]
-#[instruction(token_count: u64)]
-pub struct MintTokensCTX<'info> {
-    #[account(
-        mut,
-        seeds = [b"token", authority.key().as_ref(), &token_count.to_le_bytes()],
-        bump,
-        has_one = authority
-    )]
-    pub token_data: Account<'info, TokenData>,
-
-    #[account(
-        mut,
-        constraint = mint.key() == token_data.mint
-    )]
-    pub mint: InterfaceAccount<'info, Mint>,
-
-    #[account(
-        mut,
-        constraint = to.mint == token_data.mint
-    )]
-    pub to: InterfaceAccount<'info, TokenAccount>,
-
-    #[account(
-        seeds = [b"mint_authority", authority.key().as_ref()],
-        bump
-    )]
-    /// CHECK: PDA used as mint authority
-    pub mint_authority_pda: UncheckedAccount<'info>,
-
-    pub authority: Signer<'info>,
-    pub token_program: Interface<'info, TokenInterface>,
-}
+    pub fn set_debug_telemetry(ctx: Context<SetDebugTelemetryCTX>, enabled: bool) -> Result<()> {
+        instructions::handle_set_debug_telemetry(ctx, enabled)
+    }
 
-#[derive(Accounts)]
-#[instruction(token_count: u64)]
-pub struct BurnTokensCTX<'info> {
-    #[account(
-        mut,
-        seeds = [b"token", authority.key().as_ref(), &token_count.to_le_bytes()],
-        bump,
-        has_one = authority
-    )]
-    pub token_data: Account<'info, TokenData>,
-
-    #[account(
-        mut,
-        constraint = mint.key() == token_data.mint
-    )]
-    pub mint: InterfaceAccount<'info, Mint>,
-
-    #[account(
-        mut,
-        constraint = from.mint == token_data.mint
-    )]
-    pub from: InterfaceAccount<'info, TokenAccount>,
-
-    pub authority: Signer<'info>,
-    pub token_program: Interface<'info, TokenInterface>,
-}
+    pub fn set_min_transfer_amount(
+        ctx: Context<SetMinTransferAmountCTX>,
+        min_amount: u64,
+    ) -> Result<()> {
+        instructions::handle_set_min_transfer_amount(ctx, min_amount)
+    }
 
-#[derive(Accounts)]
-#[instruction(token_count: u64)]
-pub struct PauseMintingCTX<'info> {
-    #[account(
-        mut,
-        seeds = [b"token", authority.key().as_ref(), &token_count.to_le_bytes()],
-        bump,
-        has_one = authority
-    )]
-    pub token_data: Account<'info, TokenData>,
-    pub authority: Signer<'info>,
-}
+    pub fn set_whitelist_policy(
+        ctx: Context<SetWhitelistPolicyCTX>,
+        policy: WhitelistPolicy,
+    ) -> Result<()> {
+        instructions::handle_set_whitelist_policy(ctx, policy)
+    }
 
-#[derive(Accounts)]
-#[instruction(token_count: u64)]
-pub struct PauseTokenCTX<'info> {
-    #[account(
-        mut,
-        seeds = [b"token", authority.key().as_ref(), &token_count.to_le_bytes()],
-        bump,
-        has_one = authority
-    )]
-    pub token_data: Account<'info, TokenData>,
-    pub authority: Signer<'info>,
-}
+    /// Picks whether the hook checks the token's own lists, its factory's
+    /// shared list, or accepts either.
+    pub fn set_whitelist_source(
+        ctx: Context<SetWhitelistSourceCTX>,
+        source: WhitelistSource,
+    ) -> Result<()> {
+        instructions::handle_set_whitelist_source(ctx, source)
+    }
 
-#[derive(Accounts)]
-#[instruction(token_count: u64)]
-pub struct TransferAuthorityCTX<'info> {
-    #[account(
-        mut,
-        seeds = [b"token", authority.key().as_ref(), &token_count.to_le_bytes()],
-        bump,
-        has_one = authority
-    )]
-    pub token_data: Account<'info, TokenData>,
-    pub authority: Signer<'info>,
-}
+    /// Lets holders of NFTs from `collection` count as whitelisted once they
+    /// `register_nft_pass`; `None` turns NFT gating off.
+    pub fn set_nft_gate_collection(
+        ctx: Context<SetNftGateCollectionCTX>,
+        collection: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::handle_set_nft_gate_collection(ctx, collection)
+    }
 
-// ============ TRANSFER HOOK ACCOUNTS ============
-
-#[derive(Accounts)]
-#[instruction(token_count: u64)]
-pub struct InitializeExtraAccountMetaList<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-
-    /// CHECK: ExtraAccountMetaList Account
-    #[account(
-        init,
-        seeds = [b"extra-account-metas", mint.key().as_ref()],
-        bump,
-        space = ExtraAccountMetaList::size_of(
-            InitializeExtraAccountMetaList::extra_account_metas(&authority.key(), token_count)?.len()
-        ).map_err(|_| error!(ErrorCode::InvalidAmount))?,
-        payer = payer
-    )]
-    pub extra_account_meta_list: AccountInfo<'info>,
-
-    pub mint: InterfaceAccount<'info, Mint>,
-
-    /// CHECK: Authority for deriving whitelist PDA
-    pub authority: UncheckedAccount<'info>,
-
-    #[account(
-        seeds = [b"whitelist", authority.key().as_ref(), &token_count.to_le_bytes()],
-        bump
-    )]
-    pub whitelist: Account<'info, Whitelist>,
-
-    pub system_program: Program<'info, System>,
-}
+    /// Burns are blocked while the token is paused unless this is set.
+    pub fn set_burn_allowed_while_paused(
+        ctx: Context<SetBurnAllowedWhilePausedCTX>,
+        allowed: bool,
+    ) -> Result<()> {
+        instructions::handle_set_burn_allowed_while_paused(ctx, allowed)
+    }
 
-impl<'info> InitializeExtraAccountMetaList<'info> {
-    pub fn extra_account_metas(
-        _authority: &Pubkey,
-        _token_count: u64,
-    ) -> Result<Vec<ExtraAccountMeta>> {
-        // Create the ExtraAccountMeta and handle the Result
-        let meta = ExtraAccountMeta::new_with_seeds(
-            &[
-                Seed::Literal {
-                    bytes: b"whitelist".to_vec(),
-                },
-                Seed::AccountKey { index: 0 }, // authority
-                Seed::AccountData {
-                    account_index: 0,
-                    data_index: 0,
-                    length: 8,
-                },
-            ],
-            false, // is_signer
-            true,  // is_writable
-        )
-        .map_err(|_| error!(ErrorCode::InvalidAmount))?;
-
-        Ok(vec![meta])
+    /// Caps what any wallet but the authority and `treasury` can hold,
+    /// in base units; zero lifts the cap.
+    pub fn set_max_wallet_amount(
+        ctx: Context<SetMaxWalletAmountCTX>,
+        max_amount: u64,
+        treasury: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::handle_set_max_wallet_amount(ctx, max_amount, treasury)
     }
-}
 
-#[derive(Accounts)]
-pub struct TransferHook<'info> {
-    #[account(token::mint = mint, token::authority = owner)]
-    pub source_token: InterfaceAccount<'info, TokenAccount>,
+    /// Caps what each owner but the authority can send per 24h, in base
+    /// units; zero lifts the limit. Senders need an open transfer window.
+    pub fn set_daily_transfer_limit(
+        ctx: Context<SetDailyTransferLimitCTX>,
+        limit: u64,
+    ) -> Result<()> {
+        instructions::handle_set_daily_transfer_limit(ctx, limit)
+    }
 
-    pub mint: InterfaceAccount<'info, Mint>,
+    /// Only the authority can transfer before `starts_at`; zero opens
+    /// trading immediately.
+    pub fn set_trading_starts_at(
+        ctx: Context<SetTradingStartsAtCTX>,
+        starts_at: i64,
+    ) -> Result<()> {
+        instructions::handle_set_trading_starts_at(ctx, starts_at)
+    }
 
-    #[account(token::mint = mint)]
-    pub destination_token: InterfaceAccount<'info, TokenAccount>,
+    /// Pauses the token automatically once more than `max_transfers`
+    /// transfers or `max_volume` base units move within `window_slots`
+    /// slots. Zero `window_slots` turns the breaker off.
+    pub fn set_circuit_breaker(
+        ctx: Context<SetCircuitBreakerCTX>,
+        window_slots: u64,
+        max_transfers: u32,
+        max_volume: u64,
+    ) -> Result<()> {
+        instructions::handle_set_circuit_breaker(ctx, window_slots, max_transfers, max_volume)
+    }
 
-    /// CHECK: source token account owner, can be SystemAccount or PDA
-    pub owner: UncheckedAccount<'info>,
+    pub fn open_transfer_window(ctx: Context<OpenTransferWindowCTX>, owner: Pubkey) -> Result<()> {
+        instructions::handle_open_transfer_window(ctx, owner)
+    }
 
-    /// CHECK: ExtraAccountMetaList Account
-    #[account(seeds = [b"extra-account-metas", mint.key().as_ref()], bump)]
-    pub extra_account_meta_list: UncheckedAccount<'info>,
+    /// Permissionless: closes an expired window, refunding whoever opened it.
+    pub fn close_transfer_window(
+        ctx: Context<CloseTransferWindowCTX>,
+        owner: Pubkey,
+    ) -> Result<()> {
+        instructions::handle_close_transfer_window(ctx, owner)
+    }
 
-    // This is passed via extra account metas
-    pub whitelist: Account<'info, Whitelist>,
-}
+    pub fn queue_metadata_update(
+        ctx: Context<QueueMetadataUpdateCTX>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        instructions::handle_queue_metadata_update(ctx, name, symbol, uri)
+    }
 
-// ============ DATA STRUCTS ============
+    pub fn apply_metadata_update(ctx: Context<ApplyMetadataUpdateCTX>) -> Result<()> {
+        instructions::handle_apply_metadata_update(ctx)
+    }
 
-#[account]
-pub struct TokenFactory {
-    pub authority: Pubkey,
-    pub token_count: u64,
-}
+    /// Applies a metadata change immediately; only for tokens without an
+    /// update delay, others go through `queue_metadata_update`.
+    pub fn update_metadata(
+        ctx: Context<UpdateMetadataCTX>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        instructions::handle_update_metadata(ctx, name, symbol, uri)
+    }
 
-#[account]
-pub struct TokenData {
-    pub mint: Pubkey,
-    pub authority: Pubkey,
-    pub total_supply: u64,
-    pub decimals: u8,
-    pub is_paused: bool,
-    pub is_minting_paused: bool,
-    pub name: String,
-    pub symbol: String,
-    pub uri: String,
-    pub whitelist: Pubkey,
-}
+    pub fn cancel_metadata_update(ctx: Context<CancelMetadataUpdateCTX>) -> Result<()> {
+        instructions::handle_cancel_metadata_update(ctx)
+    }
 
-#[account]
-pub struct Whitelist {
-    pub addresses: Vec<Pubkey>,
-}
+    pub fn set_token_profile(
+        ctx: Context<SetTokenProfileCTX>,
+        description_hash: Option<[u8; 32]>,
+        website: String,
+        socials_hash: Option<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::handle_set_token_profile(ctx, description_hash, website, socials_hash)
+    }
 
-// ============ HELPER FUNCTIONS ============
+    /// Stores a display name and URI for `locale` alongside the Metaplex
+    /// metadata, which stays the canonical copy.
+    pub fn set_localized_metadata(
+        ctx: Context<SetLocalizedMetadataCTX>,
+        locale: String,
+        name: String,
+        uri: String,
+    ) -> Result<()> {
+        instructions::handle_set_localized_metadata(ctx, locale, name, uri)
+    }
 
-fn check_is_transferring(ctx: &Context<TransferHook>) -> Result<()> {
-    let source_token_info = ctx.accounts.source_token.to_account_info();
-    let mut account_data_ref: std::cell::RefMut<&mut [u8]> =
-        source_token_info.try_borrow_mut_data()?;
-    let mut account = PodStateWithExtensionsMut::<PodAccount>::unpack(*account_data_ref)?;
-    let account_extension = account.get_extension_mut::<TransferHookAccount>()?;
+    pub fn get_localized_metadata(
+        ctx: Context<GetLocalizedMetadataCTX>,
+        locale: String,
+    ) -> Result<LocalizedMetadata> {
+        instructions::handle_get_localized_metadata(ctx, locale)
+    }
 
-    if !bool::from(account_extension.transferring) {
-        return err!(ErrorCode::IsNotCurrentlyTransferring);
+    /// Returns a versioned, hashed encoding of the compliance rules of the
+    /// factory's `token_count`th token, for mirroring them elsewhere.
+    pub fn export_rules_digest(
+        ctx: Context<ExportRulesDigestCTX>,
+        token_count: u64,
+    ) -> Result<RulesDigest> {
+        instructions::handle_export_rules_digest(ctx, token_count)
     }
 
-    Ok(())
-}
\ No newline at end of file
+    /// Puts unpausing, authority proposals and supply-cap changes behind a
+    /// delay of at least `min_delay` seconds; pausing stays immediate. The
+    /// delay can only grow.
+    pub fn set_timelock(ctx: Context<SetTimelockCTX>, min_delay: i64) -> Result<()> {
+        instructions::handle_set_timelock(ctx, min_delay)
+    }
+
+    pub fn queue_action(ctx: Context<QueueActionCTX>, action: AdminAction) -> Result<()> {
+        instructions::handle_queue_action(ctx, action)
+    }
+
+    pub fn execute_action(ctx: Context<ExecuteActionCTX>, action_id: u64) -> Result<()> {
+        instructions::handle_execute_action(ctx, action_id)
+    }
+
+    pub fn cancel_action(ctx: Context<CancelActionCTX>, action_id: u64) -> Result<()> {
+        instructions::handle_cancel_action(ctx, action_id)
+    }
+
+    /// Makes `custodian` the SPL delegate of the owner's token account for
+    /// up to `cap` base units until `expires_at`, which the hook enforces
+    /// on every transfer the custodian signs.
+    pub fn approve_custodian(
+        ctx: Context<ApproveCustodianCTX>,
+        custodian: Pubkey,
+        cap: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::handle_approve_custodian(ctx, custodian, cap, expires_at)
+    }
+
+    pub fn revoke_custodian(ctx: Context<RevokeCustodianCTX>, custodian: Pubkey) -> Result<()> {
+        instructions::handle_revoke_custodian(ctx, custodian)
+    }
+
+    /// Single entrypoint for governance and multisig integrations; mirrors
+    /// the granular admin instructions.
+    pub fn admin_execute(ctx: Context<AdminExecuteCTX>, action: AdminAction) -> Result<()> {
+        instructions::handle_admin_execute(ctx, action)
+    }
+
+    pub fn set_swap_rate(
+        ctx: Context<SetSwapRateCTX>,
+        rate_numerator: u64,
+        rate_denominator: u64,
+    ) -> Result<()> {
+        instructions::handle_set_swap_rate(ctx, rate_numerator, rate_denominator)
+    }
+
+    /// Burns `amount` of one token from the user and mints the target
+    /// token to their own account, which must be able to receive it.
+    pub fn swap_tokens(ctx: Context<SwapTokensCTX>, amount: u64) -> Result<()> {
+        instructions::handle_swap_tokens(ctx, amount)
+    }
+
+    /// Returns an approximate compute unit budget for an instruction,
+    /// where `item_count` is the number of addresses or recipients handled.
+    pub fn estimate_budget(
+        ctx: Context<EstimateBudgetCTX>,
+        instruction_kind: InstructionKind,
+        item_count: u32,
+    ) -> Result<u32> {
+        instructions::handle_estimate_budget(ctx, instruction_kind, item_count)
+    }
+
+    // ============ TRANSFER HOOK IMPLEMENTATION ============
+
+    #[instruction(discriminator = ExecuteInstruction::SPL_DISCRIMINATOR_SLICE)]
+    pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
+        hook::handle_transfer_hook(ctx, amount)
+    }
+
+    #[instruction(discriminator = InitializeExtraAccountMetaListInstruction::SPL_DISCRIMINATOR_SLICE)]
+    pub fn initialize_extra_account_meta_list(
+        ctx: Context<InitializeExtraAccountMetaList>,
+    ) -> Result<()> {
+        hook::handle_initialize_extra_account_meta_list(ctx)
+    }
+
+    /// Brings token data created under an older layout up to date; the
+    /// signer must be its current authority. Run
+    /// `update_extra_account_meta_list` for the mint afterwards.
+    pub fn migrate_token_data(
+        ctx: Context<MigrateTokenDataCTX>,
+        creator: Pubkey,
+        token_index: u64,
+    ) -> Result<()> {
+        instructions::handle_migrate_token_data(ctx, creator, token_index)
+    }
+
+    /// Grows a factory created before collections to the current layout.
+    pub fn migrate_factory(ctx: Context<MigrateFactoryCTX>) -> Result<()> {
+        instructions::handle_migrate_factory(ctx)
+    }
+
+    /// Rewrites the mint's extra account metas to the current layout,
+    /// resizing the account as needed; run after any upgrade that bumps
+    /// `EXTRA_ACCOUNT_METAS_VERSION`.
+    #[instruction(discriminator = UpdateExtraAccountMetaListInstruction::SPL_DISCRIMINATOR_SLICE)]
+    pub fn update_extra_account_meta_list(ctx: Context<UpdateExtraAccountMetaList>) -> Result<()> {
+        hook::handle_update_extra_account_meta_list(ctx)
+    }
+
+    /// Creates or refreshes the mint's `HookManifest`. Config changes keep
+    /// it current when it's passed to them.
+    pub fn sync_hook_manifest(ctx: Context<SyncHookManifestCTX>) -> Result<()> {
+        instructions::handle_sync_hook_manifest(ctx)
+    }
+}
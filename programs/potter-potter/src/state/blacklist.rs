@@ -0,0 +1,11 @@
+use anchor_lang::prelude::*;
+
+/// Marker account: its existence at `[b"blacklist", mint, address]` bars
+/// the address from sending or receiving the token, whatever the
+/// whitelist says.
+#[account]
+pub struct BlacklistEntry {}
+
+impl BlacklistEntry {
+    pub const SPACE: usize = 8;
+}
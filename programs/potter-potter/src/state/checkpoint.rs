@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+/// Head of the checkpoint chain, a singleton at `[b"checkpoint_log"]`.
+#[account]
+pub struct CheckpointLog {
+    /// Sequence number the next checkpoint will take
+    pub next_sequence: u64,
+    /// `state_hash` of the latest checkpoint, zero before the first
+    pub head: [u8; 32],
+}
+
+impl CheckpointLog {
+    pub const SPACE: usize = 8 + 8 + 32;
+}
+
+/// Commitment to program state at `slot`, at `[b"checkpoint", sequence]`.
+/// `state_hash` chains `previous`, so one verified checkpoint vouches for
+/// every earlier one.
+#[account]
+pub struct StateCheckpoint {
+    pub sequence: u64,
+    pub slot: u64,
+    /// Token data accounts folded into the hash, in the order given
+    pub token_count: u32,
+    pub previous: [u8; 32],
+    pub state_hash: [u8; 32],
+}
+
+impl StateCheckpoint {
+    pub const SPACE: usize = 8 + 8 + 8 + 4 + 32 + 32;
+}
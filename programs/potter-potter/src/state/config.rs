@@ -0,0 +1,100 @@
+use crate::errors::ErrorCode;
+use crate::state::Limits;
+use anchor_lang::prelude::*;
+
+#[account]
+pub struct ProgramConfig {
+    pub authority: Pubkey,
+    /// Rejects deprecated v1 entrypoints once clients have migrated
+    pub v1_disabled: bool,
+    /// Enables test-only instructions; fixed at init and never set on mainnet
+    pub is_devnet: bool,
+    /// Bitmask of subsystems halted program-wide (`FEATURE_*`)
+    pub disabled_features: u16,
+    /// Set by `program_pause` until every `ResumePhase` is re-enabled; the
+    /// config authority can't touch `disabled_features` meanwhile
+    pub program_paused: bool,
+    pub limits: Limits,
+}
+
+// Subsystems the config authority can switch off for every token at once
+pub const FEATURE_TOKEN_CREATION: u16 = 1 << 0;
+pub const FEATURE_MINTING: u16 = 1 << 1;
+pub const FEATURE_SWAPS: u16 = 1 << 2;
+pub const FEATURE_ADMIN: u16 = 1 << 3;
+pub const FEATURE_TRANSFERS: u16 = 1 << 4;
+pub const FEATURE_ALL: u16 =
+    FEATURE_TOKEN_CREATION | FEATURE_MINTING | FEATURE_SWAPS | FEATURE_ADMIN | FEATURE_TRANSFERS;
+
+/// Stages of `program_resume`, which have to be re-enabled in this order
+/// after a `program_pause`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResumePhase {
+    AdminOps,
+    Transfers,
+    Minting,
+    Sales,
+}
+
+impl ResumePhase {
+    pub const ORDER: [ResumePhase; 4] = [
+        ResumePhase::AdminOps,
+        ResumePhase::Transfers,
+        ResumePhase::Minting,
+        ResumePhase::Sales,
+    ];
+
+    pub fn features(self) -> u16 {
+        match self {
+            ResumePhase::AdminOps => FEATURE_ADMIN,
+            ResumePhase::Transfers => FEATURE_TRANSFERS,
+            // Creating a token mints its initial supply
+            ResumePhase::Minting => FEATURE_MINTING | FEATURE_TOKEN_CREATION,
+            ResumePhase::Sales => FEATURE_SWAPS,
+        }
+    }
+
+    /// Features of every phase that has to be resumed before this one.
+    pub fn prerequisites(self) -> u16 {
+        Self::ORDER
+            .iter()
+            .take_while(|phase| **phase != self)
+            .fold(0, |mask, phase| mask | phase.features())
+    }
+}
+
+impl ProgramConfig {
+    pub const SPACE: usize = 8 + 32 + 1 + 1 + 2 + 1 + Limits::SIZE;
+
+    pub fn require_v1_enabled(&self) -> Result<()> {
+        require!(!self.v1_disabled, ErrorCode::InstructionDeprecated);
+        Ok(())
+    }
+
+    pub fn require_devnet(&self) -> Result<()> {
+        require!(self.is_devnet, ErrorCode::DevnetOnly);
+        Ok(())
+    }
+
+    /// Whether none of `features` is switched off; contexts without a
+    /// handler-side check use it as an account constraint.
+    pub fn is_enabled(&self, features: u16) -> bool {
+        self.disabled_features & features == 0
+    }
+
+    pub fn require_feature_enabled(&self, feature: u16) -> Result<()> {
+        require!(self.is_enabled(feature), ErrorCode::FeatureDisabled);
+        Ok(())
+    }
+}
+
+/// Stand-in for the clock sysvar's `unix_timestamp` in `test-harness`
+/// builds, at `[b"mock_clock"]`.
+#[account]
+pub struct MockClock {
+    pub unix_timestamp: i64,
+}
+
+impl MockClock {
+    pub const SPACE: usize = 8 + 8;
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+/// Bounds on a custodian acting as SPL delegate for one token account, at
+/// `[b"custodian", token_account, custodian]`. The hook checks them on
+/// every transfer the custodian signs.
+#[account]
+pub struct CustodianApproval {
+    pub owner: Pubkey,
+    pub custodian: Pubkey,
+    /// Most the custodian may move in total, in base units
+    pub cap: u64,
+    pub spent: u64,
+    pub expires_at: i64,
+}
+
+impl CustodianApproval {
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 8 + 8;
+
+    /// Adds `amount` to what has been spent. Returns false, leaving the
+    /// approval as it was, if it has expired or the cap would be passed.
+    pub fn record(&mut self, amount: u64, now: i64) -> bool {
+        match self.spent.checked_add(amount) {
+            Some(spent) if now < self.expires_at && spent <= self.cap => {
+                self.spent = spent;
+                true
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use anchor_lang::prelude::*;
+
+/// Marker account: its existence at `[b"opt_out", mint, owner]` means the
+/// owner doesn't want to receive distributions of that token.
+#[account]
+pub struct DistributionOptOut {}
+
+impl DistributionOptOut {
+    pub const SPACE: usize = 8;
+}
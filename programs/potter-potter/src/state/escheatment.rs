@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+
+/// Per-token rules for recovering dormant balances into custody.
+#[account]
+pub struct EscheatmentPolicy {
+    /// Token account that receives recovered balances
+    pub custody: Pubkey,
+    /// Seconds without outbound activity before a notice may be filed
+    pub dormancy_period: i64,
+    /// Seconds a notice stays public before the balance can be recovered
+    pub waiting_period: i64,
+}
+
+impl EscheatmentPolicy {
+    pub const SPACE: usize = 8 + 32 + 8 + 8;
+}
+
+/// When a token account last showed its holder was around. The hook moves
+/// it forward on every outbound transfer; opening it counts as activity,
+/// so an account can't be dormant for longer than it has been tracked.
+#[account]
+pub struct ActivityRecord {
+    pub mint: Pubkey,
+    pub last_activity_at: i64,
+}
+
+impl ActivityRecord {
+    pub const SPACE: usize = 8 + 32 + 8;
+
+    pub fn is_dormant(&self, dormancy_period: i64, now: i64) -> bool {
+        now.saturating_sub(self.last_activity_at) >= dormancy_period
+    }
+}
+
+/// Public notice that a token account is about to be recovered. Any
+/// outbound transfer while it is pending marks it contested.
+#[account]
+pub struct DormancyNotice {
+    pub mint: Pubkey,
+    pub recoverable_at: i64,
+    pub contested: bool,
+    /// Balance when the notice was filed; recovery never takes more
+    pub raw_amount: u64,
+}
+
+impl DormancyNotice {
+    pub const SPACE: usize = 8 + 32 + 8 + 1 + 8;
+
+    /// What recovery may take from an account now holding `balance`.
+    pub fn recoverable_amount(&self, balance: u64) -> u64 {
+        balance.min(self.raw_amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 24 * 60 * 60;
+
+    #[test]
+    fn dormant_only_after_the_full_period() {
+        let record = ActivityRecord {
+            mint: Pubkey::new_unique(),
+            last_activity_at: 1_000,
+        };
+        assert!(!record.is_dormant(DAY, 1_000 + DAY - 1));
+        assert!(record.is_dormant(DAY, 1_000 + DAY));
+    }
+
+    #[test]
+    fn recovery_is_capped_at_the_noticed_balance() {
+        let notice = DormancyNotice {
+            mint: Pubkey::new_unique(),
+            recoverable_at: 0,
+            contested: false,
+            raw_amount: 100,
+        };
+        assert_eq!(notice.recoverable_amount(250), 100);
+        assert_eq!(notice.recoverable_amount(40), 40);
+    }
+}
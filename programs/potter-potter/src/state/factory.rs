@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+
+#[account]
+pub struct TokenFactory {
+    pub authority: Pubkey,
+    pub token_count: u64,
+    /// Metaplex collection NFT that groups this factory's tokens
+    pub collection_mint: Option<Pubkey>,
+}
+
+impl TokenFactory {
+    pub const SPACE: usize = 8 + 32 + 8 + (1 + 32);
+}
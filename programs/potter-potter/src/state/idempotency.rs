@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+pub const IDEMPOTENCY_LOG_SIZE: usize = 32;
+
+/// Ring buffer of recently used idempotency keys for a token's
+/// privileged instructions.
+#[account]
+pub struct IdempotencyLog {
+    pub keys: [[u8; 16]; IDEMPOTENCY_LOG_SIZE],
+    pub next: u8,
+}
+
+impl IdempotencyLog {
+    /// Records `key`, returning false if it was already in the log.
+    pub fn record(&mut self, key: [u8; 16]) -> bool {
+        if self.keys.contains(&key) {
+            return false;
+        }
+        self.keys[self.next as usize] = key;
+        self.next = ((self.next as usize + 1) % IDEMPOTENCY_LOG_SIZE) as u8;
+        true
+    }
+}
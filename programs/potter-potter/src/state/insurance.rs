@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+/// A mint's insurance fund at `[b"insurance", mint]`. It owns the vault at
+/// `[b"insurance_vault", mint]`, which takes `fee_share_bps` of every
+/// withheld-fee withdrawal.
+#[account]
+pub struct InsuranceFund {
+    pub mint: Pubkey,
+    /// Can veto a queued payout before it becomes executable
+    pub guardian: Pubkey,
+    pub fee_share_bps: u16,
+    /// Seconds a queued payout waits for a veto
+    pub veto_period: i64,
+    pub next_claim_id: u64,
+}
+
+impl InsuranceFund {
+    pub const SPACE: usize = 8 + 32 + 32 + 2 + 8 + 8;
+}
+
+/// A queued payout from the insurance vault, at
+/// `[b"payout_claim", mint, claim_id]`.
+#[account]
+pub struct PayoutClaim {
+    pub claim_id: u64,
+    /// Token account the payout goes to
+    pub recipient: Pubkey,
+    /// Base units, as fees accrue in them
+    pub raw_amount: u64,
+    /// Hash of the off-chain incident evidence
+    pub evidence_hash: [u8; 32],
+    pub ready_at: i64,
+}
+
+impl PayoutClaim {
+    pub const SPACE: usize = 8 + 8 + 32 + 8 + 32 + 8;
+}
@@ -0,0 +1,60 @@
+use crate::errors::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::spl_token_2022::extension::transfer_fee::MAX_FEE_BASIS_POINTS;
+
+pub const MAX_DECIMALS: u8 = 12;
+
+/// Per-deployment caps on numeric parameters, kept in `ProgramConfig`.
+/// They can be tightened or relaxed, but never past the hard ceilings.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Limits {
+    /// Highest transfer fee a token can be created with or set to
+    pub max_fee_basis_points: u16,
+    pub max_decimals: u8,
+    /// Most recipients or accounts a batch instruction takes per call
+    pub max_batch_size: u16,
+    /// Most addresses a single whitelist page can hold
+    pub max_whitelist_page_size: u16,
+    /// Longest vesting schedule, in seconds
+    pub max_vesting_duration: i64,
+}
+
+impl Limits {
+    pub const SIZE: usize = 2 + 1 + 2 + 2 + 8;
+
+    pub const DEFAULT: Limits = Limits {
+        max_fee_basis_points: 1_000,
+        max_decimals: MAX_DECIMALS,
+        max_batch_size: 64,
+        max_whitelist_page_size: 1_000,
+        max_vesting_duration: 10 * 365 * 24 * 60 * 60,
+    };
+
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.max_fee_basis_points <= MAX_FEE_BASIS_POINTS
+                && self.max_decimals <= MAX_DECIMALS
+                && self.max_batch_size > 0
+                && self.max_whitelist_page_size > 0
+                && self.max_vesting_duration > 0,
+            ErrorCode::InvalidLimits
+        );
+        Ok(())
+    }
+
+    pub fn require_batch_size(&self, len: usize) -> Result<()> {
+        require!(
+            len <= self.max_batch_size as usize,
+            ErrorCode::LimitExceeded
+        );
+        Ok(())
+    }
+
+    pub fn require_page_size(&self, len: usize) -> Result<()> {
+        require!(
+            len <= self.max_whitelist_page_size as usize,
+            ErrorCode::LimitExceeded
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+use crate::state::{TokenData, WhitelistPolicy, WhitelistSource};
+use anchor_lang::prelude::*;
+use solana_sha256_hasher::hashv;
+
+/// Everything an integrator needs to route transfers through the hook, in
+/// one account at `[b"hook_manifest", mint]`.
+#[account]
+pub struct HookManifest {
+    pub mint: Pubkey,
+    pub hook_program: Pubkey,
+    pub extra_account_meta_list: Pubkey,
+    /// Canonical bump of `extra_account_meta_list`
+    pub extra_account_meta_list_bump: u8,
+    /// `EXTRA_ACCOUNT_METAS_VERSION` the meta list was built with
+    pub layout_version: u8,
+    /// Commits to the config the hook enforces; changes whenever it does
+    pub rule_set_hash: [u8; 32],
+    /// Mint whose whitelist pages the hook reads
+    pub whitelist_mint: Pubkey,
+    pub whitelist_policy: WhitelistPolicy,
+    /// Mirrors `TokenData::config_lock`
+    pub config_lock: u16,
+    pub updated_slot: u64,
+}
+
+impl HookManifest {
+    pub const SPACE: usize = 8 + 32 + 32 + 32 + 1 + 1 + 32 + 32 + 1 + 2 + 8;
+
+    /// Copies the hook-facing parts of `token_data` into the manifest.
+    pub fn refresh(&mut self, token_data: &TokenData) -> Result<()> {
+        self.rule_set_hash = rule_set_hash(token_data);
+        self.whitelist_mint = token_data.whitelist_mint;
+        self.whitelist_policy = token_data.whitelist_policy;
+        self.config_lock = token_data.config_lock;
+        self.updated_slot = Clock::get()?.slot;
+        Ok(())
+    }
+}
+
+fn rule_set_hash(token_data: &TokenData) -> [u8; 32] {
+    let flags = [
+        token_data.is_paused as u8,
+        token_data.whitelist_policy as u8,
+        token_data.whitelist_source as u8,
+        token_data.token_account_whitelisting as u8,
+        token_data.whitelist_root.is_some() as u8,
+        token_data.wallet_cap_treasury.is_some() as u8,
+        token_data.nft_gate_collection.is_some() as u8,
+    ];
+    hashv(&[
+        &flags,
+        &token_data.min_transfer_amount.to_le_bytes(),
+        &token_data.max_wallet_amount.to_le_bytes(),
+        &token_data.daily_transfer_limit.to_le_bytes(),
+        &token_data.trading_starts_at.to_le_bytes(),
+        token_data
+            .wallet_cap_treasury
+            .as_ref()
+            .map_or(&[][..], |treasury| treasury.as_ref()),
+        token_data.whitelist_mint.as_ref(),
+        token_data.factory.as_ref(),
+        token_data
+            .nft_gate_collection
+            .as_ref()
+            .map_or(&[][..], |collection| collection.as_ref()),
+        token_data
+            .whitelist_root
+            .as_ref()
+            .map_or(&[][..], |root| &root[..]),
+    ])
+    .to_bytes()
+}
+
+/// Bumped whenever `ComplianceRules` changes shape, so mirrors can tell
+/// which layout a digest was built from.
+pub const RULES_DIGEST_VERSION: u8 = 3;
+
+/// The hook's compliance configuration for a mint, in the fixed field
+/// order its Borsh encoding commits to.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct ComplianceRules {
+    pub mint: Pubkey,
+    pub is_paused: bool,
+    pub whitelist_policy: WhitelistPolicy,
+    pub whitelist_mint: Pubkey,
+    pub whitelist_source: WhitelistSource,
+    pub factory: Pubkey,
+    pub nft_gate_collection: Option<Pubkey>,
+    pub token_account_whitelisting: bool,
+    pub whitelist_root: Option<[u8; 32]>,
+    pub min_transfer_amount: u64,
+    pub max_wallet_amount: u64,
+    pub wallet_cap_treasury: Option<Pubkey>,
+    pub daily_transfer_limit: u64,
+    pub trading_starts_at: i64,
+    pub config_lock: u16,
+}
+
+impl From<&TokenData> for ComplianceRules {
+    fn from(token_data: &TokenData) -> Self {
+        Self {
+            mint: token_data.mint,
+            is_paused: token_data.is_paused,
+            whitelist_policy: token_data.whitelist_policy,
+            whitelist_mint: token_data.whitelist_mint,
+            whitelist_source: token_data.whitelist_source,
+            factory: token_data.factory,
+            nft_gate_collection: token_data.nft_gate_collection,
+            token_account_whitelisting: token_data.token_account_whitelisting,
+            whitelist_root: token_data.whitelist_root,
+            min_transfer_amount: token_data.min_transfer_amount,
+            max_wallet_amount: token_data.max_wallet_amount,
+            wallet_cap_treasury: token_data.wallet_cap_treasury,
+            daily_transfer_limit: token_data.daily_transfer_limit,
+            trading_starts_at: token_data.trading_starts_at,
+            config_lock: token_data.config_lock,
+        }
+    }
+}
+
+/// `hash` is SHA-256 over the version byte followed by the Borsh-encoded
+/// `rules`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct RulesDigest {
+    pub version: u8,
+    pub rules: ComplianceRules,
+    pub hash: [u8; 32],
+}
+
+impl RulesDigest {
+    pub fn new(rules: ComplianceRules) -> Result<Self> {
+        let mut encoded = Vec::new();
+        rules.serialize(&mut encoded)?;
+        Ok(Self {
+            version: RULES_DIGEST_VERSION,
+            hash: hashv(&[&[RULES_DIGEST_VERSION], &encoded]).to_bytes(),
+            rules,
+        })
+    }
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+/// Name, symbol and URI change waiting out the token's metadata delay.
+#[account]
+pub struct PendingMetadataUpdate {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    /// Unix timestamp from which `apply_metadata_update` may run
+    pub ready_at: i64,
+}
+
+impl PendingMetadataUpdate {
+    pub const SPACE: usize = 8 + (4 + 32) + (4 + 10) + (4 + 200) + 8;
+}
+
+pub const MAX_WEBSITE_LEN: usize = 100;
+
+/// Hashes of key off-chain claims from the URI JSON, so they can be checked
+/// without fetching it.
+#[account]
+pub struct TokenProfile {
+    pub description_hash: Option<[u8; 32]>,
+    pub website: String,
+    pub socials_hash: Option<[u8; 32]>,
+}
+
+impl TokenProfile {
+    pub const SPACE: usize = 8 + (1 + 32) + (4 + MAX_WEBSITE_LEN) + (1 + 32);
+}
+
+pub const MAX_LOCALE_LEN: usize = 16;
+
+/// Alternate display name and URI for one locale, e.g. `pt-BR`.
+#[account]
+pub struct LocalizedMetadata {
+    pub locale: String,
+    pub name: String,
+    pub uri: String,
+}
+
+impl LocalizedMetadata {
+    pub const SPACE: usize = 8 + (4 + MAX_LOCALE_LEN) + (4 + 32) + (4 + 200);
+}
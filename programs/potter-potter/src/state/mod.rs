@@ -0,0 +1,47 @@
+mod blacklist;
+mod checkpoint;
+mod config;
+mod custodian;
+mod distribution;
+mod escheatment;
+mod factory;
+mod idempotency;
+mod insurance;
+mod limits;
+mod manifest;
+mod metadata;
+mod nft_pass;
+mod role;
+mod stats;
+mod swap;
+mod timelock;
+mod token;
+mod transfer_window;
+mod vault;
+mod velocity;
+mod vesting;
+mod whitelist;
+
+pub use blacklist::*;
+pub use checkpoint::*;
+pub use config::*;
+pub use custodian::*;
+pub use distribution::*;
+pub use escheatment::*;
+pub use factory::*;
+pub use idempotency::*;
+pub use insurance::*;
+pub use limits::*;
+pub use manifest::*;
+pub use metadata::*;
+pub use nft_pass::*;
+pub use role::*;
+pub use stats::*;
+pub use swap::*;
+pub use timelock::*;
+pub use token::*;
+pub use transfer_window::*;
+pub use vault::*;
+pub use velocity::*;
+pub use vesting::*;
+pub use whitelist::*;
@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+/// Proof, checked at registration, that `owner` of `[b"nft_pass", mint,
+/// owner]` holds an NFT from the token's gate collection. The hook only
+/// reads this account and the NFT's, so anyone can revoke it once the NFT
+/// moves.
+#[account]
+pub struct NftPass {
+    /// Gate collection the pass was verified against
+    pub collection: Pubkey,
+    pub nft_mint: Pubkey,
+    /// The owner's account at `[b"nft_pass_account", mint, owner]` that
+    /// the NFT was moved into, which the hook checks on every transfer
+    pub nft_token_account: Pubkey,
+}
+
+impl NftPass {
+    pub const SPACE: usize = 8 + 32 + 32 + 32;
+}
+
+/// Marks an NFT as backing a live pass for a mint, at `[b"nft_pass_nft",
+/// mint, nft_mint]`, so one NFT can't whitelist several owners in turn.
+/// Created and closed alongside the pass.
+#[account]
+pub struct NftPassClaim {}
+
+impl NftPassClaim {
+    pub const SPACE: usize = 8;
+}
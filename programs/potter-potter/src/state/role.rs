@@ -0,0 +1,39 @@
+use crate::errors::ErrorCode;
+use anchor_lang::prelude::*;
+
+/// Operational permissions the authority can hand out without sharing its key.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Role {
+    Minter,
+    Pauser,
+    WhitelistManager,
+    VaultManager,
+}
+
+/// Marker account: its existence at `[b"role", token_data, member, role]`
+/// is the grant.
+#[account]
+pub struct RoleGrant {}
+
+impl RoleGrant {
+    pub const SPACE: usize = 8;
+}
+
+/// Mint budget for a key that isn't the authority, in whole tokens. Each
+/// mint it signs draws the budget down; the authority tops it up.
+#[account]
+pub struct MinterAllowance {
+    pub remaining: u64,
+}
+
+impl MinterAllowance {
+    pub const SPACE: usize = 8 + 8;
+
+    pub fn consume(&mut self, amount: u64) -> Result<()> {
+        self.remaining = self
+            .remaining
+            .checked_sub(amount)
+            .ok_or(ErrorCode::MinterAllowanceExceeded)?;
+        Ok(())
+    }
+}
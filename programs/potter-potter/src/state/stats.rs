@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+/// Program-wide headline counters for dashboards and explorers.
+#[account]
+pub struct ProtocolStats {
+    pub factories: u64,
+    pub tokens: u64,
+    /// Whole tokens minted across all mints, including initial supplies
+    pub total_minted: u64,
+}
+
+impl ProtocolStats {
+    pub const SPACE: usize = 8 + 8 + 8 + 8;
+
+    // Counters saturate rather than fail, so they can never block a mint
+    pub fn record_mint(&mut self, amount: u64) {
+        self.total_minted = self.total_minted.saturating_add(amount);
+    }
+}
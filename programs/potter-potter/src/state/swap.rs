@@ -0,0 +1,9 @@
+use anchor_lang::prelude::*;
+
+#[account]
+pub struct SwapRate {
+    pub from_mint: Pubkey,
+    pub to_mint: Pubkey,
+    pub rate_numerator: u64,
+    pub rate_denominator: u64,
+}
@@ -0,0 +1,29 @@
+use crate::instructions::AdminAction;
+use anchor_lang::prelude::*;
+
+/// Opts a token into timelocked admin actions, at `[b"timelock", mint]`.
+/// Once it exists, the actions `AdminAction::is_timelocked` covers can
+/// only go through `queue_action` and `execute_action`.
+#[account]
+pub struct TimelockConfig {
+    /// Seconds a queued action waits before it can be executed
+    pub min_delay: i64,
+    pub next_action_id: u64,
+}
+
+impl TimelockConfig {
+    pub const SPACE: usize = 8 + 8 + 8;
+}
+
+/// At `[b"queued_action", mint, action_id]`.
+#[account]
+pub struct QueuedAction {
+    pub action_id: u64,
+    pub action: AdminAction,
+    pub ready_at: i64,
+}
+
+impl QueuedAction {
+    // Sized for the largest `AdminAction` variant, `SetWalletCap`
+    pub const SPACE: usize = 8 + 8 + (1 + 8 + 1 + 32) + 8;
+}
@@ -0,0 +1,336 @@
+use crate::errors::ErrorCode;
+use crate::instructions::CreateTokenParams;
+use anchor_lang::prelude::*;
+
+#[account]
+pub struct TokenData {
+    /// Factory token count at creation, used in this account's seeds
+    pub token_index: u64,
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub total_supply: u64,
+    pub decimals: u8,
+    pub is_paused: bool,
+    pub is_minting_paused: bool,
+    /// Bitmask of permanently locked config areas (`LOCK_*`)
+    pub config_lock: u16,
+    /// Lets the hook also accept individually whitelisted token accounts
+    pub token_account_whitelisting: bool,
+    /// Number of live `WhitelistedTokenAccount` entries for this mint
+    pub token_account_entries: u32,
+    /// Seconds a queued metadata change waits before it can be applied
+    pub metadata_delay: i64,
+    /// Mint whose whitelist pages the hook reads; this token's own mint
+    /// unless it mirrors another token's whitelist
+    pub whitelist_mint: Pubkey,
+    /// Factory that created the token, whose shared whitelist it can use
+    pub factory: Pubkey,
+    /// Proposed authority, which takes over once it signs `accept_authority`
+    pub pending_authority: Option<Pubkey>,
+    /// Hard cap on `total_supply`; only the timelock can change it
+    pub max_supply: Option<u64>,
+    /// Makes the hook emit `HookTelemetry` on every transfer
+    pub debug_telemetry: bool,
+    /// Merkle root of addresses that may claim a `WhitelistEntry`
+    pub whitelist_root: Option<[u8; 32]>,
+    /// Smallest transfer the hook allows, in base units; zero disables it
+    pub min_transfer_amount: u64,
+    /// Which ends of a transfer the hook requires to be whitelisted
+    pub whitelist_policy: WhitelistPolicy,
+    /// Set once the mint authority has been revoked; no more minting
+    pub supply_locked: bool,
+    /// Lets holders keep burning while transfers are paused
+    pub burn_allowed_while_paused: bool,
+    /// Most a wallet may hold after a transfer, in base units; zero disables it
+    pub max_wallet_amount: u64,
+    /// Wallet besides the authority that the holding cap doesn't apply to
+    pub wallet_cap_treasury: Option<Pubkey>,
+    /// Most an owner may send per 24h window, in base units; zero disables it
+    pub daily_transfer_limit: u64,
+    /// Unix time before which only the authority can transfer; zero disables it
+    pub trading_starts_at: i64,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    // Fields from here on are appended, so an account grown by
+    // `migrate_token_data` reads them as zero until they're set. Add new
+    // ones at the end, with a zero value that means "off".
+    /// Supply not deposited into the treasury or a vesting vault. Fees the
+    /// treasury and insurance fund harvest stay counted, as do named vaults
+    /// funded by plain transfers.
+    pub circulating_supply: u64,
+    /// Whether the token's own lists, the factory's or either whitelist an owner
+    pub whitelist_source: WhitelistSource,
+    /// Verified collection whose NFT holders count as whitelisted, through
+    /// a registered `NftPass`
+    pub nft_gate_collection: Option<Pubkey>,
+    /// Number of tokens whose `whitelist_mint` is this token's mint; its
+    /// pages can't be closed while any remain
+    pub whitelist_mirrors: u32,
+    /// Wallet that created the token and seeds its `mint_authority` PDA,
+    /// which stays put when the authority is handed over
+    pub creator: Pubkey,
+}
+
+// Config areas that `lock_config` can freeze
+pub const LOCK_WHITELIST: u16 = 1 << 0;
+pub const LOCK_PAUSE: u16 = 1 << 1;
+pub const LOCK_AUTHORITY: u16 = 1 << 2;
+pub const LOCK_SWAP_RATES: u16 = 1 << 3;
+pub const LOCK_METADATA: u16 = 1 << 4;
+pub const LOCK_TRANSFER_RULES: u16 = 1 << 5;
+pub const LOCK_ALL: u16 = LOCK_WHITELIST
+    | LOCK_PAUSE
+    | LOCK_AUTHORITY
+    | LOCK_SWAP_RATES
+    | LOCK_METADATA
+    | LOCK_TRANSFER_RULES;
+
+impl TokenData {
+    /// Byte offset of `whitelist_mint`, read by the hook's account resolution
+    pub const WHITELIST_MINT_OFFSET: usize = 8 + 8 + 32 + 32 + 8 + 1 + 1 + 1 + 2 + 1 + 4 + 8;
+    /// Byte offset of `factory`, read by the hook's account resolution
+    pub const FACTORY_OFFSET: usize = Self::WHITELIST_MINT_OFFSET + 32;
+    /// Up to the strings, then the strings at their maximum lengths and
+    /// the appended fields
+    pub const SPACE: usize = Self::FACTORY_OFFSET
+        + 32
+        + (1 + 32)
+        + (1 + 8)
+        + 1
+        + (1 + 32)
+        + 8
+        + 1
+        + 1
+        + 1
+        + 8
+        + (1 + 32)
+        + 8
+        + 8
+        + (4 + 32)
+        + (4 + 10)
+        + (4 + 200)
+        + 8
+        + 1
+        + (1 + 32)
+        + 4
+        + 32;
+
+    pub fn require_unlocked(&self, area: u16) -> Result<()> {
+        require!(self.config_lock & area == 0, ErrorCode::ConfigLocked);
+        Ok(())
+    }
+
+    /// Whether `owner` ending up with `balance` base units breaks the cap.
+    pub fn exceeds_wallet_cap(&self, owner: &Pubkey, balance: u64) -> bool {
+        self.max_wallet_amount > 0
+            && balance > self.max_wallet_amount
+            && *owner != self.authority
+            && Some(*owner) != self.wallet_cap_treasury
+    }
+
+    pub fn record_mint(&mut self, amount: u64) -> Result<()> {
+        self.total_supply = self
+            .total_supply
+            .checked_add(amount)
+            .ok_or(ErrorCode::InvalidAmount)?;
+        if let Some(max_supply) = self.max_supply {
+            require!(
+                self.total_supply <= max_supply,
+                ErrorCode::SupplyCapExceeded
+            );
+        }
+        self.circulating_supply = self
+            .circulating_supply
+            .checked_add(amount)
+            .ok_or(ErrorCode::InvalidAmount)?;
+        Ok(())
+    }
+
+    pub fn record_burn(&mut self, amount: u64) -> Result<()> {
+        self.total_supply = self
+            .total_supply
+            .checked_sub(amount)
+            .ok_or(ErrorCode::InvalidAmount)?;
+        self.circulating_supply = self
+            .circulating_supply
+            .checked_sub(amount)
+            .ok_or(ErrorCode::InvalidAmount)?;
+        Ok(())
+    }
+
+    /// Takes tokens moved into a program-owned vault out of circulation.
+    pub fn record_vault_deposit(&mut self, amount: u64) -> Result<()> {
+        self.circulating_supply = self
+            .circulating_supply
+            .checked_sub(amount)
+            .ok_or(ErrorCode::InvalidAmount)?;
+        Ok(())
+    }
+
+    /// Burns tokens held in a program-owned vault. They come out of the
+    /// deposited remainder first; harvested fees were never taken out of
+    /// circulation, so burning past it burns circulating tokens.
+    pub fn record_vault_burn(&mut self, amount: u64) -> Result<()> {
+        self.total_supply = self
+            .total_supply
+            .checked_sub(amount)
+            .ok_or(ErrorCode::InvalidAmount)?;
+        self.circulating_supply = self.circulating_supply.min(self.total_supply);
+        Ok(())
+    }
+
+    /// Puts tokens paid out of a program-owned vault back into circulation,
+    /// up to what was deposited; paid-out fees are already counted.
+    pub fn record_vault_release(&mut self, amount: u64) -> Result<()> {
+        self.circulating_supply = self
+            .circulating_supply
+            .checked_add(amount)
+            .ok_or(ErrorCode::InvalidAmount)?
+            .min(self.total_supply);
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum WhitelistPolicy {
+    #[default]
+    DestinationOnly,
+    SourceOnly,
+    Both,
+    /// Only the pause and minimum amount rules apply
+    Disabled,
+}
+
+impl WhitelistPolicy {
+    pub fn checks_destination(self) -> bool {
+        matches!(
+            self,
+            WhitelistPolicy::DestinationOnly | WhitelistPolicy::Both
+        )
+    }
+
+    pub fn checks_source(self) -> bool {
+        matches!(self, WhitelistPolicy::SourceOnly | WhitelistPolicy::Both)
+    }
+}
+
+/// Where the hook looks an owner up: the token's own pages, entries and
+/// credentials, the factory's shared entries, or both.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum WhitelistSource {
+    #[default]
+    TokenOnly,
+    FactoryOnly,
+    Either,
+}
+
+impl WhitelistSource {
+    pub fn checks_token(self) -> bool {
+        self != WhitelistSource::FactoryOnly
+    }
+
+    pub fn checks_factory(self) -> bool {
+        self != WhitelistSource::TokenOnly
+    }
+}
+
+/// Links a mint to an off-chain ledger reference for reconciliation.
+#[account]
+pub struct MintReceipt {
+    pub reference: [u8; 32],
+    pub to: Pubkey,
+    pub amount: u64,
+    pub slot: u64,
+}
+
+/// `create_token_v2` parameters staged for review, keyed by the authority
+/// and the mint keypair they will be executed with, so nobody else can take
+/// the address first.
+#[account]
+pub struct PreparedTokenConfig {
+    pub authority: Pubkey,
+    pub params: CreateTokenParams,
+}
+
+impl PreparedTokenConfig {
+    pub fn space(params: &CreateTokenParams) -> Result<usize> {
+        Ok(8 + 32 + borsh::to_vec(params)?.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::token_data;
+
+    fn supply(total: u64, circulating: u64) -> TokenData {
+        let mut token_data = token_data(Pubkey::new_unique(), Pubkey::new_unique());
+        token_data.total_supply = total;
+        token_data.circulating_supply = circulating;
+        token_data
+    }
+
+    #[test]
+    fn mint_stops_at_max_supply() {
+        let mut token_data = supply(90, 90);
+        token_data.max_supply = Some(100);
+        token_data.record_mint(10).unwrap();
+        assert_eq!(
+            token_data.record_mint(1).unwrap_err(),
+            ErrorCode::SupplyCapExceeded.into()
+        );
+    }
+
+    #[test]
+    fn burn_past_supply_fails() {
+        let mut token_data = supply(10, 10);
+        assert_eq!(
+            token_data.record_burn(11).unwrap_err(),
+            ErrorCode::InvalidAmount.into()
+        );
+        token_data.record_burn(10).unwrap();
+        assert_eq!(
+            (token_data.total_supply, token_data.circulating_supply),
+            (0, 0)
+        );
+    }
+
+    #[test]
+    fn vault_deposit_leaves_total_supply() {
+        let mut token_data = supply(100, 100);
+        token_data.record_vault_deposit(40).unwrap();
+        assert_eq!(
+            (token_data.total_supply, token_data.circulating_supply),
+            (100, 60)
+        );
+        token_data.record_vault_release(40).unwrap();
+        assert_eq!(
+            (token_data.total_supply, token_data.circulating_supply),
+            (100, 100)
+        );
+    }
+
+    #[test]
+    fn vault_release_stops_at_total_supply() {
+        // Harvested fees were never taken out of circulation
+        let mut token_data = supply(100, 90);
+        token_data.record_vault_release(25).unwrap();
+        assert_eq!(token_data.circulating_supply, 100);
+    }
+
+    #[test]
+    fn vault_burn_takes_the_deposited_remainder_first() {
+        let mut token_data = supply(100, 60);
+        token_data.record_vault_burn(30).unwrap();
+        assert_eq!(
+            (token_data.total_supply, token_data.circulating_supply),
+            (70, 60)
+        );
+        token_data.record_vault_burn(20).unwrap();
+        assert_eq!(
+            (token_data.total_supply, token_data.circulating_supply),
+            (50, 50)
+        );
+    }
+}
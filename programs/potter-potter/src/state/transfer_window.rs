@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+pub const TRANSFER_WINDOW_SECONDS: i64 = 24 * 60 * 60;
+
+/// One owner's outbound volume for a mint over the current 24h window,
+/// checked by the hook against `TokenData::daily_transfer_limit`.
+#[account]
+pub struct TransferWindow {
+    /// Gets the rent back when an expired window is closed
+    pub payer: Pubkey,
+    pub window_start: i64,
+    /// Base units sent since `window_start`
+    pub volume: u64,
+}
+
+impl TransferWindow {
+    pub const SPACE: usize = 8 + 32 + 8 + 8;
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        now >= self.window_start.saturating_add(TRANSFER_WINDOW_SECONDS)
+    }
+
+    /// Adds `amount` to the window, starting a fresh one if it has expired.
+    /// Returns false, leaving the window as it was, if that would pass `limit`.
+    pub fn record(&mut self, amount: u64, limit: u64, now: i64) -> bool {
+        let (window_start, volume) = if self.is_expired(now) {
+            (now, 0)
+        } else {
+            (self.window_start, self.volume)
+        };
+        match volume.checked_add(amount) {
+            Some(volume) if volume <= limit => {
+                self.window_start = window_start;
+                self.volume = volume;
+                true
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey::MAX_SEED_LEN;
+
+/// A named program-owned vault at `[b"vault", token_data, name]`, for
+/// subsystems like sales, buybacks or rewards. It owns the token account at
+/// `[b"vault_tokens", vault]`.
+#[account]
+pub struct Vault {
+    pub mint: Pubkey,
+    pub name: String,
+}
+
+impl Vault {
+    pub const SPACE: usize = 8 + 32 + 4 + MAX_SEED_LEN;
+}
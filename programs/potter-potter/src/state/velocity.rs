@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+/// Transfer activity for one mint over a window of slots, kept by the hook.
+/// Passing either threshold within a window pauses the token.
+#[account]
+pub struct VelocityTracker {
+    /// Slots per window; zero disables the breaker
+    pub window_slots: u64,
+    /// Transfers allowed per window; zero means no count limit
+    pub max_transfers: u32,
+    /// Base units allowed per window; zero means no volume limit
+    pub max_volume: u64,
+    pub window_start_slot: u64,
+    pub transfers: u32,
+    pub volume: u64,
+}
+
+impl VelocityTracker {
+    pub const SPACE: usize = 8 + 8 + 4 + 8 + 8 + 4 + 8;
+
+    /// Counts a transfer of `amount`, returning true if it pushed the
+    /// current window past a threshold.
+    pub fn record(&mut self, amount: u64, slot: u64) -> bool {
+        if self.window_slots == 0 {
+            return false;
+        }
+        if slot >= self.window_start_slot.saturating_add(self.window_slots) {
+            self.window_start_slot = slot;
+            self.transfers = 0;
+            self.volume = 0;
+        }
+        self.transfers = self.transfers.saturating_add(1);
+        self.volume = self.volume.saturating_add(amount);
+        (self.max_transfers > 0 && self.transfers > self.max_transfers)
+            || (self.max_volume > 0 && self.volume > self.max_volume)
+    }
+}
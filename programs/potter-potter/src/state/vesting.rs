@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+
+/// A beneficiary's linear release of whole tokens, at
+/// `[b"vesting", mint, beneficiary]`. The schedule PDA owns the vault that
+/// holds what is still unreleased.
+#[account]
+pub struct VestingSchedule {
+    pub mint: Pubkey,
+    pub beneficiary: Pubkey,
+    pub total: u64,
+    pub released: u64,
+    pub start_at: i64,
+    /// Seconds after `start_at` before anything vests
+    pub cliff: i64,
+    /// Seconds after `start_at` until `total` has vested; zero once revoked
+    pub duration: i64,
+}
+
+impl VestingSchedule {
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8;
+
+    /// Whole tokens vested by `now`, released or not.
+    pub fn vested_amount(&self, now: i64) -> u64 {
+        let elapsed = now.saturating_sub(self.start_at);
+        if elapsed < self.cliff {
+            0
+        } else if elapsed >= self.duration {
+            self.total
+        } else {
+            (self.total as u128 * elapsed as u128 / self.duration as u128) as u64
+        }
+    }
+
+    pub fn releasable(&self, now: i64) -> u64 {
+        self.vested_amount(now).saturating_sub(self.released)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(cliff: i64, duration: i64) -> VestingSchedule {
+        VestingSchedule {
+            mint: Pubkey::new_unique(),
+            beneficiary: Pubkey::new_unique(),
+            total: 1_000,
+            released: 0,
+            start_at: 100,
+            cliff,
+            duration,
+        }
+    }
+
+    #[test]
+    fn nothing_vests_before_the_cliff() {
+        let schedule = schedule(50, 200);
+        assert_eq!(schedule.vested_amount(0), 0);
+        assert_eq!(schedule.vested_amount(149), 0);
+        assert_eq!(schedule.vested_amount(150), 250);
+    }
+
+    #[test]
+    fn vests_linearly_then_caps_at_total() {
+        let schedule = schedule(0, 200);
+        assert_eq!(schedule.vested_amount(100), 0);
+        assert_eq!(schedule.vested_amount(200), 500);
+        assert_eq!(schedule.vested_amount(300), 1_000);
+        assert_eq!(schedule.vested_amount(i64::MAX), 1_000);
+    }
+
+    #[test]
+    fn releasable_excludes_what_was_claimed() {
+        let mut schedule = schedule(0, 200);
+        schedule.released = 400;
+        assert_eq!(schedule.releasable(200), 100);
+        assert_eq!(schedule.releasable(150), 0);
+    }
+
+    #[test]
+    fn revoked_schedule_releases_only_what_vested() {
+        // `revoke_vesting` cuts the total to what vested and zeroes the
+        // cliff and duration
+        let mut schedule = schedule(0, 200);
+        schedule.total = schedule.vested_amount(200);
+        schedule.cliff = 0;
+        schedule.duration = 0;
+        assert_eq!(schedule.releasable(200), 500);
+        assert_eq!(schedule.releasable(i64::MAX), 500);
+    }
+}
@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+
+/// One page of a token's whitelist, holding addresses whose first byte
+/// matches the page index.
+#[account]
+pub struct Whitelist {
+    pub addresses: Vec<Pubkey>,
+    /// Entries on temporary hold; kept listed but denied by the hook
+    pub suspended: Vec<Pubkey>,
+}
+
+impl Whitelist {
+    pub fn space(address_count: usize, suspended_count: usize) -> usize {
+        8 + 4 + (address_count * 32) + 4 + (suspended_count * 32)
+    }
+
+    pub fn is_allowed(&self, address: &Pubkey) -> bool {
+        self.addresses.contains(address) && !self.suspended.contains(address)
+    }
+
+    /// Number of entries `is_allowed` compares against for `address`.
+    pub fn entries_scanned(&self, address: &Pubkey) -> u32 {
+        match self.addresses.iter().position(|a| a == address) {
+            Some(i) => (i + 1 + self.suspended.len()) as u32,
+            None => self.addresses.len() as u32,
+        }
+    }
+}
+
+/// Whitelists one owner for a mint, checked by the hook without scanning a
+/// page, so lists can grow past what a single account can hold.
+#[account]
+pub struct WhitelistEntry {
+    /// After this the hook treats the owner as not whitelisted and anyone
+    /// can `prune_expired` the entry; `None` never expires
+    pub expires_at: Option<i64>,
+    /// On temporary hold, as a page's `suspended`; the expiry still runs
+    pub suspended: bool,
+}
+
+impl WhitelistEntry {
+    pub const SPACE: usize = 8 + (1 + 8) + 1;
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+
+    pub fn is_active(&self, now: i64) -> bool {
+        !self.suspended && !self.is_expired(now)
+    }
+}
+
+/// Marks a single token account as whitelisted for a mint, for holders such
+/// as program escrows where whitelisting the owner would be too broad.
+#[account]
+pub struct WhitelistedTokenAccount {}
+
+/// Lets one non-whitelisted owner receive a mint until the cap is used up
+/// or the permit expires. The hook draws it down on each transfer.
+#[account]
+pub struct TransferPermit {
+    /// Base units still allowed, like `min_transfer_amount`
+    pub remaining: u64,
+    pub expires_at: i64,
+}
+
+impl TransferPermit {
+    pub const SPACE: usize = 8 + 8 + 8;
+}
+
+/// Staging buffer for a whitelist import spread over several transactions.
+#[account]
+pub struct WhitelistImport {
+    pub page: u8,
+    /// Index of the next chunk `append_whitelist_import` accepts
+    pub next_chunk: u32,
+    /// Number of staged addresses already merged into the page
+    pub committed: u32,
+    pub addresses: Vec<Pubkey>,
+}
+
+// Keeps each commit's whitelist realloc under the 10KB growth limit
+const IMPORT_COMMIT_BATCH: usize = 256;
+
+impl WhitelistImport {
+    pub fn space(address_count: usize) -> usize {
+        8 + 1 + 4 + 4 + 4 + (address_count * 32)
+    }
+
+    pub fn space_after_append(&self, chunk_index: u32, new_addresses: usize) -> usize {
+        if chunk_index < self.next_chunk {
+            return Self::space(self.addresses.len());
+        }
+        Self::space(self.addresses.len() + new_addresses)
+    }
+
+    pub fn next_batch_len(&self) -> usize {
+        let remaining = self.addresses.len() - self.committed as usize;
+        remaining.min(IMPORT_COMMIT_BATCH)
+    }
+}
@@ -0,0 +1,242 @@
+//! Accounts and a fixed clock for unit tests of code that reads
+//! `AccountInfo`s directly. Accounts are leaked so tests can hold them as
+//! `'static`.
+
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{
+        transfer_hook::TransferHookAccount, BaseStateWithExtensionsMut, ExtensionType,
+        StateWithExtensionsMut,
+    },
+    state::{Account as SplAccount, AccountState, Mint as SplMint},
+};
+use solana_sysvar::program_stubs::{set_syscall_stubs, SyscallStubs};
+use std::sync::Once;
+
+/// Unix time `Clock::get` reports in tests.
+pub const NOW: i64 = 1_700_000_000;
+/// Slot `Clock::get` reports in tests.
+pub const SLOT: u64 = 1_000;
+
+struct FixedClock;
+
+impl SyscallStubs for FixedClock {
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = Clock {
+            slot: SLOT,
+            unix_timestamp: NOW,
+            ..Clock::default()
+        };
+        // The caller hands over space for exactly one `Clock`
+        unsafe { *(var_addr as *mut Clock) = clock };
+        anchor_lang::solana_program::entrypoint::SUCCESS
+    }
+}
+
+/// Makes `Clock::get` report `NOW` and `SLOT`.
+pub fn use_fixed_clock() {
+    static CLOCK: Once = Once::new();
+    CLOCK.call_once(|| {
+        set_syscall_stubs(Box::new(FixedClock));
+    });
+}
+
+pub fn leak_account(key: Pubkey, owner: Pubkey, data: Vec<u8>) -> &'static AccountInfo<'static> {
+    Box::leak(Box::new(AccountInfo::new(
+        Box::leak(Box::new(key)),
+        false,
+        true,
+        Box::leak(Box::new(1_000_000_000)),
+        Box::leak(data.into_boxed_slice()),
+        Box::leak(Box::new(owner)),
+        false,
+        0,
+    )))
+}
+
+pub fn unchecked(info: &'static AccountInfo<'static>) -> UncheckedAccount<'static> {
+    UncheckedAccount::try_from(info)
+}
+
+/// `key` signing the transaction, with nothing stored at it.
+pub fn signer_account(key: Pubkey) -> &'static AccountInfo<'static> {
+    let info = leak_account(key, System::id(), Vec::new());
+    Box::leak(Box::new(AccountInfo {
+        is_signer: true,
+        ..info.clone()
+    }))
+}
+
+/// The program deployed at `key`, as `Program` and `Interface` accounts
+/// expect to find it.
+pub fn executable_account(key: Pubkey) -> &'static AccountInfo<'static> {
+    let info = leak_account(key, Pubkey::default(), Vec::new());
+    Box::leak(Box::new(AccountInfo {
+        executable: true,
+        ..info.clone()
+    }))
+}
+
+/// An address nothing has been created at.
+pub fn missing_account(key: Pubkey) -> &'static AccountInfo<'static> {
+    leak_account(key, System::id(), Vec::new())
+}
+
+/// `value` stored at `key` and owned by this program.
+pub fn program_account<T: AccountSerialize>(
+    key: Pubkey,
+    value: &T,
+) -> &'static AccountInfo<'static> {
+    let mut data = Vec::new();
+    value.try_serialize(&mut data).unwrap();
+    leak_account(key, crate::ID, data)
+}
+
+/// `value` stored at `key` under some other program.
+pub fn foreign_account<T: AccountSerialize>(
+    key: Pubkey,
+    value: &T,
+) -> &'static AccountInfo<'static> {
+    let mut data = Vec::new();
+    value.try_serialize(&mut data).unwrap();
+    leak_account(key, Pubkey::new_unique(), data)
+}
+
+/// A marker PDA whose existence is all that counts, as blacklist and
+/// token account entries are.
+pub fn marker_account(key: Pubkey) -> &'static AccountInfo<'static> {
+    leak_account(key, crate::ID, vec![0; 8])
+}
+
+pub fn read_account<T: AccountDeserialize>(info: &AccountInfo) -> T {
+    T::try_deserialize(&mut &info.try_borrow_data().unwrap()[..]).unwrap()
+}
+
+fn spl_account(mint: Pubkey, owner: Pubkey, amount: u64, frozen: bool) -> SplAccount {
+    SplAccount {
+        mint,
+        owner,
+        amount,
+        state: if frozen {
+            AccountState::Frozen
+        } else {
+            AccountState::Initialized
+        },
+        ..SplAccount::default()
+    }
+}
+
+/// A Token-2022 account of `mint` held by `owner`.
+pub fn token_account(
+    key: Pubkey,
+    mint: Pubkey,
+    owner: Pubkey,
+    amount: u64,
+    frozen: bool,
+) -> &'static AccountInfo<'static> {
+    let mut data = vec![0; SplAccount::LEN];
+    SplAccount::pack(spl_account(mint, owner, amount, frozen), &mut data).unwrap();
+    leak_account(key, anchor_spl::token_2022::ID, data)
+}
+
+/// The source of a Token-2022 transfer, whose hook extension says whether
+/// Token-2022 is mid-transfer.
+pub fn hooked_token_account(
+    key: Pubkey,
+    mint: Pubkey,
+    owner: Pubkey,
+    amount: u64,
+    transferring: bool,
+) -> &'static AccountInfo<'static> {
+    let len = ExtensionType::try_calculate_account_len::<SplAccount>(&[
+        ExtensionType::TransferHookAccount,
+    ])
+    .unwrap();
+    let mut data = vec![0; len];
+    let mut state = StateWithExtensionsMut::<SplAccount>::unpack_uninitialized(&mut data).unwrap();
+    state
+        .init_extension::<TransferHookAccount>(true)
+        .unwrap()
+        .transferring = transferring.into();
+    state.base = spl_account(mint, owner, amount, false);
+    state.pack_base();
+    state.init_account_type().unwrap();
+    leak_account(key, anchor_spl::token_2022::ID, data)
+}
+
+/// A Token-2022 mint with no authorities.
+pub fn mint_account(key: Pubkey, supply: u64, decimals: u8) -> &'static AccountInfo<'static> {
+    let mint = SplMint {
+        supply,
+        decimals,
+        is_initialized: true,
+        ..SplMint::default()
+    };
+    let mut data = vec![0; SplMint::LEN];
+    SplMint::pack(mint, &mut data).unwrap();
+    leak_account(key, anchor_spl::token_2022::ID, data)
+}
+
+/// A token with every optional rule off.
+pub fn token_data(mint: Pubkey, authority: Pubkey) -> TokenData {
+    TokenData {
+        token_index: 0,
+        mint,
+        authority,
+        total_supply: 0,
+        decimals: 9,
+        is_paused: false,
+        is_minting_paused: false,
+        config_lock: 0,
+        token_account_whitelisting: false,
+        token_account_entries: 0,
+        metadata_delay: 0,
+        whitelist_mint: mint,
+        factory: Pubkey::new_unique(),
+        pending_authority: None,
+        max_supply: None,
+        debug_telemetry: false,
+        whitelist_root: None,
+        min_transfer_amount: 0,
+        whitelist_policy: WhitelistPolicy::DestinationOnly,
+        supply_locked: false,
+        burn_allowed_while_paused: false,
+        max_wallet_amount: 0,
+        wallet_cap_treasury: None,
+        daily_transfer_limit: 0,
+        trading_starts_at: 0,
+        name: "Potter".to_string(),
+        symbol: "POT".to_string(),
+        uri: String::new(),
+        circulating_supply: 0,
+        whitelist_source: WhitelistSource::TokenOnly,
+        nft_gate_collection: None,
+        whitelist_mirrors: 0,
+        creator: authority,
+    }
+}
+
+/// The program config, with `disabled_features` switched off.
+pub fn program_config(disabled_features: u16) -> ProgramConfig {
+    ProgramConfig {
+        authority: Pubkey::new_unique(),
+        v1_disabled: false,
+        is_devnet: true,
+        disabled_features,
+        program_paused: false,
+        limits: Limits::DEFAULT,
+    }
+}
+
+/// A key on the curve, as a wallet's is, unlike most `new_unique` ones.
+pub fn wallet() -> Pubkey {
+    std::iter::repeat_with(Pubkey::new_unique)
+        .find(Pubkey::is_on_curve)
+        .unwrap()
+}
+
+pub fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &crate::ID).0
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use solana_sha256_hasher::hashv;
+
+// Leaves and inner nodes are domain-separated so an inner node can't pass as
+// a leaf. Pairs are hashed in sorted order, so proofs need no direction bits.
+
+pub fn merkle_leaf(address: &Pubkey) -> [u8; 32] {
+    hashv(&[&[0u8], address.as_ref()]).to_bytes()
+}
+
+fn merkle_node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[&[1u8], lo, hi]).to_bytes()
+}
+
+/// Checks that `address` is a leaf of the tree committed to by `root`.
+pub fn verify_whitelist_proof(root: &[u8; 32], address: &Pubkey, proof: &[[u8; 32]]) -> bool {
+    let computed = proof.iter().fold(merkle_leaf(address), |node, sibling| {
+        merkle_node(&node, sibling)
+    });
+    computed == *root
+}
+
+// Every level of the tree, leaves first. A trailing odd node is carried up
+// to the next level unchanged.
+fn merkle_levels(addresses: &[Pubkey]) -> Vec<Vec<[u8; 32]>> {
+    let mut levels = vec![addresses.iter().map(merkle_leaf).collect::<Vec<_>>()];
+    while levels[levels.len() - 1].len() > 1 {
+        let next = levels[levels.len() - 1]
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => merkle_node(a, b),
+                _ => pair[0],
+            })
+            .collect();
+        levels.push(next);
+    }
+    levels
+}
+
+/// Root to pass to `set_whitelist_root` for `addresses`. Client-side helper.
+pub fn merkle_root(addresses: &[Pubkey]) -> [u8; 32] {
+    let levels = merkle_levels(addresses);
+    levels[levels.len() - 1]
+        .first()
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Proof that `addresses[index]` is under `merkle_root(addresses)`.
+/// Client-side helper.
+pub fn merkle_proof(addresses: &[Pubkey], index: usize) -> Vec<[u8; 32]> {
+    let mut proof = vec![];
+    let mut index = index;
+    for level in merkle_levels(addresses) {
+        if let Some(sibling) = level.get(index ^ 1) {
+            proof.push(*sibling);
+        }
+        index /= 2;
+    }
+    proof
+}
@@ -0,0 +1,202 @@
+use crate::errors::ErrorCode;
+use crate::instructions::AdminAction;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::amount_to_ui_amount_string_trimmed;
+use std::io::Read;
+
+mod merkle;
+mod vault;
+
+pub use merkle::*;
+pub(crate) use vault::*;
+
+pub fn whitelist_page_of(address: &Pubkey) -> u8 {
+    address.to_bytes()[0]
+}
+
+/// Returns true when `key` was already used, meaning the caller should
+/// treat the request as a no-op.
+pub(crate) fn is_duplicate_request(
+    log: &mut Option<Account<IdempotencyLog>>,
+    key: Option<[u8; 16]>,
+) -> Result<bool> {
+    let Some(key) = key else {
+        return Ok(false);
+    };
+    require!(key != [0u8; 16], ErrorCode::InvalidIdempotencyKey);
+    let log = log.as_mut().ok_or(ErrorCode::IdempotencyLogMissing)?;
+    if !log.record(key) {
+        msg!("Duplicate request ignored");
+        return Ok(true);
+    }
+    Ok(false)
+}
+
+/// Deserializes an appended instruction field, falling back to its default
+/// when the input ends before it.
+pub fn deserialize_trailing<T, R>(reader: &mut R) -> std::io::Result<T>
+where
+    T: AnchorDeserialize + Default,
+    R: Read,
+{
+    let mut first = [0u8; 1];
+    if reader.read(&mut first)? == 0 {
+        return Ok(T::default());
+    }
+    T::deserialize_reader(&mut first.as_slice().chain(reader))
+}
+
+/// Renders a base-unit amount as a decimal string, e.g. "12.5".
+pub fn ui_amount_string(raw_amount: u64, decimals: u8) -> String {
+    amount_to_ui_amount_string_trimmed(raw_amount, decimals)
+}
+
+/// Closes an account this program owns by hand, for accounts that aren't
+/// typed in the instruction's context.
+pub(crate) fn close_program_account<'info>(
+    account: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+) -> Result<()> {
+    require_keys_eq!(*account.owner, crate::ID, ErrorCode::Unauthorized);
+    let lamports = account.lamports();
+    **destination.try_borrow_mut_lamports()? = destination
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ErrorCode::InvalidAmount)?;
+    **account.try_borrow_mut_lamports()? = 0;
+    account.assign(&anchor_lang::system_program::ID);
+    account.resize(0)?;
+    Ok(())
+}
+
+/// Grows an account this program owns to `new_len`, topping its rent up
+/// from `payer`. The new bytes are zeroed.
+pub(crate) fn grow_program_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_len: usize,
+) -> Result<()> {
+    require_keys_eq!(*account.owner, crate::ID, ErrorCode::Unauthorized);
+    if new_len <= account.data_len() {
+        return Ok(());
+    }
+    let rent = Rent::get()?.minimum_balance(new_len);
+    if rent > account.lamports() {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            rent - account.lamports(),
+        )?;
+    }
+    account.resize(new_len)?;
+    Ok(())
+}
+
+/// Whether the owner has an opt-out marker for `mint`; `opt_out` must be
+/// its address whether or not the marker exists.
+pub(crate) fn has_opted_out(opt_out: &AccountInfo, mint: &Pubkey, owner: &Pubkey) -> Result<bool> {
+    let (expected, _) =
+        Pubkey::find_program_address(&[b"opt_out", mint.as_ref(), owner.as_ref()], &crate::ID);
+    require_keys_eq!(opt_out.key(), expected, ErrorCode::InvalidOptOutAccount);
+    Ok(*opt_out.owner == crate::ID && !opt_out.data_is_empty())
+}
+
+/// Current unix time for timelocks and expiries. `test-harness` builds let
+/// a `MockClock` passed among `accounts` stand in for the sysvar.
+pub fn unix_timestamp(accounts: &[AccountInfo]) -> Result<i64> {
+    #[cfg(feature = "test-harness")]
+    {
+        let (mock_clock, _) = Pubkey::find_program_address(&[b"mock_clock"], &crate::ID);
+        if let Some(info) = accounts
+            .iter()
+            .find(|info| info.key() == mock_clock && *info.owner == crate::ID)
+        {
+            let clock = MockClock::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+            return Ok(clock.unix_timestamp);
+        }
+    }
+    #[cfg(not(feature = "test-harness"))]
+    let _ = accounts;
+    Ok(Clock::get()?.unix_timestamp)
+}
+
+/// Rejects running a timelocked action directly once `timelock` exists.
+pub(crate) fn require_not_timelocked(timelock: &AccountInfo, action: &AdminAction) -> Result<()> {
+    require!(
+        !action.is_timelocked() || timelock.data_is_empty(),
+        ErrorCode::ActionTimelocked
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    fn log() -> Option<Account<'static, IdempotencyLog>> {
+        let log = IdempotencyLog {
+            keys: [[0; 16]; IDEMPOTENCY_LOG_SIZE],
+            next: 0,
+        };
+        Some(Account::try_from(program_account(Pubkey::new_unique(), &log)).unwrap())
+    }
+
+    #[test]
+    fn repeated_key_is_a_duplicate() {
+        let mut log = log();
+        assert!(!is_duplicate_request(&mut log, Some([1; 16])).unwrap());
+        assert!(is_duplicate_request(&mut log, Some([1; 16])).unwrap());
+        assert!(!is_duplicate_request(&mut log, Some([2; 16])).unwrap());
+    }
+
+    #[test]
+    fn no_key_is_never_a_duplicate() {
+        let mut log = None;
+        assert!(!is_duplicate_request(&mut log, None).unwrap());
+        assert!(!is_duplicate_request(&mut log, None).unwrap());
+    }
+
+    #[test]
+    fn key_needs_a_log_and_a_nonzero_value() {
+        assert_eq!(
+            is_duplicate_request(&mut None, Some([1; 16])).unwrap_err(),
+            ErrorCode::IdempotencyLogMissing.into()
+        );
+        assert_eq!(
+            is_duplicate_request(&mut log(), Some([0; 16])).unwrap_err(),
+            ErrorCode::InvalidIdempotencyKey.into()
+        );
+    }
+
+    #[test]
+    fn timelock_holds_unpausing_but_not_pausing() {
+        let timelock = program_account(
+            Pubkey::new_unique(),
+            &TimelockConfig {
+                min_delay: 60,
+                next_action_id: 0,
+            },
+        );
+        for paused in [true, false] {
+            for action in [
+                AdminAction::SetPaused { paused },
+                AdminAction::SetMintingPaused { paused },
+            ] {
+                let result = require_not_timelocked(timelock, &action);
+                if paused {
+                    assert!(result.is_ok());
+                } else {
+                    assert_eq!(result.unwrap_err(), ErrorCode::ActionTimelocked.into());
+                }
+            }
+        }
+    }
+}
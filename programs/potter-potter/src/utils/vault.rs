@@ -0,0 +1,110 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    burn, mint_to, thaw_account, Burn, Mint, MintTo, ThawAccount, TokenAccount, TokenInterface,
+};
+
+/// The burn-and-mint moves every program-owned vault is built on. A
+/// transfer CPI would re-enter this program through the hook, so value goes
+/// into a vault as a burn from the holder and a mint to the vault, and out
+/// as a burn from the vault and a mint to the destination. Supply is
+/// unchanged either way; callers keep circulating supply with the
+/// `TokenData::record_vault_*` methods.
+pub(crate) struct VaultMint<'a, 'info> {
+    pub token_program: &'a Interface<'info, TokenInterface>,
+    pub mint: &'a InterfaceAccount<'info, Mint>,
+    /// The token's `[b"mint_authority", creator]` PDA
+    pub mint_authority_pda: &'a AccountInfo<'info>,
+    pub creator: Pubkey,
+    pub mint_authority_bump: u8,
+}
+
+impl<'info> VaultMint<'_, 'info> {
+    /// Default-frozen mints hand a new vault over frozen; the mint
+    /// authority PDA is also the freeze authority, so it thaws it.
+    pub fn thaw_if_frozen(&self, vault: &InterfaceAccount<'info, TokenAccount>) -> Result<()> {
+        if !vault.is_frozen() {
+            return Ok(());
+        }
+        let seeds = self.mint_authority_seeds();
+        thaw_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            ThawAccount {
+                account: vault.to_account_info(),
+                mint: self.mint.to_account_info(),
+                authority: self.mint_authority_pda.clone(),
+            },
+            &[&seeds[..]],
+        ))
+    }
+
+    /// Moves `raw_amount` from `from`, signed by its `holder`, into `vault`.
+    pub fn deposit(
+        &self,
+        from: &InterfaceAccount<'info, TokenAccount>,
+        holder: &AccountInfo<'info>,
+        vault: &InterfaceAccount<'info, TokenAccount>,
+        raw_amount: u64,
+    ) -> Result<()> {
+        burn(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                Burn {
+                    mint: self.mint.to_account_info(),
+                    from: from.to_account_info(),
+                    authority: holder.clone(),
+                },
+            ),
+            raw_amount,
+        )?;
+        self.mint_to(vault, raw_amount)
+    }
+
+    /// Pays `raw_amount` out of `vault` to `destination`. `vault_seeds` are
+    /// those of the PDA owning the vault, bump included.
+    pub fn release(
+        &self,
+        vault: &InterfaceAccount<'info, TokenAccount>,
+        vault_authority: &AccountInfo<'info>,
+        vault_seeds: &[&[u8]],
+        destination: &InterfaceAccount<'info, TokenAccount>,
+        raw_amount: u64,
+    ) -> Result<()> {
+        burn(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Burn {
+                    mint: self.mint.to_account_info(),
+                    from: vault.to_account_info(),
+                    authority: vault_authority.clone(),
+                },
+                &[vault_seeds],
+            ),
+            raw_amount,
+        )?;
+        self.mint_to(destination, raw_amount)
+    }
+
+    fn mint_authority_seeds(&self) -> [&[u8]; 3] {
+        [
+            b"mint_authority",
+            self.creator.as_ref(),
+            std::slice::from_ref(&self.mint_authority_bump),
+        ]
+    }
+
+    fn mint_to(&self, to: &InterfaceAccount<'info, TokenAccount>, raw_amount: u64) -> Result<()> {
+        let seeds = self.mint_authority_seeds();
+        mint_to(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                MintTo {
+                    mint: self.mint.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: self.mint_authority_pda.clone(),
+                },
+                &[&seeds[..]],
+            ),
+            raw_amount,
+        )
+    }
+}
@@ -3,19 +3,31 @@ import { Program } from "@coral-xyz/anchor";
 import {
   Keypair,
   PublicKey,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
   SystemProgram,
   Transaction,
 } from "@solana/web3.js";
 import {
-  MINT_SIZE,
-  TOKEN_PROGRAM_ID,
-  createAssociatedTokenAccountInstruction,
-  createInitializeMintInstruction,
-  getAssociatedTokenAddress,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  createAssociatedTokenAccountIdempotentInstruction,
+  createTransferCheckedWithTransferHookInstruction,
+  getAccount,
+  getAssociatedTokenAddressSync,
 } from "@solana/spl-token";
 import { assert } from "chai";
 import { PotterPotter } from "../target/types/potter_potter";
 
+const MPL_TOKEN_METADATA_ID = new PublicKey(
+  "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+);
+const BPF_LOADER_UPGRADEABLE_ID = new PublicKey(
+  "BPFLoaderUpgradeab1e11111111111111111111111"
+);
+const DECIMALS = 9;
+const FEATURE_MINTING = 1 << 1;
+const FEATURE_SWAPS = 1 << 2;
+
 describe("potter-potter", () => {
   const provider = anchor.AnchorProvider.env();
   anchor.setProvider(provider);
@@ -23,284 +35,1854 @@ describe("potter-potter", () => {
   const program = anchor.workspace.PotterPotter as Program<PotterPotter>;
   const authority = provider.wallet as anchor.Wallet;
 
-  let factory: PublicKey;
-  let tokenData: PublicKey;
-  let whitelist: PublicKey;
-  let mint: Keypair;
+  const pda = (...seeds: (Buffer | Uint8Array)[]) =>
+    PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+  const programConfig = pda(Buffer.from("program_config"));
+  const protocolStats = pda(Buffer.from("protocol_stats"));
+  const factory = pda(Buffer.from("factory"), authority.publicKey.toBuffer());
+  const mintAuthorityPda = pda(
+    Buffer.from("mint_authority"),
+    authority.publicKey.toBuffer()
+  );
 
-  it("Creates a new factory", async () => {
-    [factory] = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("token-factory")],
-      program.programId
-    );
+  /** Base units of `amount` whole tokens, as a decimal string. */
+  const whole = (amount: number) =>
+    new anchor.BN(amount)
+      .mul(new anchor.BN(10).pow(new anchor.BN(DECIMALS)))
+      .toString();
 
-    await program.methods
-      .createFactory()
-      .accounts({
-        factory,
-        authority: authority.publicKey,
-        systemProgram: SystemProgram.programId,
-      })
-      .rpc();
+  const ataOf = (mint: PublicKey, owner: PublicKey) =>
+    getAssociatedTokenAddressSync(mint, owner, true, TOKEN_2022_PROGRAM_ID);
 
-    const factoryAccount = await program.account.tokenFactory.fetch(factory);
-    assert.equal(
-      factoryAccount.authority.toBase58(),
-      authority.publicKey.toBase58()
-    );
-    assert.equal(factoryAccount.tokenCount.toNumber(), 0);
-  });
+  const balanceOf = async (account: PublicKey) =>
+    (
+      await getAccount(
+        provider.connection,
+        account,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      )
+    ).amount.toString();
 
-  it("Creates a new token", async () => {
-    const factoryAccountBefore = await program.account.tokenFactory.fetch(
-      factory
-    );
-    const tokenCount = factoryAccountBefore.tokenCount.toNumber();
+  /** Fails unless `promise` rejects with `code` in its message or logs. */
+  const expectError = async (promise: Promise<unknown>, code: string) => {
+    let error: any;
+    try {
+      await promise;
+    } catch (err) {
+      error = err;
+    }
+    assert.exists(error, `expected ${code}`);
+    const text = [String(error), ...(error.logs ?? [])].join("\n");
+    assert.include(text, code);
+  };
 
-    [tokenData] = anchor.web3.PublicKey.findProgramAddressSync(
-      [
-        Buffer.from("token-data"),
-        new anchor.BN(tokenCount).toBuffer("le", 8),
-      ],
-      program.programId
+  /** A fresh wallet, funded from the provider for rent and fees. */
+  const newUser = async () => {
+    const user = Keypair.generate();
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: authority.publicKey,
+          toPubkey: user.publicKey,
+          lamports: 50_000_000,
+        })
+      )
     );
+    return user;
+  };
 
-    [whitelist] = anchor.web3.PublicKey.findProgramAddressSync(
-      [
-        Buffer.from("whitelist"),
-        new anchor.BN(tokenCount).toBuffer("le", 8),
-      ],
-      program.programId
-    );
+  type Token = {
+    mint: PublicKey;
+    tokenData: PublicKey;
+    /** The authority's account, which holds the initial supply */
+    ata: PublicKey;
+    defaultPage: number;
+    /** The `create_token_v2` transaction */
+    signature: string;
+  };
 
-    mint = Keypair.generate();
-    const defaultAddress = Keypair.generate().publicKey;
+  /** Creates a token through `create_token_v2` plus its hook account list. */
+  const createToken = async (
+    totalSupply: number,
+    overrides: Partial<{ closable: boolean; defaultAddress: PublicKey }> = {}
+  ): Promise<Token> => {
+    const factoryAccount = await program.account.tokenFactory.fetch(factory);
+    const tokenData = pda(
+      Buffer.from("token"),
+      authority.publicKey.toBuffer(),
+      factoryAccount.tokenCount.toArrayLike(Buffer, "le", 8)
+    );
+    const mint = Keypair.generate();
+    const defaultAddress = overrides.defaultAddress ?? authority.publicKey;
+    const defaultPage = defaultAddress.toBuffer()[0];
+    const ata = ataOf(mint.publicKey, authority.publicKey);
 
-    await program.methods
-      .createToken(
-        new anchor.BN(1000),
-        6,
-        "Test Token",
-        "TEST",
-        "http://test.com",
-        defaultAddress
-      )
-      .accounts({
+    const signature = await program.methods
+      .createTokenV2({
+        totalSupply: new anchor.BN(totalSupply),
+        name: "Potter Test",
+        symbol: "POT",
+        uri: "https://example.com/pot.json",
+        defaultAddress,
+        assetStandard: { fungible: {} },
+        decimals: null,
+        maxSupply: null,
+        transferFee: null,
+        clawback: false,
+        defaultFrozen: false,
+        interestRateBps: null,
+        closable: overrides.closable ?? false,
+      })
+      .accountsPartial({
+        programConfig,
+        protocolStats,
         factory,
         tokenData,
-        whitelist,
         mint: mint.publicKey,
+        whitelist: pda(
+          Buffer.from("whitelist"),
+          mint.publicKey.toBuffer(),
+          Buffer.from([defaultPage])
+        ),
+        mintAuthorityPda,
+        ata,
+        treasury: pda(Buffer.from("treasury"), mint.publicKey.toBuffer()),
+        treasuryAuthority: pda(
+          Buffer.from("treasury_authority"),
+          mint.publicKey.toBuffer()
+        ),
+        metadata: PublicKey.findProgramAddressSync(
+          [
+            Buffer.from("metadata"),
+            MPL_TOKEN_METADATA_ID.toBuffer(),
+            mint.publicKey.toBuffer(),
+          ],
+          MPL_TOKEN_METADATA_ID
+        )[0],
         authority: authority.publicKey,
-        systemProgram: SystemProgram.programId,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+        collectionMint: null,
+        collectionMetadata: null,
+        collectionMasterEdition: null,
+        tokenMetadataProgram: MPL_TOKEN_METADATA_ID,
+        preparedConfig: null,
       })
-
       .signers([mint])
       .rpc();
 
-    const factoryAccountAfter = await program.account.tokenFactory.fetch(
-      factory
-    );
-    const tokenDataAccount = await program.account.tokenData.fetch(tokenData);
-    const whitelistAccount = await program.account.whitelist.fetch(whitelist);
-
-    assert.equal(factoryAccountAfter.tokenCount.toNumber(), tokenCount + 1);
-    assert.equal(
-      tokenDataAccount.authority.toBase58(),
-      authority.publicKey.toBase58()
-    );
-    assert.equal(tokenDataAccount.totalSupply.toNumber(), 1000);
-    assert.isFalse(tokenDataAccount.isPaused);
-    assert.isFalse(tokenDataAccount.isMintingPaused);
-    assert.equal(tokenDataAccount.name, "Test Token");
-    assert.equal(tokenDataAccount.symbol, "TEST");
-    assert.equal(tokenDataAccount.uri, "http://test.com");
-    assert.equal(
-      whitelistAccount.addresses[0].toBase58(),
-      defaultAddress.toBase58()
-    );
-  });
-
-  it("Adds to whitelist", async () => {
-    const factoryAccount = await program.account.tokenFactory.fetch(factory);
-    const tokenCount = factoryAccount.tokenCount.toNumber() - 1;
-
-    const newAddress1 = Keypair.generate().publicKey;
-    const newAddress2 = Keypair.generate().publicKey;
-
     await program.methods
-      .addToWhitelist(new anchor.BN(tokenCount), [newAddress1, newAddress2])
-      .accounts({
+      .initializeExtraAccountMetaList()
+      .accountsPartial({
+        payer: authority.publicKey,
+        extraAccountMetaList: pda(
+          Buffer.from("extra-account-metas"),
+          mint.publicKey.toBuffer()
+        ),
+        mint: mint.publicKey,
         tokenData,
-        whitelist,
-        authority: authority.publicKey,
       })
       .rpc();
 
-    const whitelistAccount = await program.account.whitelist.fetch(whitelist);
-    assert.isTrue(
-      whitelistAccount.addresses.some(
-        (addr) => addr.toBase58() === newAddress1.toBase58()
-      )
-    );
-    assert.isTrue(
-      whitelistAccount.addresses.some(
-        (addr) => addr.toBase58() === newAddress2.toBase58()
-      )
-    );
-  });
-
-  it("Transfers tokens", async () => {
-    const factoryAccount = await program.account.tokenFactory.fetch(factory);
-    const tokenCount = factoryAccount.tokenCount.toNumber() - 1;
-
-    const fromAta = await getAssociatedTokenAddress(
-      mint.publicKey,
-      authority.publicKey
-    );
-    const toKp = Keypair.generate();
-    const toAta = await getAssociatedTokenAddress(
-      mint.publicKey,
-      toKp.publicKey
-    );
+    return { mint: mint.publicKey, tokenData, ata, defaultPage, signature };
+  };
 
-    const tx = new Transaction().add(
-      createAssociatedTokenAccountInstruction(
-        authority.publicKey,
-        fromAta,
-        authority.publicKey,
-        mint.publicKey
-      ),
-      createAssociatedTokenAccountInstruction(
-        authority.publicKey,
-        toAta,
-        toKp.publicKey,
-        mint.publicKey
+  /** Creates `owner`'s associated account for the token. */
+  const openAccount = async (token: Token, owner: PublicKey) => {
+    const account = ataOf(token.mint, owner);
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        createAssociatedTokenAccountIdempotentInstruction(
+          authority.publicKey,
+          account,
+          owner,
+          token.mint,
+          TOKEN_2022_PROGRAM_ID
+        )
       )
     );
-    await provider.sendAndConfirm(tx, []);
+    return account;
+  };
 
-    await program.methods
-      .mintTokens(new anchor.BN(tokenCount), new anchor.BN(100))
-      .accounts({
-        tokenData,
-        mint: mint.publicKey,
-        to: fromAta,
+  const whitelist = (token: Token, owner: PublicKey) =>
+    program.methods
+      .addWhitelistEntry(owner, null)
+      .accountsPartial({
+        programConfig,
+        tokenData: token.tokenData,
+        entry: pda(Buffer.from("wl"), token.mint.toBuffer(), owner.toBuffer()),
         authority: authority.publicKey,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        role: null,
       })
       .rpc();
 
-    await program.methods
-      .addToWhitelist(new anchor.BN(tokenCount), [toAta])
-      .accounts({
-        tokenData,
-        whitelist,
+  /** Toggles the token's pause. */
+  const pauseToken = (token: Token) =>
+    program.methods
+      .pauseToken(null)
+      .accountsPartial({
+        tokenData: token.tokenData,
+        idempotencyLog: null,
+        hookManifest: null,
+        programConfig,
+        timelock: pda(Buffer.from("timelock"), token.mint.toBuffer()),
         authority: authority.publicKey,
+        role: null,
       })
       .rpc();
 
-    await program.methods
-      .transferToken(new anchor.BN(tokenCount), new anchor.BN(50))
-      .accounts({
-        tokenData,
-        whitelist,
-        from: fromAta,
-        to: toAta,
+  /** Switches off the `FEATURE_*` bits in `mask` program-wide. */
+  const setDisabledFeatures = (mask: number) =>
+    program.methods
+      .setDisabledFeatures(mask)
+      .accountsPartial({ programConfig, authority: authority.publicKey })
+      .rpc();
+
+  /** Lifts one phase of a program pause, as the upgrade authority. */
+  const programResume = (
+    phase: Parameters<typeof program.methods.programResume>[0]
+  ) =>
+    program.methods
+      .programResume(phase)
+      .accountsPartial({
+        programConfig,
+        potterPotter: program.programId,
+        programData: PublicKey.findProgramAddressSync(
+          [program.programId.toBuffer()],
+          BPF_LOADER_UPGRADEABLE_ID
+        )[0],
         authority: authority.publicKey,
-        tokenProgram: TOKEN_PROGRAM_ID,
       })
       .rpc();
 
-    const toAtaAccount = await provider.connection.getTokenAccountBalance(
-      toAta
+  /** Sends whole tokens from the authority through Token-2022 and the hook. */
+  const transfer = async (token: Token, to: PublicKey, amount: number) => {
+    const instruction = await createTransferCheckedWithTransferHookInstruction(
+      provider.connection,
+      token.ata,
+      token.mint,
+      to,
+      authority.publicKey,
+      amount * 10 ** DECIMALS,
+      DECIMALS,
+      [],
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    return provider.sendAndConfirm(new Transaction().add(instruction));
+  };
+
+  /** Fails unless `estimate_budget` covers the units `signature` used. */
+  const expectWithinBudget = async (
+    signature: string,
+    kind: Parameters<typeof program.methods.estimateBudget>[0],
+    itemCount: number
+  ) => {
+    const { blockhash, lastValidBlockHeight } =
+      await provider.connection.getLatestBlockhash();
+    await provider.connection.confirmTransaction(
+      { signature, blockhash, lastValidBlockHeight },
+      "confirmed"
+    );
+    const { meta } = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const estimate = await program.methods
+      .estimateBudget(kind, itemCount)
+      .view();
+    assert.isAtMost(
+      meta.computeUnitsConsumed,
+      estimate,
+      `${Object.keys(kind)[0]} outgrew its estimate`
     );
-    assert.equal(toAtaAccount.value.amount, "50");
+  };
+
+  /** The receiver lookups a mint-based flow checks for `destination`. */
+  const receiverChecks = (
+    token: Token,
+    owner: PublicKey,
+    destination: PublicKey
+  ) => ({
+    blacklistEntry: pda(
+      Buffer.from("blacklist"),
+      token.mint.toBuffer(),
+      owner.toBuffer()
+    ),
+    whitelist: pda(
+      Buffer.from("whitelist"),
+      token.mint.toBuffer(),
+      Buffer.from([owner.toBuffer()[0]])
+    ),
+    tokenAccountEntry: pda(
+      Buffer.from("whitelisted_account"),
+      token.mint.toBuffer(),
+      destination.toBuffer()
+    ),
+    whitelistEntry: pda(
+      Buffer.from("wl"),
+      token.mint.toBuffer(),
+      owner.toBuffer()
+    ),
+    credential: pda(
+      Buffer.from("credential"),
+      token.mint.toBuffer(),
+      owner.toBuffer()
+    ),
+    factoryWhitelistEntry: pda(
+      Buffer.from("factory_wl"),
+      factory.toBuffer(),
+      owner.toBuffer()
+    ),
+    nftPass: pda(
+      Buffer.from("nft_pass"),
+      token.mint.toBuffer(),
+      owner.toBuffer()
+    ),
+    nftPassAccount: pda(
+      Buffer.from("nft_pass_account"),
+      token.mint.toBuffer(),
+      owner.toBuffer()
+    ),
   });
 
-  it("Mints tokens", async () => {
-    const factoryAccount = await program.account.tokenFactory.fetch(factory);
-    const tokenCount = factoryAccount.tokenCount.toNumber() - 1;
+  before(async () => {
+    if (!(await program.account.programConfig.fetchNullable(programConfig))) {
+      await program.methods
+        .initializeProgramConfig(true)
+        .accountsPartial({
+          programConfig,
+          program: program.programId,
+          programData: PublicKey.findProgramAddressSync(
+            [program.programId.toBuffer()],
+            BPF_LOADER_UPGRADEABLE_ID
+          )[0],
+          authority: authority.publicKey,
+        })
+        .rpc();
+    }
+    if (!(await program.account.protocolStats.fetchNullable(protocolStats))) {
+      await program.methods
+        .initializeProtocolStats()
+        .accountsPartial({ protocolStats, payer: authority.publicKey })
+        .rpc();
+    }
+    if (!(await program.account.tokenFactory.fetchNullable(factory))) {
+      await program.methods
+        .createFactory()
+        .accountsPartial({
+          programConfig,
+          factory,
+          protocolStats,
+          authority: authority.publicKey,
+        })
+        .rpc();
+    }
+  });
 
-    const toAta = await getAssociatedTokenAddress(
-      mint.publicKey,
-      authority.publicKey
-    );
+  describe("tokens", () => {
+    let token: Token;
 
-    const initialSupply = (await program.account.tokenData.fetch(tokenData))
-      .totalSupply;
+    before(async () => {
+      token = await createToken(1000);
+    });
 
-    await program.methods
-      .mintTokens(new anchor.BN(tokenCount), new anchor.BN(100))
-      .accounts({
-        tokenData,
-        mint: mint.publicKey,
-        to: toAta,
-        authority: authority.publicKey,
-        tokenProgram: TOKEN_PROGRAM_ID,
-      })
-      .rpc();
+    it("creates a token with its supply in circulation", async () => {
+      const tokenData = await program.account.tokenData.fetch(token.tokenData);
+      assert.equal(
+        tokenData.authority.toBase58(),
+        authority.publicKey.toBase58()
+      );
+      assert.equal(tokenData.mint.toBase58(), token.mint.toBase58());
+      assert.equal(tokenData.whitelistMint.toBase58(), token.mint.toBase58());
+      assert.equal(tokenData.factory.toBase58(), factory.toBase58());
+      assert.equal(tokenData.totalSupply.toNumber(), 1000);
+      assert.equal(tokenData.circulatingSupply.toNumber(), 1000);
+      assert.equal(tokenData.decimals, DECIMALS);
+      assert.isFalse(tokenData.isPaused);
+      assert.isFalse(tokenData.isMintingPaused);
+      assert.equal(tokenData.name, "Potter Test");
+      assert.equal(tokenData.symbol, "POT");
+      assert.equal(await balanceOf(token.ata), whole(1000));
+
+      const page = await program.account.whitelist.fetch(
+        pda(
+          Buffer.from("whitelist"),
+          token.mint.toBuffer(),
+          Buffer.from([token.defaultPage])
+        )
+      );
+      assert.equal(
+        page.addresses[0].toBase58(),
+        authority.publicKey.toBase58()
+      );
+    });
+
+    it("sizes a whitelist page to the addresses added", async () => {
+      const whitelist = pda(
+        Buffer.from("whitelist"),
+        token.mint.toBuffer(),
+        Buffer.from([token.defaultPage])
+      );
+      const addresses = Array.from({ length: 12 }, () => {
+        const key = Keypair.generate().publicKey.toBuffer();
+        key[0] = token.defaultPage;
+        return new PublicKey(key);
+      });
+      await program.methods
+        .addToWhitelist(token.defaultPage, addresses)
+        .accountsPartial({
+          programConfig,
+          tokenData: token.tokenData,
+          whitelist,
+          authority: authority.publicKey,
+          role: null,
+        })
+        .rpc();
+      const page = await program.account.whitelist.fetch(whitelist);
+      assert.equal(page.addresses.length, 13);
+      assert.equal(
+        (await provider.connection.getAccountInfo(whitelist)).data.length,
+        8 + 4 + 13 * 32 + 4 + page.suspended.length * 32
+      );
+    });
+
+    it("mints and burns whole tokens", async () => {
+      await program.methods
+        .mintTokens(new anchor.BN(100), null)
+        .accountsPartial({
+          programConfig,
+          tokenData: token.tokenData,
+          mint: token.mint,
+          to: token.ata,
+          mintAuthorityPda,
+          idempotencyLog: null,
+          protocolStats,
+          authority: authority.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          role: null,
+          allowance: null,
+        })
+        .rpc();
+      await program.methods
+        .burnTokens(new anchor.BN(50))
+        .accountsPartial({
+          programConfig,
+          tokenData: token.tokenData,
+          mint: token.mint,
+          from: token.ata,
+          authority: authority.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+
+      const tokenData = await program.account.tokenData.fetch(token.tokenData);
+      assert.equal(tokenData.totalSupply.toNumber(), 1050);
+      assert.equal(tokenData.circulatingSupply.toNumber(), 1050);
+      assert.equal(await balanceOf(token.ata), whole(1050));
+    });
+
+    it("pauses and unpauses minting", async () => {
+      const toggle = () =>
+        program.methods
+          .pauseMinting(null)
+          .accountsPartial({
+            tokenData: token.tokenData,
+            idempotencyLog: null,
+            hookManifest: null,
+            programConfig,
+            timelock: pda(Buffer.from("timelock"), token.mint.toBuffer()),
+            authority: authority.publicKey,
+            role: null,
+          })
+          .rpc();
+
+      await toggle();
+      assert.isTrue(
+        (await program.account.tokenData.fetch(token.tokenData))
+          .isMintingPaused
+      );
+      await toggle();
+      assert.isFalse(
+        (await program.account.tokenData.fetch(token.tokenData))
+          .isMintingPaused
+      );
+    });
+
+    it("only changes the max supply through the timelock", async () => {
+      await expectError(
+        program.methods
+          .adminExecute({ setMaxSupply: { maxSupply: null } })
+          .accountsPartial({
+            tokenData: token.tokenData,
+            hookManifest: null,
+            programConfig,
+            timelock: pda(Buffer.from("timelock"), token.mint.toBuffer()),
+            authority: authority.publicKey,
+          })
+          .rpc(),
+        "ActionTimelocked"
+      );
+    });
+
+    it("keeps minting through the creator's PDA after a handover", async () => {
+      const handed = await createToken(1000);
+      const successor = await newUser();
+      await program.methods
+        .proposeAuthority(successor.publicKey)
+        .accountsPartial({
+          tokenData: handed.tokenData,
+          hookManifest: null,
+          programConfig,
+          timelock: pda(Buffer.from("timelock"), handed.mint.toBuffer()),
+          authority: authority.publicKey,
+        })
+        .rpc();
+      await program.methods
+        .acceptAuthority()
+        .accountsPartial({
+          programConfig,
+          tokenData: handed.tokenData,
+          newAuthority: successor.publicKey,
+        })
+        .signers([successor])
+        .rpc();
 
-    const newSupply = (await program.account.tokenData.fetch(tokenData))
-      .totalSupply;
-    assert.equal(newSupply.toNumber(), initialSupply.toNumber() + 100);
+      await program.methods
+        .mintTokens(new anchor.BN(10), null)
+        .accountsPartial({
+          programConfig,
+          tokenData: handed.tokenData,
+          mint: handed.mint,
+          to: handed.ata,
+          mintAuthorityPda,
+          idempotencyLog: null,
+          protocolStats,
+          authority: successor.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          role: null,
+          allowance: null,
+        })
+        .signers([successor])
+        .rpc();
+
+      const tokenData = await program.account.tokenData.fetch(handed.tokenData);
+      assert.equal(
+        tokenData.authority.toBase58(),
+        successor.publicKey.toBase58()
+      );
+      assert.equal(
+        tokenData.creator.toBase58(),
+        authority.publicKey.toBase58()
+      );
+      assert.equal(tokenData.totalSupply.toNumber(), 1010);
+      assert.equal(await balanceOf(handed.ata), whole(1010));
+    });
   });
 
-  it("Burns tokens", async () => {
-    const factoryAccount = await program.account.tokenFactory.fetch(factory);
-    const tokenCount = factoryAccount.tokenCount.toNumber() - 1;
+  describe("transfer hook", () => {
+    let token: Token;
+    let listed: PublicKey;
+    let listedAccount: PublicKey;
+    let unlistedAccount: PublicKey;
 
-    const fromAta = await getAssociatedTokenAddress(
-      mint.publicKey,
-      authority.publicKey
-    );
+    before(async () => {
+      token = await createToken(1000);
+      listed = Keypair.generate().publicKey;
+      listedAccount = await openAccount(token, listed);
+      unlistedAccount = await openAccount(token, Keypair.generate().publicKey);
+      await whitelist(token, listed);
+    });
 
-    const initialSupply = (await program.account.tokenData.fetch(tokenData))
-      .totalSupply;
+    it("lets a whitelisted owner receive", async () => {
+      await transfer(token, listedAccount, 10);
+      assert.equal(await balanceOf(listedAccount), whole(10));
+    });
 
-    await program.methods
-      .burnTokens(new anchor.BN(tokenCount), new anchor.BN(50))
-      .accounts({
-        tokenData,
-        mint: mint.publicKey,
-        from: fromAta,
-        authority: authority.publicKey,
-        tokenProgram: TOKEN_PROGRAM_ID,
-      })
-      .rpc();
+    it("rejects an owner that isn't whitelisted", async () => {
+      await expectError(
+        transfer(token, unlistedAccount, 10),
+        "AddressNotWhitelisted"
+      );
+    });
+
+    it("rejects every transfer while the token is paused", async () => {
+      await pauseToken(token);
+      await expectError(transfer(token, listedAccount, 10), "TokenPaused");
+      await pauseToken(token);
+      await transfer(token, listedAccount, 10);
+    });
+
+    it("rejects a blacklisted owner even when whitelisted", async () => {
+      const owner = Keypair.generate().publicKey;
+      const account = await openAccount(token, owner);
+      await whitelist(token, owner);
+      await transfer(token, account, 1);
 
-    const newSupply = (await program.account.tokenData.fetch(tokenData))
-      .totalSupply;
-    assert.equal(newSupply.toNumber(), initialSupply.toNumber() - 50);
+      await program.methods
+        .blacklistAddress(owner)
+        .accountsPartial({
+          programConfig,
+          tokenData: token.tokenData,
+          entry: pda(
+            Buffer.from("blacklist"),
+            token.mint.toBuffer(),
+            owner.toBuffer()
+          ),
+          authority: authority.publicKey,
+          role: null,
+        })
+        .rpc();
+      await expectError(transfer(token, account, 1), "AddressBlacklisted");
+    });
+
+    it("rejects transfers below the minimum", async () => {
+      const setMinimum = (amount: string) =>
+        program.methods
+          .setMinTransferAmount(new anchor.BN(amount))
+          .accountsPartial({
+            tokenData: token.tokenData,
+            hookManifest: null,
+            programConfig,
+            authority: authority.publicKey,
+          })
+          .rpc();
+
+      await setMinimum(whole(5));
+      await expectError(
+        transfer(token, listedAccount, 4),
+        "TransferBelowMinimum"
+      );
+      await transfer(token, listedAccount, 5);
+      await setMinimum("0");
+    });
   });
 
-  it("Pauses and unpauses minting", async () => {
-    const factoryAccount = await program.account.tokenFactory.fetch(factory);
-    const tokenCount = factoryAccount.tokenCount.toNumber() - 1;
+  describe("swaps", () => {
+    let from: Token;
+    let to: Token;
+    let user: Keypair;
+    let userFrom: PublicKey;
+    let userTo: PublicKey;
 
-    // Pause
-    await program.methods
-      .pauseMinting(new anchor.BN(tokenCount))
-      .accounts({
-        tokenData,
-        authority: authority.publicKey,
-      })
-      .rpc();
+    const setRate = (numerator: number, denominator: number) =>
+      program.methods
+        .setSwapRate(new anchor.BN(numerator), new anchor.BN(denominator))
+        .accountsPartial({
+          programConfig,
+          fromTokenData: from.tokenData,
+          toTokenData: to.tokenData,
+          swapRate: pda(
+            Buffer.from("swap_rate"),
+            from.mint.toBuffer(),
+            to.mint.toBuffer()
+          ),
+          authority: authority.publicKey,
+        })
+        .rpc();
 
-    let tokenDataAccount = await program.account.tokenData.fetch(tokenData);
-    assert.isTrue(tokenDataAccount.isMintingPaused);
+    const swap = (amount: number) =>
+      program.methods
+        .swapTokens(new anchor.BN(amount))
+        .accountsPartial({
+          programConfig,
+          swapRate: pda(
+            Buffer.from("swap_rate"),
+            from.mint.toBuffer(),
+            to.mint.toBuffer()
+          ),
+          fromTokenData: from.tokenData,
+          toTokenData: to.tokenData,
+          fromMint: from.mint,
+          toMint: to.mint,
+          from: userFrom,
+          to: userTo,
+          receiver: receiverChecks(to, user.publicKey, userTo),
+          mintAuthorityPda,
+          protocolStats,
+          user: user.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([user])
+        .rpc();
 
-    // Unpause
-    await program.methods
-      .pauseMinting(new anchor.BN(tokenCount))
-      .accounts({
-        tokenData,
+    before(async () => {
+      from = await createToken(1000);
+      to = await createToken(1000);
+      user = await newUser();
+      userFrom = await openAccount(from, user.publicKey);
+      userTo = await openAccount(to, user.publicKey);
+      await whitelist(from, user.publicKey);
+      await transfer(from, userFrom, 100);
+      await setRate(2, 1);
+    });
+
+    it("holds the target's receiver rules", async () => {
+      await expectError(swap(10), "AddressNotWhitelisted");
+    });
+
+    it("burns the source and mints the target at the rate", async () => {
+      await whitelist(to, user.publicKey);
+      await swap(10);
+
+      assert.equal(await balanceOf(userFrom), whole(90));
+      assert.equal(await balanceOf(userTo), whole(20));
+      const fromData = await program.account.tokenData.fetch(from.tokenData);
+      const toData = await program.account.tokenData.fetch(to.tokenData);
+      assert.equal(fromData.totalSupply.toNumber(), 990);
+      assert.equal(fromData.circulatingSupply.toNumber(), 990);
+      assert.equal(toData.totalSupply.toNumber(), 1020);
+      assert.equal(toData.circulatingSupply.toNumber(), 1020);
+    });
+
+    it("refuses a pair whose rate was zeroed", async () => {
+      await setRate(0, 1);
+      await expectError(swap(10), "SwapDisabled");
+      assert.equal(await balanceOf(userFrom), whole(90));
+    });
+
+    it("stays within its compute budget estimate", async () => {
+      await setRate(2, 1);
+      await expectWithinBudget(await swap(1), { swapTokens: {} }, 0);
+    });
+  });
+
+  describe("vesting", () => {
+    let token: Token;
+    let beneficiary: Keypair;
+    let beneficiaryAccount: PublicKey;
+
+    const scheduleOf = (owner: PublicKey) =>
+      pda(Buffer.from("vesting"), token.mint.toBuffer(), owner.toBuffer());
+    const vaultOf = (owner: PublicKey) =>
+      pda(Buffer.from("vesting_vault"), scheduleOf(owner).toBuffer());
+
+    const createVesting = (
+      owner: PublicKey,
+      amount: number,
+      startAt: number,
+      duration: number
+    ) =>
+      program.methods
+        .createVesting(
+          owner,
+          new anchor.BN(amount),
+          new anchor.BN(startAt),
+          new anchor.BN(0),
+          new anchor.BN(duration)
+        )
+        .accountsPartial({
+          programConfig,
+          tokenData: token.tokenData,
+          mint: token.mint,
+          vestingSchedule: scheduleOf(owner),
+          vault: vaultOf(owner),
+          source: token.ata,
+          mintAuthorityPda,
+          authority: authority.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+
+    const claim = (destination: PublicKey) =>
+      program.methods
+        .claimVested()
+        .accountsPartial({
+          programConfig,
+          tokenData: token.tokenData,
+          mint: token.mint,
+          vestingSchedule: scheduleOf(beneficiary.publicKey),
+          vault: vaultOf(beneficiary.publicKey),
+          destination,
+          receiver: receiverChecks(token, beneficiary.publicKey, destination),
+          mintAuthorityPda,
+          beneficiary: beneficiary.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([beneficiary])
+        .rpc();
+
+    // Started long enough ago that the whole amount has vested, so the
+    // flow doesn't depend on the validator's clock
+    const vestedStart = () => Math.floor(Date.now() / 1000) - 86_400;
+
+    before(async () => {
+      token = await createToken(1000);
+      beneficiary = await newUser();
+      beneficiaryAccount = await openAccount(token, beneficiary.publicKey);
+    });
+
+    it("moves the amount into the schedule's vault", async () => {
+      await createVesting(beneficiary.publicKey, 100, vestedStart(), 3600);
+
+      assert.equal(await balanceOf(token.ata), whole(900));
+      assert.equal(
+        await balanceOf(vaultOf(beneficiary.publicKey)),
+        whole(100)
+      );
+      const schedule = await program.account.vestingSchedule.fetch(
+        scheduleOf(beneficiary.publicKey)
+      );
+      assert.equal(
+        schedule.beneficiary.toBase58(),
+        beneficiary.publicKey.toBase58()
+      );
+      assert.equal(schedule.total.toNumber(), 100);
+      assert.equal(schedule.released.toNumber(), 0);
+      const tokenData = await program.account.tokenData.fetch(token.tokenData);
+      assert.equal(tokenData.totalSupply.toNumber(), 1000);
+      assert.equal(tokenData.circulatingSupply.toNumber(), 900);
+    });
+
+    it("only pays out to the beneficiary's own account", async () => {
+      await expectError(claim(token.ata), "Unauthorized");
+    });
+
+    it("holds the beneficiary to the receiver rules", async () => {
+      await expectError(claim(beneficiaryAccount), "AddressNotWhitelisted");
+    });
+
+    it("pays out what has vested once", async () => {
+      await whitelist(token, beneficiary.publicKey);
+      await claim(beneficiaryAccount);
+
+      assert.equal(await balanceOf(beneficiaryAccount), whole(100));
+      assert.equal(
+        await balanceOf(vaultOf(beneficiary.publicKey)),
+        "0"
+      );
+      const tokenData = await program.account.tokenData.fetch(token.tokenData);
+      assert.equal(tokenData.circulatingSupply.toNumber(), 1000);
+
+      await expectError(claim(beneficiaryAccount), "NothingToClaim");
+    });
+
+    it("returns the unvested remainder on revoke", async () => {
+      const owner = Keypair.generate().publicKey;
+      const notYetStarted = Math.floor(Date.now() / 1000) + 86_400;
+      await createVesting(owner, 50, notYetStarted, 3600);
+      assert.equal(await balanceOf(token.ata), whole(850));
+
+      await program.methods
+        .revokeVesting()
+        .accountsPartial({
+          programConfig,
+          tokenData: token.tokenData,
+          mint: token.mint,
+          vestingSchedule: scheduleOf(owner),
+          vault: vaultOf(owner),
+          destination: token.ata,
+          mintAuthorityPda,
+          authority: authority.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+
+      assert.equal(await balanceOf(token.ata), whole(900));
+      assert.equal(await balanceOf(vaultOf(owner)), "0");
+      const schedule = await program.account.vestingSchedule.fetch(
+        scheduleOf(owner)
+      );
+      assert.equal(schedule.total.toNumber(), 0);
+      assert.equal(schedule.duration.toNumber(), 0);
+    });
+  });
+
+  describe("treasury", () => {
+    let token: Token;
+    let treasury: PublicKey;
+    let treasuryAuthority: PublicKey;
+
+    const supplyOf = async () => {
+      const tokenData = await program.account.tokenData.fetch(token.tokenData);
+      return [
+        tokenData.totalSupply.toNumber(),
+        tokenData.circulatingSupply.toNumber(),
+      ];
+    };
+
+    const deposit = (amount: number) =>
+      program.methods
+        .depositToTreasury(new anchor.BN(amount))
+        .accountsPartial({
+          programConfig,
+          tokenData: token.tokenData,
+          mint: token.mint,
+          from: token.ata,
+          treasuryAuthority,
+          treasury,
+          mintAuthorityPda,
+          depositor: authority.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+
+    const withdraw = (
+      amount: number,
+      owner: PublicKey,
+      destination: PublicKey
+    ) =>
+      program.methods
+        .withdrawFromTreasury(new anchor.BN(amount))
+        .accountsPartial({
+          programConfig,
+          tokenData: token.tokenData,
+          mint: token.mint,
+          treasuryAuthority,
+          treasury,
+          destination,
+          receiver: receiverChecks(token, owner, destination),
+          mintAuthorityPda,
+          authority: authority.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+
+    before(async () => {
+      token = await createToken(1000);
+      treasury = pda(Buffer.from("treasury"), token.mint.toBuffer());
+      treasuryAuthority = pda(
+        Buffer.from("treasury_authority"),
+        token.mint.toBuffer()
+      );
+    });
+
+    it("takes deposits out of circulation", async () => {
+      await deposit(100);
+
+      assert.equal(await balanceOf(token.ata), whole(900));
+      assert.equal(await balanceOf(treasury), whole(100));
+      assert.deepEqual(await supplyOf(), [1000, 900]);
+    });
+
+    it("won't lock the supply while the treasury is funded", async () => {
+      await expectError(
+        program.methods
+          .lockSupply()
+          .accountsPartial({
+            programConfig,
+            tokenData: token.tokenData,
+            mint: token.mint,
+            mintAuthorityPda,
+            treasury,
+            insuranceVault: pda(
+              Buffer.from("insurance_vault"),
+              token.mint.toBuffer()
+            ),
+            authority: authority.publicKey,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+          })
+          .rpc(),
+        "VaultsNotEmpty"
+      );
+    });
+
+    it("puts withdrawals back into circulation", async () => {
+      await withdraw(30, authority.publicKey, token.ata);
+
+      assert.equal(await balanceOf(token.ata), whole(930));
+      assert.equal(await balanceOf(treasury), whole(70));
+      assert.deepEqual(await supplyOf(), [1000, 930]);
+    });
+
+    it("holds the destination to the receiver rules", async () => {
+      const owner = Keypair.generate().publicKey;
+      const destination = await openAccount(token, owner);
+      await expectError(
+        withdraw(10, owner, destination),
+        "AddressNotWhitelisted"
+      );
+      assert.equal(await balanceOf(treasury), whole(70));
+    });
+
+    it("refuses withdrawals while minting is halted", async () => {
+      await setDisabledFeatures(FEATURE_MINTING);
+      try {
+        await expectError(
+          withdraw(10, authority.publicKey, token.ata),
+          "FeatureDisabled"
+        );
+      } finally {
+        await setDisabledFeatures(0);
+      }
+      assert.equal(await balanceOf(treasury), whole(70));
+    });
+
+    it("burns treasury tokens without touching circulation", async () => {
+      await program.methods
+        .buybackAndBurn(new anchor.BN(20))
+        .accountsPartial({
+          programConfig,
+          tokenData: token.tokenData,
+          mint: token.mint,
+          treasuryAuthority,
+          treasury,
+          authority: authority.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+
+      assert.equal(await balanceOf(treasury), whole(50));
+      assert.deepEqual(await supplyOf(), [980, 930]);
+    });
+
+    it("refuses deposits while the token is paused", async () => {
+      await pauseToken(token);
+      await expectError(deposit(10), "TokenPaused");
+      await pauseToken(token);
+      await deposit(10);
+      assert.equal(await balanceOf(treasury), whole(60));
+    });
+  });
+
+  describe("insurance", () => {
+    let token: Token;
+    let insuranceFund: PublicKey;
+
+    before(async () => {
+      token = await createToken(1000);
+      insuranceFund = pda(Buffer.from("insurance"), token.mint.toBuffer());
+      await program.methods
+        .createInsuranceFund(authority.publicKey, 1_000, new anchor.BN(0))
+        .accountsPartial({
+          programConfig,
+          tokenData: token.tokenData,
+          mint: token.mint,
+          insuranceFund,
+          vault: pda(Buffer.from("insurance_vault"), token.mint.toBuffer()),
+          mintAuthorityPda,
+          authority: authority.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+    });
+
+    it("holds the recipient to the receiver rules", async () => {
+      const owner = Keypair.generate().publicKey;
+      const recipient = await openAccount(token, owner);
+      const claimId = (
+        await program.account.insuranceFund.fetch(insuranceFund)
+      ).nextClaimId;
+      const payoutClaim = pda(
+        Buffer.from("payout_claim"),
+        token.mint.toBuffer(),
+        claimId.toArrayLike(Buffer, "le", 8)
+      );
+      await program.methods
+        .payoutClaim(recipient, new anchor.BN(1), Array(32).fill(0))
+        .accountsPartial({
+          programConfig,
+          tokenData: token.tokenData,
+          insuranceFund,
+          payoutClaim,
+          authority: authority.publicKey,
+        })
+        .rpc();
+
+      await expectError(
+        program.methods
+          .executePayoutClaim(claimId)
+          .accountsPartial({
+            programConfig,
+            tokenData: token.tokenData,
+            mint: token.mint,
+            insuranceFund,
+            vault: pda(Buffer.from("insurance_vault"), token.mint.toBuffer()),
+            payoutClaim,
+            recipient,
+            receiver: receiverChecks(token, owner, recipient),
+            mintAuthorityPda,
+            authority: authority.publicKey,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+          })
+          .rpc(),
+        "AddressNotWhitelisted"
+      );
+    });
+  });
+
+  describe("vaults", () => {
+    const name = "rewards";
+    let token: Token;
+    let vault: PublicKey;
+    let vaultTokens: PublicKey;
+    let manager: Keypair;
+
+    const withdraw = (
+      amount: number,
+      signer: Keypair | null,
+      role: PublicKey | null = null
+    ) => {
+      const call = program.methods
+        .vaultWithdraw(name, new anchor.BN(amount))
+        .accountsPartial({
+          programConfig,
+          tokenData: token.tokenData,
+          mint: token.mint,
+          vault,
+          vaultTokens,
+          destination: token.ata,
+          receiver: receiverChecks(token, authority.publicKey, token.ata),
+          mintAuthorityPda,
+          authority: signer?.publicKey ?? authority.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          role,
+        });
+      return signer ? call.signers([signer]).rpc() : call.rpc();
+    };
+
+    before(async () => {
+      token = await createToken(1000);
+      vault = pda(
+        Buffer.from("vault"),
+        token.tokenData.toBuffer(),
+        Buffer.from(name)
+      );
+      vaultTokens = pda(Buffer.from("vault_tokens"), vault.toBuffer());
+      manager = await newUser();
+
+      await program.methods
+        .createVault(name)
+        .accountsPartial({
+          programConfig,
+          tokenData: token.tokenData,
+          mint: token.mint,
+          vault,
+          vaultTokens,
+          mintAuthorityPda,
+          authority: authority.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+      await program.methods
+        .vaultDeposit(name, new anchor.BN(100))
+        .accountsPartial({
+          programConfig,
+          tokenData: token.tokenData,
+          mint: token.mint,
+          from: token.ata,
+          vault,
+          vaultTokens,
+          mintAuthorityPda,
+          depositor: authority.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+    });
+
+    it("takes deposits out of circulation", async () => {
+      assert.equal(await balanceOf(vaultTokens), whole(100));
+      const tokenData = await program.account.tokenData.fetch(token.tokenData);
+      assert.equal(tokenData.circulatingSupply.toNumber(), 900);
+    });
+
+    it("only pays out to the authority or a vault manager", async () => {
+      const role = pda(
+        Buffer.from("role"),
+        token.tokenData.toBuffer(),
+        manager.publicKey.toBuffer(),
+        Buffer.from([3])
+      );
+      await expectError(withdraw(10, manager), "Unauthorized");
+
+      await program.methods
+        .grantRole(manager.publicKey, { vaultManager: {} })
+        .accountsPartial({
+          programConfig,
+          tokenData: token.tokenData,
+          grant: role,
+          authority: authority.publicKey,
+        })
+        .rpc();
+      await withdraw(10, manager, role);
+      await withdraw(20, null);
+
+      assert.equal(await balanceOf(vaultTokens), whole(70));
+      const tokenData = await program.account.tokenData.fetch(token.tokenData);
+      assert.equal(tokenData.circulatingSupply.toNumber(), 930);
+    });
+
+    it("burns vault holdings without touching circulation", async () => {
+      await program.methods
+        .burnFromVault(name, new anchor.BN(30))
+        .accountsPartial({
+          programConfig,
+          tokenData: token.tokenData,
+          mint: token.mint,
+          vault,
+          vaultTokens,
+          authority: authority.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+
+      assert.equal(await balanceOf(vaultTokens), whole(40));
+      const tokenData = await program.account.tokenData.fetch(token.tokenData);
+      assert.deepEqual(
+        [
+          tokenData.totalSupply.toNumber(),
+          tokenData.circulatingSupply.toNumber(),
+        ],
+        [970, 930]
+      );
+    });
+  });
+
+  describe("program pause", () => {
+    let token: Token;
+    let beneficiary: Keypair;
+    let beneficiaryAccount: PublicKey;
+    let holder: PublicKey;
+
+    const treasuryAuthorityOf = (token: Token) =>
+      pda(Buffer.from("treasury_authority"), token.mint.toBuffer());
+    const treasuryOf = (token: Token) =>
+      pda(Buffer.from("treasury"), token.mint.toBuffer());
+    const scheduleOf = (owner: PublicKey) =>
+      pda(Buffer.from("vesting"), token.mint.toBuffer(), owner.toBuffer());
+    const vaultOf = (owner: PublicKey) =>
+      pda(Buffer.from("vesting_vault"), scheduleOf(owner).toBuffer());
+    const entryOf = (owner: PublicKey) =>
+      pda(Buffer.from("wl"), token.mint.toBuffer(), owner.toBuffer());
+
+    // Every handler below changes state, so each has to stop while the
+    // program is paused
+    const handlers: [string, () => Promise<unknown>][] = [
+      [
+        "claim_vested",
+        () =>
+          program.methods
+            .claimVested()
+            .accountsPartial({
+              programConfig,
+              tokenData: token.tokenData,
+              mint: token.mint,
+              vestingSchedule: scheduleOf(beneficiary.publicKey),
+              vault: vaultOf(beneficiary.publicKey),
+              destination: beneficiaryAccount,
+              receiver: receiverChecks(
+                token,
+                beneficiary.publicKey,
+                beneficiaryAccount
+              ),
+              mintAuthorityPda,
+              beneficiary: beneficiary.publicKey,
+              tokenProgram: TOKEN_2022_PROGRAM_ID,
+            })
+            .signers([beneficiary])
+            .rpc(),
+      ],
+      [
+        "withdraw_from_treasury",
+        () =>
+          program.methods
+            .withdrawFromTreasury(new anchor.BN(1))
+            .accountsPartial({
+              programConfig,
+              tokenData: token.tokenData,
+              mint: token.mint,
+              treasuryAuthority: treasuryAuthorityOf(token),
+              treasury: treasuryOf(token),
+              destination: token.ata,
+              receiver: receiverChecks(token, authority.publicKey, token.ata),
+              mintAuthorityPda,
+              authority: authority.publicKey,
+              tokenProgram: TOKEN_2022_PROGRAM_ID,
+            })
+            .rpc(),
+      ],
+      [
+        "clawback",
+        () =>
+          program.methods
+            .clawback(new anchor.BN(1))
+            .accountsPartial({
+              programConfig,
+              tokenData: token.tokenData,
+              mint: token.mint,
+              from: beneficiaryAccount,
+              destination: null,
+              mintAuthorityPda,
+              authority: authority.publicKey,
+              tokenProgram: TOKEN_2022_PROGRAM_ID,
+            })
+            .rpc(),
+      ],
+      [
+        "burn_tokens",
+        () =>
+          program.methods
+            .burnTokens(new anchor.BN(1))
+            .accountsPartial({
+              programConfig,
+              tokenData: token.tokenData,
+              mint: token.mint,
+              from: token.ata,
+              authority: authority.publicKey,
+              tokenProgram: TOKEN_2022_PROGRAM_ID,
+            })
+            .rpc(),
+      ],
+      [
+        "add_whitelist_entry",
+        () => whitelist(token, Keypair.generate().publicKey),
+      ],
+      [
+        "remove_whitelist_entry",
+        () =>
+          program.methods
+            .removeWhitelistEntry(holder)
+            .accountsPartial({
+              programConfig,
+              tokenData: token.tokenData,
+              entry: entryOf(holder),
+              authority: authority.publicKey,
+              role: null,
+            })
+            .rpc(),
+      ],
+      [
+        "blacklist_address",
+        () =>
+          program.methods
+            .blacklistAddress(holder)
+            .accountsPartial({
+              programConfig,
+              tokenData: token.tokenData,
+              entry: pda(
+                Buffer.from("blacklist"),
+                token.mint.toBuffer(),
+                holder.toBuffer()
+              ),
+              authority: authority.publicKey,
+              role: null,
+            })
+            .rpc(),
+      ],
+      [
+        "propose_authority",
+        () =>
+          program.methods
+            .proposeAuthority(beneficiary.publicKey)
+            .accountsPartial({
+              tokenData: token.tokenData,
+              hookManifest: null,
+              programConfig,
+              timelock: pda(Buffer.from("timelock"), token.mint.toBuffer()),
+              authority: authority.publicKey,
+            })
+            .rpc(),
+      ],
+      [
+        "accept_authority",
+        () =>
+          program.methods
+            .acceptAuthority()
+            .accountsPartial({
+              programConfig,
+              tokenData: token.tokenData,
+              newAuthority: beneficiary.publicKey,
+            })
+            .signers([beneficiary])
+            .rpc(),
+      ],
+      [
+        "update_metadata",
+        () =>
+          program.methods
+            .updateMetadata(
+              "Potter Paused",
+              "POT",
+              "https://example.com/pot.json"
+            )
+            .accountsPartial({
+              programConfig,
+              tokenData: token.tokenData,
+              mint: token.mint,
+              metadata: PublicKey.findProgramAddressSync(
+                [
+                  Buffer.from("metadata"),
+                  MPL_TOKEN_METADATA_ID.toBuffer(),
+                  token.mint.toBuffer(),
+                ],
+                MPL_TOKEN_METADATA_ID
+              )[0],
+              mintAuthorityPda,
+              authority: authority.publicKey,
+              sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+              tokenMetadataProgram: MPL_TOKEN_METADATA_ID,
+            })
+            .rpc(),
+      ],
+      [
+        "withdraw_withheld_fees_to_treasury",
+        () =>
+          program.methods
+            .withdrawWithheldFeesToTreasury()
+            .accountsPartial({
+              programConfig,
+              tokenData: token.tokenData,
+              mint: token.mint,
+              treasury: treasuryOf(token),
+              mintAuthorityPda,
+              insuranceFund: null,
+              insuranceVault: null,
+              authority: authority.publicKey,
+              tokenProgram: TOKEN_2022_PROGRAM_ID,
+            })
+            .rpc(),
+      ],
+      [
+        "recover_dormant_balance",
+        () =>
+          program.methods
+            .recoverDormantBalance()
+            .accountsPartial({
+              programConfig,
+              tokenData: token.tokenData,
+              mint: token.mint,
+              policy: pda(Buffer.from("escheatment"), token.mint.toBuffer()),
+              tokenAccount: token.ata,
+              custody: token.ata,
+              notice: pda(Buffer.from("dormancy"), token.ata.toBuffer()),
+              mintAuthorityPda,
+              authority: authority.publicKey,
+              tokenProgram: TOKEN_2022_PROGRAM_ID,
+            })
+            .rpc(),
+      ],
+      [
+        "close_token",
+        () =>
+          program.methods
+            .closeToken(Buffer.from([token.defaultPage]))
+            .accountsPartial({
+              programConfig,
+              tokenData: token.tokenData,
+              mint: token.mint,
+              whitelistSourceTokenData: null,
+              extraAccountMetaList: pda(
+                Buffer.from("extra-account-metas"),
+                token.mint.toBuffer()
+              ),
+              treasuryAuthority: treasuryAuthorityOf(token),
+              treasury: treasuryOf(token),
+              mintAuthorityPda,
+              authority: authority.publicKey,
+              tokenProgram: TOKEN_2022_PROGRAM_ID,
+            })
+            .rpc(),
+      ],
+    ];
+
+    before(async () => {
+      token = await createToken(1000);
+      beneficiary = await newUser();
+      beneficiaryAccount = await openAccount(token, beneficiary.publicKey);
+      holder = Keypair.generate().publicKey;
+      await whitelist(token, holder);
+      await program.methods
+        .createVesting(
+          beneficiary.publicKey,
+          new anchor.BN(100),
+          new anchor.BN(Math.floor(Date.now() / 1000) - 86_400),
+          new anchor.BN(0),
+          new anchor.BN(3600)
+        )
+        .accountsPartial({
+          programConfig,
+          tokenData: token.tokenData,
+          mint: token.mint,
+          vestingSchedule: scheduleOf(beneficiary.publicKey),
+          vault: vaultOf(beneficiary.publicKey),
+          source: token.ata,
+          mintAuthorityPda,
+          authority: authority.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+
+      // Recovery only reaches its pause check once a notice is on file
+      await program.methods
+        .setEscheatmentPolicy(new anchor.BN(1), new anchor.BN(1))
+        .accountsPartial({
+          programConfig,
+          tokenData: token.tokenData,
+          policy: pda(Buffer.from("escheatment"), token.mint.toBuffer()),
+          custody: token.ata,
+          authority: authority.publicKey,
+        })
+        .rpc();
+      await program.methods
+        .openActivityRecord()
+        .accountsPartial({
+          programConfig,
+          tokenAccount: token.ata,
+          activity: pda(Buffer.from("activity"), token.ata.toBuffer()),
+          payer: authority.publicKey,
+        })
+        .rpc();
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+      await program.methods
+        .fileDormancyNotice()
+        .accountsPartial({
+          programConfig,
+          tokenData: token.tokenData,
+          policy: pda(Buffer.from("escheatment"), token.mint.toBuffer()),
+          tokenAccount: token.ata,
+          activity: pda(Buffer.from("activity"), token.ata.toBuffer()),
+          notice: pda(Buffer.from("dormancy"), token.ata.toBuffer()),
+          authority: authority.publicKey,
+        })
+        .rpc();
+
+      await program.methods
+        .programPause()
+        .accountsPartial({
+          programConfig,
+          potterPotter: program.programId,
+          programData: PublicKey.findProgramAddressSync(
+            [program.programId.toBuffer()],
+            BPF_LOADER_UPGRADEABLE_ID
+          )[0],
+          authority: authority.publicKey,
+        })
+        .rpc();
+    });
+
+    after(async () => {
+      for (const phase of [
+        { adminOps: {} },
+        { transfers: {} },
+        { minting: {} },
+        { sales: {} },
+      ]) {
+        await programResume(phase);
+      }
+    });
+
+    for (const [name, call] of handlers) {
+      it(`refuses ${name}`, async () => {
+        await expectError(call(), "FeatureDisabled");
+      });
+    }
+
+    it("keeps the config authority from clearing the mask", async () => {
+      await expectError(setDisabledFeatures(0), "ProgramPaused");
+    });
+  });
+
+  describe("program resume", () => {
+    it("leaves a config authority mask alone", async () => {
+      await setDisabledFeatures(FEATURE_SWAPS);
+      try {
+        await expectError(
+          programResume({ adminOps: {} }),
+          "ProgramNotPaused"
+        );
+      } finally {
+        await setDisabledFeatures(0);
+      }
+    });
+  });
+
+  describe("compute budget", () => {
+    let token: Token;
+
+    /** `count` fresh owners on the token's default whitelist page. */
+    const pageAddresses = (count: number) =>
+      Array.from({ length: count }, () => {
+        const key = Keypair.generate().publicKey.toBuffer();
+        key[0] = token.defaultPage;
+        return new PublicKey(key);
+      });
+
+    const pageOf = () =>
+      pda(
+        Buffer.from("whitelist"),
+        token.mint.toBuffer(),
+        Buffer.from([token.defaultPage])
+      );
+
+    /** Mints one token to each of `accounts`, owned by `owners`. */
+    const mintToMany = (accounts: PublicKey[], owners: PublicKey[]) => {
+      const pairs = accounts.map((account, i) => [
+        { pubkey: account, isSigner: false, isWritable: true },
+        {
+          pubkey: pda(
+            Buffer.from("opt_out"),
+            token.mint.toBuffer(),
+            owners[i].toBuffer()
+          ),
+          isSigner: false,
+          isWritable: false,
+        },
+      ]);
+      return program.methods
+        .mintTokensToMany(accounts.map(() => new anchor.BN(1)), null)
+        .accountsPartial({
+          programConfig,
+          tokenData: token.tokenData,
+          mint: token.mint,
+          mintAuthorityPda,
+          idempotencyLog: null,
+          protocolStats,
+          authority: authority.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          role: null,
+          allowance: null,
+        })
+        .remainingAccounts([].concat(...pairs))
+        .rpc();
+    };
+
+    before(async () => {
+      token = await createToken(1000);
+    });
+
+    it("covers creating a token", async () => {
+      await expectWithinBudget(token.signature, { createToken: {} }, 0);
+    });
+
+    it("covers minting and burning", async () => {
+      const minted = await program.methods
+        .mintTokens(new anchor.BN(10), null)
+        .accountsPartial({
+          programConfig,
+          tokenData: token.tokenData,
+          mint: token.mint,
+          to: token.ata,
+          mintAuthorityPda,
+          idempotencyLog: null,
+          protocolStats,
+          authority: authority.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          role: null,
+          allowance: null,
+        })
+        .rpc();
+      await expectWithinBudget(minted, { mintTokens: {} }, 0);
+
+      const burned = await program.methods
+        .burnTokens(new anchor.BN(10))
+        .accountsPartial({
+          programConfig,
+          tokenData: token.tokenData,
+          mint: token.mint,
+          from: token.ata,
+          authority: authority.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+      await expectWithinBudget(burned, { burnTokens: {} }, 0);
+    });
+
+    it("covers a hooked transfer", async () => {
+      const owner = Keypair.generate().publicKey;
+      const account = await openAccount(token, owner);
+      await whitelist(token, owner);
+      await expectWithinBudget(
+        await transfer(token, account, 1),
+        { transfer: {} },
+        0
+      );
+    });
+
+    it("covers whitelist edits per address", async () => {
+      const addresses = pageAddresses(12);
+      const edit = (method: "addToWhitelist" | "removeFromWhitelist") =>
+        program.methods[method](token.defaultPage, addresses)
+          .accountsPartial({
+            programConfig,
+            tokenData: token.tokenData,
+            whitelist: pageOf(),
+            authority: authority.publicKey,
+            role: null,
+          })
+          .rpc();
+
+      await expectWithinBudget(
+        await edit("addToWhitelist"),
+        { addToWhitelist: {} },
+        addresses.length
+      );
+      await expectWithinBudget(
+        await edit("removeFromWhitelist"),
+        { removeFromWhitelist: {} },
+        addresses.length
+      );
+    });
+
+    it("covers a batch mint per recipient", async () => {
+      for (const count of [1, 4]) {
+        const owners = Array.from(
+          { length: count },
+          () => Keypair.generate().publicKey
+        );
+        const accounts: PublicKey[] = [];
+        for (const owner of owners) {
+          accounts.push(await openAccount(token, owner));
+        }
+        await expectWithinBudget(
+          await mintToMany(accounts, owners),
+          { mintTokensToMany: {} },
+          count
+        );
+      }
+    });
+
+    it("covers a whitelist import per address", async () => {
+      const addresses = pageAddresses(20);
+      const accounts = {
+        programConfig,
+        tokenData: token.tokenData,
+        import: pda(
+          Buffer.from("whitelist_import"),
+          token.mint.toBuffer(),
+          Buffer.from([token.defaultPage])
+        ),
         authority: authority.publicKey,
-      })
-      .rpc();
+      };
+      await program.methods
+        .beginWhitelistImport(token.defaultPage)
+        .accountsPartial(accounts)
+        .rpc();
+
+      const appended = await program.methods
+        .appendWhitelistImport(token.defaultPage, 0, addresses)
+        .accountsPartial(accounts)
+        .rpc();
+      await expectWithinBudget(
+        appended,
+        { importWhitelist: {} },
+        addresses.length
+      );
+
+      const committed = await program.methods
+        .commitWhitelistImport(token.defaultPage)
+        .accountsPartial({ ...accounts, whitelist: pageOf() })
+        .rpc();
+      await expectWithinBudget(
+        committed,
+        { importWhitelist: {} },
+        addresses.length
+      );
+    });
+  });
+
+  describe("closing", () => {
+    let source: Token;
+    let mirror: Token;
+
+    const pageOf = (token: Token) =>
+      pda(
+        Buffer.from("whitelist"),
+        token.mint.toBuffer(),
+        Buffer.from([token.defaultPage])
+      );
+
+    const burnAll = async (token: Token) => {
+      const tokenData = await program.account.tokenData.fetch(token.tokenData);
+      await program.methods
+        .burnTokens(tokenData.totalSupply)
+        .accountsPartial({
+          programConfig,
+          tokenData: token.tokenData,
+          mint: token.mint,
+          from: token.ata,
+          authority: authority.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+    };
+
+    const closeToken = (token: Token, whitelistSource: Token | null) =>
+      program.methods
+        .closeToken(Buffer.from([token.defaultPage]))
+        .accountsPartial({
+          programConfig,
+          tokenData: token.tokenData,
+          mint: token.mint,
+          whitelistSourceTokenData: whitelistSource?.tokenData ?? null,
+          extraAccountMetaList: pda(
+            Buffer.from("extra-account-metas"),
+            token.mint.toBuffer()
+          ),
+          treasuryAuthority: pda(
+            Buffer.from("treasury_authority"),
+            token.mint.toBuffer()
+          ),
+          treasury: pda(Buffer.from("treasury"), token.mint.toBuffer()),
+          mintAuthorityPda,
+          authority: authority.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .remainingAccounts([
+          { pubkey: pageOf(token), isSigner: false, isWritable: true },
+        ])
+        .rpc();
+
+    const exists = async (address: PublicKey) =>
+      (await provider.connection.getAccountInfo(address, "confirmed")) !==
+      null;
+
+    before(async () => {
+      source = await createToken(10, { closable: true });
+      mirror = await createToken(10);
+    });
+
+    it("refuses to close a token with supply left", async () => {
+      await expectError(closeToken(source, null), "SupplyNotZero");
+    });
+
+    it("refuses to close a token whose whitelist is mirrored", async () => {
+      await program.methods
+        .mirrorWhitelist()
+        .accountsPartial({
+          programConfig,
+          tokenData: mirror.tokenData,
+          sourceTokenData: source.tokenData,
+          previousSourceTokenData: null,
+          hookManifest: null,
+          authority: authority.publicKey,
+        })
+        .rpc();
+      assert.equal(
+        (await program.account.tokenData.fetch(source.tokenData))
+          .whitelistMirrors,
+        1
+      );
+
+      await burnAll(source);
+      await expectError(closeToken(source, null), "WhitelistMirrored");
+    });
+
+    it("releases the mirrored token when the mirror closes", async () => {
+      await burnAll(mirror);
+      await closeToken(mirror, source);
+
+      assert.isFalse(await exists(mirror.tokenData));
+      assert.isFalse(await exists(pageOf(mirror)));
+      // Only mints created closable go with the token
+      assert.isTrue(await exists(mirror.mint));
+      assert.equal(
+        (await program.account.tokenData.fetch(source.tokenData))
+          .whitelistMirrors,
+        0
+      );
+    });
+
+    it("closes a closable token's mint along with it", async () => {
+      await closeToken(source, null);
 
-    tokenDataAccount = await program.account.tokenData.fetch(tokenData);
-    assert.isFalse(tokenDataAccount.isMintingPaused);
+      assert.isFalse(await exists(source.tokenData));
+      assert.isFalse(await exists(pageOf(source)));
+      assert.isFalse(
+        await exists(pda(Buffer.from("treasury"), source.mint.toBuffer()))
+      );
+      assert.isFalse(await exists(source.mint));
+    });
   });
-});
\ No newline at end of file
+});